@@ -1,17 +1,158 @@
+//! Lexical types of attribute values in schema documents.
+//!
+//! Each type checks the lexical rules of the corresponding XSD built-in
+//! type when it is created, either through its `new` constructor or while
+//! reading a schema document. All of them dereference to `str`.
+use std::{fmt, ops::Deref, str::FromStr};
+
+use serde::Deserialize;
+
+/// Implements the conversions shared by all lexical types.
+macro_rules! impl_lexical {
+    ($($name:ident),* $(,)?) => {$(
+        impl $name {
+            /// Returns the value as a string slice.
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Deref for $name {
+            type Target = str;
+
+            fn deref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = String;
+
+            fn from_str(value: &str) -> Result<Self, String> {
+                $name::new(value)
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = String;
+
+            fn try_from(value: String) -> Result<Self, String> {
+                $name::new(&value)
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    )*};
+}
+
+impl_lexical!(AnyURI, ID, NCName, QName, Token);
+
+/// Collapses whitespace as described for [Token].
+fn collapse(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The `NameStartChar` production of XML 1.0, without `:`.
+fn is_name_start_char(c: char) -> bool {
+    matches!(
+        c,
+        'A'..='Z'
+            | '_'
+            | 'a'..='z'
+            | '\u{c0}'..='\u{d6}'
+            | '\u{d8}'..='\u{f6}'
+            | '\u{f8}'..='\u{2ff}'
+            | '\u{370}'..='\u{37d}'
+            | '\u{37f}'..='\u{1fff}'
+            | '\u{200c}'..='\u{200d}'
+            | '\u{2070}'..='\u{218f}'
+            | '\u{2c00}'..='\u{2fef}'
+            | '\u{3001}'..='\u{d7ff}'
+            | '\u{f900}'..='\u{fdcf}'
+            | '\u{fdf0}'..='\u{fffd}'
+            | '\u{10000}'..='\u{effff}'
+    )
+}
+
+/// The `NameChar` production of XML 1.0, without `:`.
+fn is_name_char(c: char) -> bool {
+    is_name_start_char(c)
+        || matches!(
+            c,
+            '-'
+                | '.'
+                | '0'..='9'
+                | '\u{b7}'
+                | '\u{300}'..='\u{36f}'
+                | '\u{203f}'..='\u{2040}'
+        )
+}
+
+/// Checks that a value is a valid [NCName].
+fn check_ncname(value: &str) -> Result<(), String> {
+    let mut chars = value.chars();
+    match chars.next() {
+        None => Err("a name must not be empty".to_string()),
+        Some(c) if !is_name_start_char(c) => Err(format!(
+            "`{value}` is not a valid name: it must start with a letter or `_`"
+        )),
+        _ => match chars.find(|c| !is_name_char(*c)) {
+            Some(c) => Err(format!(
+                "`{value}` is not a valid name: `{c}` is not allowed in names"
+            )),
+            None => Ok(()),
+        },
+    }
+}
+
 /// Represents a string value that conforms to the anyURI data type in XSD.
 /// The `anyURI` data type is a built-in XSD type used to specify a Uniform
 /// Resource Identifier (URI). URIs can be used to reference various kinds of
 /// resources, including web pages, files, images, and more.
-pub type AnyURI = String;
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String")]
+pub struct AnyURI(String);
+
+impl AnyURI {
+    /// Creates a URI, collapsing whitespace.
+    ///
+    /// Any string is accepted, as URIs are only checked when they are
+    /// dereferenced.
+    pub fn new(value: &str) -> Result<Self, String> {
+        Ok(AnyURI(collapse(value)))
+    }
+}
 
 /// Represents a string value conforming to the ID data type in XSD. The ID
 /// data type is used for unique identifiers within an XML document based on
 /// an XML Schema (XSD) definition.
 ///
-/// An ID value must:
+/// An ID value must be a valid [NCName]:
 ///  - Start with a letter or underscore (_).
-///  - Contain letters, digits, underscores, hyphens (-), periods (.), or
-///    colons (:) following the first character.
+///  - Contain letters, digits, underscores, hyphens (-), or periods (.)
+///    following the first character.
 ///
 /// ID values are required to be unique within the scope of the document
 /// referencing the XSD. This ensures that each element or attribute with
@@ -20,7 +161,18 @@ pub type AnyURI = String;
 /// This type is typically used within XSD to define attributes or elements
 /// that act as unique identifiers within the schema itself or within the
 /// XML documents that conform to the schema.
-pub type ID = String;
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String")]
+pub struct ID(String);
+
+impl ID {
+    /// Creates an ID after checking that it is a valid [NCName].
+    pub fn new(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        check_ncname(value)?;
+        Ok(ID(value.to_string()))
+    }
+}
 
 /// Represents a string value conforming to the NCName data type in XSD.
 /// NCName (Name without Colons) is a built-in XSD type used for XML names
@@ -32,19 +184,63 @@ pub type ID = String;
 ///
 /// This type is typically used within XSD to define valid names for
 /// elements, attributes, and other constructs within the schema itself.
-pub type NCName = String;
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String")]
+pub struct NCName(String);
+
+impl NCName {
+    /// Creates a name after checking the rules above, surrounding
+    /// whitespace is removed.
+    pub fn new(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        check_ncname(value)?;
+        Ok(NCName(value.to_string()))
+    }
+}
 
 /// Represents a qualified name as defined in XML Schemas (XSD).
 ///
-/// A qualified name consists of two parts:
+/// A qualified name consists of two parts, separated by a colon:
 ///  - Prefix: An optional prefix that identifies a namespace.
 ///  - Local name: The name of the element, attribute, type, etc. within
 ///    that namespace.
 ///
+/// Both parts must be valid [NCName]s. Use the `resolve_qname` method of
+/// the component the name appears in to find the namespace of the name.
+///
 /// This type is typically used within XSD to represent references to elements,
 /// attributes, complex types, simple types, and other constructs defined
 /// within the schema or imported from other schemas.
-pub type QName = String;
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String")]
+pub struct QName(String);
+
+impl QName {
+    /// Creates a qualified name after checking that the prefix (if any)
+    /// and the local name are valid [NCName]s.
+    pub fn new(value: &str) -> Result<Self, String> {
+        let value = value.trim();
+        if let Some((prefix, local_name)) = value.split_once(':') {
+            check_ncname(prefix)?;
+            check_ncname(local_name)?;
+        } else {
+            check_ncname(value)?;
+        }
+        Ok(QName(value.to_string()))
+    }
+
+    /// Returns the prefix of the name, if it has one.
+    pub fn prefix(&self) -> Option<&str> {
+        self.0.split_once(':').map(|(prefix, _)| prefix)
+    }
+
+    /// Returns the local part of the name.
+    pub fn local_name(&self) -> &str {
+        self.0
+            .split_once(':')
+            .map_or(self.0.as_str(), |(_, local_name)| local_name)
+    }
+}
 
 /// Represents a string value conforming to the `xsd:token` data type in XSD.
 ///
@@ -68,4 +264,13 @@ pub type QName = String;
 /// Be aware that the name `token` can be misleading, as it might imply
 /// a single character or a short string. In reality, `token` can contain
 /// various characters after whitespace processing.
-pub type Token = String;
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String")]
+pub struct Token(String);
+
+impl Token {
+    /// Creates a token, collapsing whitespace as described above.
+    pub fn new(value: &str) -> Result<Self, String> {
+        Ok(Token(collapse(value)))
+    }
+}
@@ -0,0 +1,490 @@
+//! The built-in types of XML Schema 1.0 and 1.1.
+//!
+//! The built-in types are defined by the XML Schema specification itself
+//! rather than by a schema document. [BUILTIN_TYPES] describes all of them:
+//! `xs:anyType`, `xs:anySimpleType`, the primitive types such as
+//! `xs:string` and `xs:decimal` and the types derived from them such as
+//! `xs:int`, with their fundamental facets and the constraining facets
+//! they are derived with.
+//!
+//! The hierarchy is the one of XML Schema 1.1, in which the primitive types
+//! are derived from `xs:anyAtomicType`. Types that were added in XML Schema
+//! 1.1 are marked by [BuiltinType::xsd11].
+use crate::facets::FacetKind;
+
+/// The `ordered` fundamental facet of a simple type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ordered {
+    /// The values are not ordered.
+    False,
+    /// Some values are not comparable, e.g. durations such as `P1M` and
+    /// `P30D`.
+    Partial,
+    /// All values are comparable.
+    Total,
+}
+
+/// The `cardinality` fundamental facet of a simple type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cardinality {
+    Finite,
+    CountablyInfinite,
+}
+
+/// The variety of a simple type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variety {
+    Atomic,
+    List,
+    Union,
+}
+
+/// A constraining facet a built-in type is derived with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuiltinFacet {
+    /// The kind of the facet.
+    pub kind: FacetKind,
+    /// The value of the facet, as it would be written in a schema.
+    pub value: &'static str,
+    /// Whether derived types may not change the value.
+    pub fixed: bool,
+}
+
+/// A type defined by the XML Schema specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BuiltinType {
+    /// The local name of the type in the XML Schema namespace.
+    pub name: &'static str,
+    /// The local name of the base type, `None` for `xs:anyType`.
+    pub base: Option<&'static str>,
+    /// The variety of a simple type, `None` for `xs:anyType`,
+    /// `xs:anySimpleType` and `xs:anyAtomicType`.
+    pub variety: Option<Variety>,
+    /// The local name of the item type of a list type.
+    pub item_type: Option<&'static str>,
+    /// The `ordered` fundamental facet.
+    pub ordered: Ordered,
+    /// The `bounded` fundamental facet.
+    pub bounded: bool,
+    /// The `cardinality` fundamental facet.
+    pub cardinality: Cardinality,
+    /// The `numeric` fundamental facet.
+    pub numeric: bool,
+    /// The constraining facets the type adds to those of its base type.
+    pub facets: &'static [BuiltinFacet],
+    /// Whether the type was added in XML Schema 1.1.
+    pub xsd11: bool,
+}
+
+impl BuiltinType {
+    /// Returns whether the type is `xs:anyType`, the only complex built-in
+    /// type.
+    pub fn is_complex(&self) -> bool {
+        self.base.is_none()
+    }
+
+    /// Returns whether the type is one of the primitive types, from which
+    /// all other atomic types are derived.
+    pub fn is_primitive(&self) -> bool {
+        self.base == Some("anyAtomicType")
+    }
+
+    /// Returns the built-in base type.
+    pub fn base_type(&self) -> Option<&'static BuiltinType> {
+        builtin_type(self.base?)
+    }
+
+    /// Returns the primitive type an atomic type is derived from, which is
+    /// the type itself for a primitive type.
+    pub fn primitive_type(&self) -> Option<&'static BuiltinType> {
+        let mut current = builtin_type(self.name)?;
+        while !current.is_primitive() {
+            current = current.base_type()?;
+        }
+        Some(current)
+    }
+
+    /// Returns the constraining facets that may restrict the type, which
+    /// depend on its variety and, for an atomic type, on its primitive
+    /// type. None apply to `xs:anyType`, `xs:anySimpleType` and
+    /// `xs:anyAtomicType`.
+    pub fn applicable_facets(&self) -> &'static [FacetKind] {
+        match self.variety {
+            Some(Variety::List) => LIST_FACETS,
+            Some(Variety::Union) => UNION_FACETS,
+            Some(Variety::Atomic) => match self.primitive_type().map(|primitive| primitive.name) {
+                Some("boolean") => BOOLEAN_FACETS,
+                Some("float" | "double" | "duration") => ORDERED_FACETS,
+                Some("decimal") => DECIMAL_FACETS,
+                Some(
+                    "dateTime" | "time" | "date" | "gYearMonth" | "gYear" | "gMonthDay" | "gDay"
+                    | "gMonth",
+                ) => DATE_TIME_FACETS,
+                _ => LENGTH_FACETS,
+            },
+            None => &[],
+        }
+    }
+
+    /// Looks up a constraining facet of the type, including those it
+    /// inherits from its base types.
+    pub fn facet(&self, kind: FacetKind) -> Option<&'static BuiltinFacet> {
+        let mut current = builtin_type(self.name)?;
+        loop {
+            if let Some(facet) = current.facets.iter().find(|facet| facet.kind == kind) {
+                return Some(facet);
+            }
+            current = current.base_type()?;
+        }
+    }
+}
+
+/// Looks up a built-in type by its local name, e.g. `int` for `xs:int`.
+pub fn builtin_type(name: &str) -> Option<&'static BuiltinType> {
+    BUILTIN_TYPES.iter().find(|builtin| builtin.name == name)
+}
+
+/// The facets that apply to lists and to the primitive types whose values
+/// have a length, such as `xs:string` and `xs:hexBinary`.
+pub(crate) const LIST_FACETS: &[FacetKind] = LENGTH_FACETS;
+
+/// The facets that apply to unions.
+pub(crate) const UNION_FACETS: &[FacetKind] = &[
+    FacetKind::Pattern,
+    FacetKind::Enumeration,
+    FacetKind::Assertion,
+];
+
+const LENGTH_FACETS: &[FacetKind] = &[
+    FacetKind::Length,
+    FacetKind::MinLength,
+    FacetKind::MaxLength,
+    FacetKind::Pattern,
+    FacetKind::Enumeration,
+    FacetKind::WhiteSpace,
+    FacetKind::Assertion,
+];
+
+const BOOLEAN_FACETS: &[FacetKind] = &[
+    FacetKind::Pattern,
+    FacetKind::WhiteSpace,
+    FacetKind::Assertion,
+];
+
+const ORDERED_FACETS: &[FacetKind] = &[
+    FacetKind::Pattern,
+    FacetKind::Enumeration,
+    FacetKind::WhiteSpace,
+    FacetKind::MaxInclusive,
+    FacetKind::MaxExclusive,
+    FacetKind::MinInclusive,
+    FacetKind::MinExclusive,
+    FacetKind::Assertion,
+];
+
+const DECIMAL_FACETS: &[FacetKind] = &[
+    FacetKind::Pattern,
+    FacetKind::Enumeration,
+    FacetKind::WhiteSpace,
+    FacetKind::MaxInclusive,
+    FacetKind::MaxExclusive,
+    FacetKind::MinInclusive,
+    FacetKind::MinExclusive,
+    FacetKind::TotalDigits,
+    FacetKind::FractionDigits,
+    FacetKind::Assertion,
+];
+
+const DATE_TIME_FACETS: &[FacetKind] = &[
+    FacetKind::Pattern,
+    FacetKind::Enumeration,
+    FacetKind::WhiteSpace,
+    FacetKind::MaxInclusive,
+    FacetKind::MaxExclusive,
+    FacetKind::MinInclusive,
+    FacetKind::MinExclusive,
+    FacetKind::Assertion,
+    FacetKind::ExplicitTimezone,
+];
+
+const fn facet(kind: FacetKind, value: &'static str, fixed: bool) -> BuiltinFacet {
+    BuiltinFacet { kind, value, fixed }
+}
+
+const COLLAPSE: BuiltinFacet = facet(FacetKind::WhiteSpace, "collapse", true);
+
+/// Defines a built-in type.
+const fn builtin(
+    name: &'static str,
+    base: &'static str,
+    variety: Option<Variety>,
+    (ordered, bounded, cardinality, numeric): (Ordered, bool, Cardinality, bool),
+    facets: &'static [BuiltinFacet],
+) -> BuiltinType {
+    BuiltinType {
+        name,
+        base: Some(base),
+        variety,
+        item_type: None,
+        ordered,
+        bounded,
+        cardinality,
+        numeric,
+        facets,
+        xsd11: false,
+    }
+}
+
+/// Defines an atomic built-in type.
+const fn atomic(
+    name: &'static str,
+    base: &'static str,
+    fundamental: (Ordered, bool, Cardinality, bool),
+    facets: &'static [BuiltinFacet],
+) -> BuiltinType {
+    builtin(name, base, Some(Variety::Atomic), fundamental, facets)
+}
+
+/// Defines a built-in list type.
+const fn list(name: &'static str, item_type: &'static str) -> BuiltinType {
+    const MIN_LENGTH: BuiltinFacet = facet(FacetKind::MinLength, "1", false);
+    BuiltinType {
+        item_type: Some(item_type),
+        ..builtin(
+            name,
+            "anySimpleType",
+            Some(Variety::List),
+            UNORDERED,
+            &[COLLAPSE, MIN_LENGTH],
+        )
+    }
+}
+
+/// Marks a type as added in XML Schema 1.1.
+const fn xsd11(builtin: BuiltinType) -> BuiltinType {
+    BuiltinType {
+        xsd11: true,
+        ..builtin
+    }
+}
+
+const UNORDERED: (Ordered, bool, Cardinality, bool) =
+    (Ordered::False, false, Cardinality::CountablyInfinite, false);
+const PARTIAL: (Ordered, bool, Cardinality, bool) = (
+    Ordered::Partial,
+    false,
+    Cardinality::CountablyInfinite,
+    false,
+);
+const FLOATING: (Ordered, bool, Cardinality, bool) =
+    (Ordered::Partial, true, Cardinality::Finite, true);
+const UNBOUNDED_NUMBER: (Ordered, bool, Cardinality, bool) =
+    (Ordered::Total, false, Cardinality::CountablyInfinite, true);
+const BOUNDED_NUMBER: (Ordered, bool, Cardinality, bool) =
+    (Ordered::Total, true, Cardinality::Finite, true);
+
+const fn range(min: &'static str, max: &'static str) -> [BuiltinFacet; 2] {
+    [
+        facet(FacetKind::MinInclusive, min, false),
+        facet(FacetKind::MaxInclusive, max, false),
+    ]
+}
+
+/// All built-in types, each one after its base type.
+pub const BUILTIN_TYPES: &[BuiltinType] = &[
+    BuiltinType {
+        name: "anyType",
+        base: None,
+        variety: None,
+        item_type: None,
+        ordered: Ordered::False,
+        bounded: false,
+        cardinality: Cardinality::CountablyInfinite,
+        numeric: false,
+        facets: &[],
+        xsd11: false,
+    },
+    builtin("anySimpleType", "anyType", None, UNORDERED, &[]),
+    xsd11(builtin(
+        "anyAtomicType",
+        "anySimpleType",
+        None,
+        UNORDERED,
+        &[],
+    )),
+    // Primitive types
+    atomic(
+        "string",
+        "anyAtomicType",
+        UNORDERED,
+        &[facet(FacetKind::WhiteSpace, "preserve", false)],
+    ),
+    atomic(
+        "boolean",
+        "anyAtomicType",
+        (Ordered::False, false, Cardinality::Finite, false),
+        &[COLLAPSE],
+    ),
+    atomic("decimal", "anyAtomicType", UNBOUNDED_NUMBER, &[COLLAPSE]),
+    atomic("float", "anyAtomicType", FLOATING, &[COLLAPSE]),
+    atomic("double", "anyAtomicType", FLOATING, &[COLLAPSE]),
+    atomic("duration", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("dateTime", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("time", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("date", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("gYearMonth", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("gYear", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("gMonthDay", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("gDay", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("gMonth", "anyAtomicType", PARTIAL, &[COLLAPSE]),
+    atomic("hexBinary", "anyAtomicType", UNORDERED, &[COLLAPSE]),
+    atomic("base64Binary", "anyAtomicType", UNORDERED, &[COLLAPSE]),
+    atomic("anyURI", "anyAtomicType", UNORDERED, &[COLLAPSE]),
+    atomic("QName", "anyAtomicType", UNORDERED, &[COLLAPSE]),
+    atomic("NOTATION", "anyAtomicType", UNORDERED, &[COLLAPSE]),
+    // Types derived from string
+    atomic(
+        "normalizedString",
+        "string",
+        UNORDERED,
+        &[facet(FacetKind::WhiteSpace, "replace", false)],
+    ),
+    atomic(
+        "token",
+        "normalizedString",
+        UNORDERED,
+        &[facet(FacetKind::WhiteSpace, "collapse", false)],
+    ),
+    atomic(
+        "language",
+        "token",
+        UNORDERED,
+        &[facet(
+            FacetKind::Pattern,
+            "[a-zA-Z]{1,8}(-[a-zA-Z0-9]{1,8})*",
+            false,
+        )],
+    ),
+    atomic(
+        "NMTOKEN",
+        "token",
+        UNORDERED,
+        &[facet(FacetKind::Pattern, "\\c+", false)],
+    ),
+    list("NMTOKENS", "NMTOKEN"),
+    atomic(
+        "Name",
+        "token",
+        UNORDERED,
+        &[facet(FacetKind::Pattern, "\\i\\c*", false)],
+    ),
+    atomic(
+        "NCName",
+        "Name",
+        UNORDERED,
+        &[facet(FacetKind::Pattern, "[\\i-[:]][\\c-[:]]*", false)],
+    ),
+    atomic("ID", "NCName", UNORDERED, &[]),
+    atomic("IDREF", "NCName", UNORDERED, &[]),
+    list("IDREFS", "IDREF"),
+    atomic("ENTITY", "NCName", UNORDERED, &[]),
+    list("ENTITIES", "ENTITY"),
+    // Types derived from decimal
+    atomic(
+        "integer",
+        "decimal",
+        UNBOUNDED_NUMBER,
+        &[
+            facet(FacetKind::FractionDigits, "0", true),
+            facet(FacetKind::Pattern, "[\\-+]?[0-9]+", false),
+        ],
+    ),
+    atomic(
+        "nonPositiveInteger",
+        "integer",
+        UNBOUNDED_NUMBER,
+        &[facet(FacetKind::MaxInclusive, "0", false)],
+    ),
+    atomic(
+        "negativeInteger",
+        "nonPositiveInteger",
+        UNBOUNDED_NUMBER,
+        &[facet(FacetKind::MaxInclusive, "-1", false)],
+    ),
+    atomic(
+        "long",
+        "integer",
+        BOUNDED_NUMBER,
+        &range("-9223372036854775808", "9223372036854775807"),
+    ),
+    atomic(
+        "int",
+        "long",
+        BOUNDED_NUMBER,
+        &range("-2147483648", "2147483647"),
+    ),
+    atomic("short", "int", BOUNDED_NUMBER, &range("-32768", "32767")),
+    atomic("byte", "short", BOUNDED_NUMBER, &range("-128", "127")),
+    atomic(
+        "nonNegativeInteger",
+        "integer",
+        UNBOUNDED_NUMBER,
+        &[facet(FacetKind::MinInclusive, "0", false)],
+    ),
+    atomic(
+        "unsignedLong",
+        "nonNegativeInteger",
+        BOUNDED_NUMBER,
+        &range("0", "18446744073709551615"),
+    ),
+    atomic(
+        "unsignedInt",
+        "unsignedLong",
+        BOUNDED_NUMBER,
+        &range("0", "4294967295"),
+    ),
+    atomic(
+        "unsignedShort",
+        "unsignedInt",
+        BOUNDED_NUMBER,
+        &range("0", "65535"),
+    ),
+    atomic(
+        "unsignedByte",
+        "unsignedShort",
+        BOUNDED_NUMBER,
+        &range("0", "255"),
+    ),
+    atomic(
+        "positiveInteger",
+        "nonNegativeInteger",
+        UNBOUNDED_NUMBER,
+        &[facet(FacetKind::MinInclusive, "1", false)],
+    ),
+    // Types added in XML Schema 1.1
+    xsd11(atomic(
+        "dateTimeStamp",
+        "dateTime",
+        PARTIAL,
+        &[facet(FacetKind::ExplicitTimezone, "required", true)],
+    )),
+    xsd11(atomic(
+        "dayTimeDuration",
+        "duration",
+        PARTIAL,
+        &[facet(FacetKind::Pattern, "[^YM]*(T.*)?", false)],
+    )),
+    xsd11(atomic(
+        "yearMonthDuration",
+        "duration",
+        PARTIAL,
+        &[facet(FacetKind::Pattern, "[^DT]*", false)],
+    )),
+    xsd11(builtin(
+        "error",
+        "anySimpleType",
+        Some(Variety::Union),
+        (Ordered::False, false, Cardinality::Finite, false),
+        &[],
+    )),
+];
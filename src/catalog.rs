@@ -0,0 +1,335 @@
+//! OASIS XML Catalogs, for reading well-known schemas from local copies.
+//!
+//! Schemas often import other schemas from `http://` locations, e.g.
+//! `http://www.w3.org/2001/xml.xsd`. A [Catalog] maps such locations, and
+//! the namespaces of imports, to local files, and a [CatalogResolver] uses
+//! a catalog before handing locations to another
+//! [SchemaResolver](crate::resolver::SchemaResolver).
+//!
+//! The following catalog entries are supported: `uri`, `rewriteURI`,
+//! `uriSuffix`, `system`, `rewriteSystem` and `nextCatalog`, as well as
+//! `group` elements and `xml:base` attributes. Other entries, and elements
+//! from other namespaces, are ignored.
+use std::{io, path::Path, sync::Arc};
+
+use serde::Deserialize;
+
+use crate::{
+    de::Document,
+    error::SchemaError,
+    fragment::{XmlElement, XmlFragment},
+    namespaces::XML_NAMESPACE,
+    resolver::{read, resolve_uri, unresolved, FileResolver, SchemaResolver, SchemaSource},
+};
+
+/// The namespace of OASIS XML Catalog documents.
+pub const CATALOG_NAMESPACE: &str = "urn:oasis:names:tc:entity:xmlns:xml:catalog";
+
+/// Root element of a catalog document.
+#[derive(Deserialize)]
+struct CatalogElement {
+    #[serde(rename = "@base")]
+    base: Option<String>,
+    #[serde(rename = "#content")]
+    content: XmlFragment,
+}
+
+/// An entry of a catalog, with URIs resolved against the base URI in
+/// effect for the entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Entry {
+    Uri { name: String, uri: String },
+    RewriteUri { start: String, prefix: String },
+    UriSuffix { suffix: String, uri: String },
+    System { id: String, uri: String },
+    RewriteSystem { start: String, prefix: String },
+}
+
+/// An OASIS XML Catalog, together with the catalogs it delegates to through
+/// `nextCatalog` entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Catalog {
+    uri: String,
+    entries: Vec<Entry>,
+    next: Vec<Catalog>,
+}
+
+impl Catalog {
+    /// Reads a catalog file, and the catalogs it refers to.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [SchemaError] if a catalog cannot be read or is not
+    /// well-formed XML.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, SchemaError> {
+        Catalog::load(&path.as_ref().to_string_lossy(), &FileResolver)
+    }
+
+    /// Reads the catalog at `location`, and the catalogs it refers to,
+    /// through a resolver.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [SchemaError] if a catalog cannot be located or read, or
+    /// is not well-formed XML.
+    pub fn load(location: &str, resolver: &impl SchemaResolver) -> Result<Self, SchemaError> {
+        let source = resolver
+            .resolve(None, location)
+            .map_err(|error| unresolved(None, location, error))?;
+        Catalog::read(source, None, location, resolver, &mut vec![])
+    }
+
+    /// Reads a located catalog, `loaded` holds the URIs of the catalogs
+    /// read so far.
+    fn read(
+        source: SchemaSource,
+        base: Option<&str>,
+        location: &str,
+        resolver: &impl SchemaResolver,
+        loaded: &mut Vec<String>,
+    ) -> Result<Self, SchemaError> {
+        let uri = source.uri.clone();
+        let content = read(source, base, location)?;
+        loaded.push(uri.clone());
+
+        let root: CatalogElement = Document::parse(&content, Some(Arc::from(uri.as_str())))
+            .and_then(|document| document.deserialize())
+            .map_err(|error| SchemaError::InDocument {
+                uri: uri.clone(),
+                error: Box::new(error),
+            })?;
+        let base = resolve_uri(Some(&uri), root.base.as_deref().unwrap_or(""));
+        let mut catalog = Catalog {
+            uri: uri.clone(),
+            entries: vec![],
+            next: vec![],
+        };
+        let mut next = vec![];
+        catalog.read_entries(root.content.elements(), &base, &mut next);
+        // The locations are already resolved against the base URI.
+        for location in next {
+            let source = resolver
+                .resolve(None, &location)
+                .map_err(|error| unresolved(Some(&uri), &location, error))?;
+            // A catalog that was already read has been consulted before.
+            if !loaded.contains(&source.uri) {
+                let next = Catalog::read(source, Some(&uri), &location, resolver, loaded)?;
+                catalog.next.push(next);
+            }
+        }
+        Ok(catalog)
+    }
+
+    /// Reads the entries of a catalog or group element, collecting the
+    /// locations of `nextCatalog` entries in `next`.
+    fn read_entries<'a>(
+        &mut self,
+        elements: impl Iterator<Item = &'a XmlElement>,
+        base: &str,
+        next: &mut Vec<String>,
+    ) {
+        for element in elements {
+            if element.namespace() != Some(CATALOG_NAMESPACE) {
+                continue;
+            }
+            let base = match xml_base(element) {
+                Some(value) => resolve_uri(Some(base), value),
+                None => base.to_string(),
+            };
+            let attribute = |name| element.attribute(name).map(str::to_string);
+            let target = |name| {
+                element
+                    .attribute(name)
+                    .map(|uri| resolve_uri(Some(&base), uri))
+            };
+            let entry = match element.local_name() {
+                "group" => {
+                    self.read_entries(element.elements(), &base, next);
+                    None
+                }
+                "nextCatalog" => {
+                    next.extend(target("catalog"));
+                    None
+                }
+                "uri" => attribute("name")
+                    .zip(target("uri"))
+                    .map(|(name, uri)| Entry::Uri { name, uri }),
+                "rewriteURI" => attribute("uriStartString")
+                    .zip(target("rewritePrefix"))
+                    .map(|(start, prefix)| Entry::RewriteUri { start, prefix }),
+                "uriSuffix" => attribute("uriSuffix")
+                    .zip(target("uri"))
+                    .map(|(suffix, uri)| Entry::UriSuffix { suffix, uri }),
+                "system" => attribute("systemId")
+                    .zip(target("uri"))
+                    .map(|(id, uri)| Entry::System { id, uri }),
+                "rewriteSystem" => attribute("systemIdStartString")
+                    .zip(target("rewritePrefix"))
+                    .map(|(start, prefix)| Entry::RewriteSystem { start, prefix }),
+                _ => None,
+            };
+            self.entries.extend(entry);
+        }
+    }
+
+    /// Returns the URI the catalog was read from.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Looks up a URI, such as a namespace or a schema location, through
+    /// the `uri`, `rewriteURI` and `uriSuffix` entries.
+    ///
+    /// An exact match takes precedence over the longest matching
+    /// `rewriteURI` prefix, which takes precedence over the longest matching
+    /// `uriSuffix`. If the catalog has no match, the `nextCatalog`s are
+    /// consulted in order.
+    pub fn resolve_uri(&self, uri: &str) -> Option<String> {
+        let exact = self.entries.iter().find_map(|entry| match entry {
+            Entry::Uri { name, uri: target } if name == uri => Some(target.clone()),
+            _ => None,
+        });
+        let rewritten = || {
+            longest(self.entries.iter().filter_map(|entry| {
+                match entry {
+                    Entry::RewriteUri { start, prefix } => uri
+                        .strip_prefix(start.as_str())
+                        .map(|rest| (start.len(), format!("{prefix}{rest}"))),
+                    _ => None,
+                }
+            }))
+        };
+        let suffixed = || {
+            longest(self.entries.iter().filter_map(|entry| match entry {
+                Entry::UriSuffix {
+                    suffix,
+                    uri: target,
+                } if uri.ends_with(suffix.as_str()) => Some((suffix.len(), target.clone())),
+                _ => None,
+            }))
+        };
+        exact
+            .or_else(rewritten)
+            .or_else(suffixed)
+            .or_else(|| self.next.iter().find_map(|next| next.resolve_uri(uri)))
+    }
+
+    /// Looks up a system identifier, such as a schema location, through the
+    /// `system` and `rewriteSystem` entries.
+    ///
+    /// An exact match takes precedence over the longest matching
+    /// `rewriteSystem` prefix. If the catalog has no match, the
+    /// `nextCatalog`s are consulted in order.
+    pub fn resolve_system(&self, id: &str) -> Option<String> {
+        let exact = self.entries.iter().find_map(|entry| match entry {
+            Entry::System { id: system, uri } if system == id => Some(uri.clone()),
+            _ => None,
+        });
+        let rewritten = || {
+            longest(self.entries.iter().filter_map(|entry| {
+                match entry {
+                    Entry::RewriteSystem { start, prefix } => id
+                        .strip_prefix(start.as_str())
+                        .map(|rest| (start.len(), format!("{prefix}{rest}"))),
+                    _ => None,
+                }
+            }))
+        };
+        exact
+            .or_else(rewritten)
+            .or_else(|| self.next.iter().find_map(|next| next.resolve_system(id)))
+    }
+
+    /// Looks up a schema location, first as URI and then as system
+    /// identifier.
+    fn resolve_location(&self, location: &str) -> Option<String> {
+        self.resolve_uri(location)
+            .or_else(|| self.resolve_system(location))
+    }
+}
+
+/// Returns the value of the `xml:base` attribute of an element.
+fn xml_base(element: &XmlElement) -> Option<&str> {
+    element
+        .attributes()
+        .iter()
+        .find(|attribute| {
+            let name = attribute.name();
+            name.namespace() == Some(XML_NAMESPACE) && name.local_name() == "base"
+        })
+        .map(|attribute| attribute.value())
+}
+
+/// Returns the candidate with the longest match, the first one on ties.
+fn longest(candidates: impl Iterator<Item = (usize, String)>) -> Option<String> {
+    let mut best: Option<(usize, String)> = None;
+    for (length, uri) in candidates {
+        let better = match &best {
+            Some((best, _)) => length > *best,
+            None => true,
+        };
+        if better {
+            best = Some((length, uri));
+        }
+    }
+    best.map(|(_, uri)| uri)
+}
+
+/// A [SchemaResolver] that looks up schema locations in a [Catalog] before
+/// handing them to another resolver.
+///
+/// The namespace of an import is looked up first, then the schema location
+/// as given, and then the schema location resolved against the including
+/// document. Locations without a catalog entry are resolved as usual, and
+/// an import without `schemaLocation` whose namespace has no entry is
+/// handed to the other resolver.
+#[derive(Debug, Clone)]
+pub struct CatalogResolver<R = FileResolver> {
+    catalog: Catalog,
+    resolver: R,
+}
+
+impl<R: SchemaResolver> CatalogResolver<R> {
+    /// Creates a resolver that reads the documents found in `catalog`, and
+    /// all others, through `resolver`.
+    pub fn new(catalog: Catalog, resolver: R) -> Self {
+        CatalogResolver { catalog, resolver }
+    }
+
+    /// Returns the catalog of the resolver.
+    pub fn catalog(&self) -> &Catalog {
+        &self.catalog
+    }
+}
+
+impl<R: SchemaResolver> SchemaResolver for CatalogResolver<R> {
+    fn resolve(&self, base: Option<&str>, location: &str) -> io::Result<SchemaSource> {
+        let mapped = self.catalog.resolve_location(location).or_else(|| {
+            let absolute = resolve_uri(base, location);
+            if absolute == location {
+                None
+            } else {
+                self.catalog.resolve_location(&absolute)
+            }
+        });
+        match mapped {
+            Some(uri) => self.resolver.resolve(None, &uri),
+            None => self.resolver.resolve(base, location),
+        }
+    }
+
+    fn resolve_import(
+        &self,
+        base: Option<&str>,
+        namespace: Option<&str>,
+        location: Option<&str>,
+    ) -> io::Result<SchemaSource> {
+        match namespace.and_then(|namespace| self.catalog.resolve_uri(namespace)) {
+            Some(uri) => self.resolver.resolve(None, &uri),
+            None => match location {
+                Some(location) => self.resolve(base, location),
+                None => self.resolver.resolve_import(base, namespace, None),
+            },
+        }
+    }
+}
@@ -0,0 +1,1521 @@
+//! Checks of the constraints the XML Schema specification puts on schemas,
+//! see [Schema::check] and [SchemaSet::check].
+//!
+//! The representation constraints (`src-*`) and the constraints on
+//! occurrences are checked on each schema document on its own. The
+//! component constraints (`cos-*`, `*-props-correct` and
+//! `derivation-ok-restriction`) need the references between components to
+//! be resolved and are checked on the [ComponentModel] of a set, as are the
+//! facets of simple types derived by restriction, which must apply to the
+//! base type and must not loosen its facets.
+//!
+//! Constraints on values, such as the validity of a default value for the
+//! type of an element, are not checked. Bounds are compared for numbers and
+//! for dates and times, but not for durations, whose order is partial.
+use std::cmp::Ordering;
+
+use crate::{
+    builtins::{BuiltinType, Variety, LIST_FACETS, UNION_FACETS},
+    error::{SchemaError, Violation},
+    facets::{ExplicitTimezoneValue, Facet, FacetKind, WhiteSpaceValue},
+    model::{
+        AttributeGroupDefinition, ComponentModel, Compositor, ContentParticle, DerivationMethod,
+        ElementDeclaration, ModelGroupDefinition, Term, Type,
+    },
+    particles::{Element, IdentityConstraint, MaxOccurs, Particle},
+    span::Span,
+    Attribute, AttributeGroup, AttributeUse, ComplexType, Derivation, Import, Schema, SchemaSet,
+    SimpleType, SimpleTypeContent,
+};
+
+/// Checks the constraints on a schema document on its own.
+pub(crate) fn schema(schema: &Schema) -> Vec<Violation> {
+    let mut checker = Checker::default();
+    checker.schema(schema);
+    checker
+        .violations
+        .sort_by_key(|violation| violation.span.start);
+    checker.violations
+}
+
+/// Checks the constraints on every document of a set, followed by the
+/// constraints on the components of the set.
+pub(crate) fn set(set: &SchemaSet) -> Vec<Violation> {
+    let mut violations = vec![];
+    for document in set.documents() {
+        violations.extend(schema(document.schema()));
+    }
+    let mut checker = Checker::default();
+    match set.model() {
+        Ok(model) => checker.components(&model),
+        Err(SchemaError::UnresolvedReference { kind, name, span }) => {
+            checker.report("src-resolve", &span, format!("`{name}` is not a {kind}"));
+        }
+        Err(_) => {}
+    }
+    // The self-references of a redefinition without original refer to the
+    // redefinition itself, so its component constraints are not checked.
+    let unmatched = set.unmatched_redefinitions();
+    checker
+        .violations
+        .retain(|violation| unmatched.iter().all(|(_, span)| violation.span != **span));
+    for (component, span) in unmatched {
+        let kind = component.space().description();
+        let name = component.name().map_or("", |name| name.as_str());
+        checker.report(
+            "src-redefine.2",
+            span,
+            format!("the redefined document has no {kind} `{name}`"),
+        );
+    }
+    violations.extend(checker.violations);
+    violations
+}
+
+#[derive(Default)]
+struct Checker {
+    violations: Vec<Violation>,
+}
+
+impl Checker {
+    fn report(&mut self, rule: &'static str, span: &Span, message: impl Into<String>) {
+        self.violations.push(Violation {
+            rule,
+            span: span.clone(),
+            message: message.into(),
+        });
+    }
+
+    fn schema(&mut self, schema: &Schema) {
+        for e in schema.imports() {
+            self.import(schema, e);
+        }
+        for redefine in schema.redefines() {
+            redefine
+                .simple_types()
+                .into_iter()
+                .for_each(|e| self.simple_type(e));
+            redefine
+                .complex_types()
+                .into_iter()
+                .for_each(|e| self.complex_type(e));
+            for e in redefine.groups() {
+                self.group_definition(e.particle());
+            }
+            for e in redefine.attribute_groups() {
+                self.attribute_group(e);
+            }
+        }
+        for e in schema.overrides() {
+            e.simple_types()
+                .into_iter()
+                .for_each(|e| self.simple_type(e));
+            e.complex_types()
+                .into_iter()
+                .for_each(|e| self.complex_type(e));
+            for e in e.groups() {
+                self.group_definition(e.particle());
+            }
+            for e in e.attribute_groups() {
+                self.attribute_group(e);
+            }
+            for e in e.elements() {
+                self.element(e, true);
+            }
+            for e in e.attributes() {
+                self.attribute(e, true);
+            }
+        }
+        for e in schema.simple_types() {
+            self.simple_type(e);
+        }
+        for e in schema.complex_types() {
+            self.complex_type(e);
+        }
+        for e in schema.groups() {
+            self.group_definition(e.particle());
+        }
+        for e in schema.attribute_groups() {
+            self.attribute_group(e);
+        }
+        for e in schema.elements() {
+            self.element(e, true);
+        }
+        for e in schema.attributes() {
+            self.attribute(e, true);
+        }
+    }
+
+    fn import(&mut self, schema: &Schema, e: &Import) {
+        let target_namespace = schema.effective_target_namespace();
+        match &e.namespace {
+            Some(namespace) if Some(namespace.as_str()) == target_namespace => self.report(
+                "src-import.1.1",
+                e.span(),
+                format!("a schema must not import its own target namespace `{namespace}`"),
+            ),
+            None if target_namespace.is_none() => self.report(
+                "src-import.1.2",
+                e.span(),
+                "a schema without target namespace must not import names without namespace",
+            ),
+            _ => {}
+        }
+    }
+
+    fn simple_type(&mut self, e: &SimpleType) {
+        match e.content() {
+            Ok(SimpleTypeContent::Restriction(restriction)) => {
+                let inline = restriction.simple_type();
+                if restriction.base.is_some() == inline.is_some() {
+                    self.report(
+                        "src-simple-type.2",
+                        restriction.span(),
+                        "a restriction must have either a `base` or a `simpleType` child",
+                    );
+                }
+                inline.into_iter().for_each(|e| self.simple_type(e));
+            }
+            Ok(SimpleTypeContent::List(list)) => {
+                let inline = list.simple_types();
+                if list.item_type.is_some() != inline.is_empty() {
+                    self.report(
+                        "src-simple-type.3",
+                        list.span(),
+                        "a list must have either an `itemType` or a `simpleType` child",
+                    );
+                }
+                inline.into_iter().for_each(|e| self.simple_type(e));
+            }
+            Ok(SimpleTypeContent::Union(union)) => {
+                let inline = union.simple_types();
+                let member_types = union.member_types.iter().flatten();
+                if member_types.count() == 0 && inline.is_empty() {
+                    self.report(
+                        "src-simple-type.4",
+                        union.span(),
+                        "a union must have `memberTypes` or `simpleType` children",
+                    );
+                }
+                inline.into_iter().for_each(|e| self.simple_type(e));
+            }
+            Err(_) => {}
+        }
+    }
+
+    fn complex_type(&mut self, e: &ComplexType) {
+        let mut attributes = e.attributes();
+        let mut particle = e.particle();
+        let complex = e.complex_content().and_then(|content| content.derivation());
+        let simple = e.simple_content().and_then(|content| content.derivation());
+        match complex.or(simple) {
+            Some(Derivation::Restriction(restriction)) => {
+                attributes.extend(restriction.attributes());
+                particle = particle.or_else(|| restriction.particle());
+                if let Some(inline) = restriction.simple_type() {
+                    self.simple_type(inline);
+                }
+            }
+            Some(Derivation::Extension(extension)) => {
+                attributes.extend(extension.attributes());
+                particle = particle.or_else(|| extension.particle());
+            }
+            None => {}
+        }
+        if let Some(particle) = particle {
+            self.particle(particle);
+        }
+        for attribute in attributes {
+            self.attribute(attribute, false);
+        }
+    }
+
+    fn group_definition(&mut self, particle: Option<Particle>) {
+        if let Some(particle) = particle {
+            self.particle(particle);
+        }
+    }
+
+    fn attribute_group(&mut self, e: &AttributeGroup) {
+        for attribute in e.attributes() {
+            self.attribute(attribute, false);
+        }
+    }
+
+    fn particle(&mut self, particle: Particle) {
+        match particle {
+            Particle::Element(e) => self.element(e, false),
+            Particle::Group(e) => self.occurs(e.min_occurs, e.max_occurs.clone(), e.span()),
+            Particle::Sequence(e) => {
+                self.occurs(e.min_occurs, e.max_occurs.clone(), e.span());
+                for item in e.items() {
+                    self.particle(item);
+                }
+            }
+            Particle::Choice(e) => {
+                self.occurs(e.min_occurs, e.max_occurs.clone(), e.span());
+                for item in e.items() {
+                    self.particle(item);
+                }
+            }
+            Particle::All(e) => {
+                self.occurs(e.min_occurs, e.max_occurs.map(MaxOccurs::Bounded), e.span());
+                for item in e.items() {
+                    self.particle(item);
+                }
+            }
+            Particle::Any(e) => self.occurs(e.min_occurs, e.max_occurs.clone(), e.span()),
+        }
+    }
+
+    fn occurs(&mut self, min: Option<u32>, max: Option<MaxOccurs>, span: &Span) {
+        let min = min.unwrap_or(1);
+        match max.unwrap_or(MaxOccurs::Bounded(1)) {
+            MaxOccurs::Bounded(max) if min > max => self.report(
+                "p-props-correct.2.1",
+                span,
+                format!("`minOccurs` ({min}) must not be greater than `maxOccurs` ({max})"),
+            ),
+            _ => {}
+        }
+    }
+
+    fn element(&mut self, e: &Element, global: bool) {
+        let span = e.span();
+        if e.default.is_some() && e.fixed.is_some() {
+            self.report(
+                "src-element.1",
+                span,
+                "`default` and `fixed` must not both be present",
+            );
+        }
+        if !global {
+            if e.name.is_some() == e.r#ref.is_some() {
+                self.report(
+                    "src-element.2.1",
+                    span,
+                    "exactly one of `name` and `ref` must be present",
+                );
+            }
+            let declares = e.complex_type().is_some()
+                || e.simple_type().is_some()
+                || !e.identity_constraints().is_empty()
+                || e.nillable.is_some()
+                || e.default.is_some()
+                || e.fixed.is_some()
+                || e.form.is_some()
+                || e.block.is_some()
+                || e.r#type.is_some();
+            if e.r#ref.is_some() && declares {
+                self.report(
+                    "src-element.2.2",
+                    span,
+                    "an element with `ref` must only have `minOccurs`, `maxOccurs` and `id`",
+                );
+            }
+            self.occurs(e.min_occurs, e.max_occurs.clone(), span);
+        }
+        if e.r#type.is_some() && (e.simple_type().is_some() || e.complex_type().is_some()) {
+            self.report(
+                "src-element.3",
+                span,
+                "an element must not have both a `type` and an inline type definition",
+            );
+        }
+        if let Some(inline) = e.simple_type() {
+            self.simple_type(inline);
+        }
+        if let Some(inline) = e.complex_type() {
+            self.complex_type(inline);
+        }
+        for alternative in e.alternatives() {
+            if let Some(inline) = alternative.simple_type() {
+                self.simple_type(inline);
+            }
+            if let Some(inline) = alternative.complex_type() {
+                self.complex_type(inline);
+            }
+        }
+    }
+
+    fn attribute(&mut self, e: &Attribute, global: bool) {
+        let span = e.span();
+        if e.default.is_some() && e.fixed.is_some() {
+            self.report(
+                "src-attribute.1",
+                span,
+                "`default` and `fixed` must not both be present",
+            );
+        }
+        if e.default.is_some() && !matches!(e.r#use, None | Some(AttributeUse::Optional)) {
+            self.report(
+                "src-attribute.2",
+                span,
+                "an attribute with a `default` must be optional",
+            );
+        }
+        if !global {
+            if e.name.is_some() == e.r#ref.is_some() {
+                self.report(
+                    "src-attribute.3.1",
+                    span,
+                    "exactly one of `name` and `ref` must be present",
+                );
+            }
+            let declares = e.form.is_some() || e.r#type.is_some() || e.simple_type().is_some();
+            if e.r#ref.is_some() && declares {
+                self.report(
+                    "src-attribute.3.2",
+                    span,
+                    "an attribute with `ref` must not have `form`, `type` or a `simpleType`",
+                );
+            }
+        }
+        if e.r#type.is_some() && e.simple_type().is_some() {
+            self.report(
+                "src-attribute.4",
+                span,
+                "an attribute must not have both a `type` and a `simpleType` child",
+            );
+        }
+        if let Some(inline) = e.simple_type() {
+            self.simple_type(inline);
+        }
+    }
+
+    fn components(&mut self, model: &ComponentModel) {
+        for ty in model.type_definitions() {
+            if let Some(definition) = ty.definition() {
+                self.type_definition(ty, definition.span());
+            }
+        }
+        for element in model.elements() {
+            self.element_declaration(element);
+        }
+        for group in model.groups() {
+            let span = group.definition().span();
+            if let Some(particle) = group.particle() {
+                self.nested_all_groups(model, particle, span);
+            }
+            if reaches_group(group, group, &mut vec![]) {
+                self.report(
+                    "mg-props-correct.2",
+                    span,
+                    format!("model group `{}` refers to itself", group.name()),
+                );
+            }
+        }
+        for group in model.attribute_groups() {
+            let span = group.definition().span();
+            let referenced = group.attribute_groups();
+            if referenced
+                .iter()
+                .any(|referenced| reaches_attribute_group(*referenced, group, &mut vec![]))
+            {
+                self.report(
+                    "src-attribute_group.3",
+                    span,
+                    format!("attribute group `{}` refers to itself", group.name()),
+                );
+            }
+            let names = attribute_names(group.attribute_uses(), referenced);
+            self.duplicate_attributes("ag-props-correct.2", span, names);
+        }
+        for definition in model.identity_constraints() {
+            let Some(key) = definition.referenced_key() else {
+                continue;
+            };
+            let span = definition.constraint().span();
+            if let IdentityConstraint::Keyref(_) = key.constraint() {
+                self.report(
+                    "c-props-correct.1",
+                    span,
+                    "a keyref must refer to a key or unique constraint",
+                );
+            }
+            let (fields, referenced) = (
+                definition.constraint().fields().len(),
+                key.constraint().fields().len(),
+            );
+            if fields != referenced {
+                self.report(
+                    "c-props-correct.2",
+                    span,
+                    format!("a keyref has {fields} fields, the key it refers to has {referenced}"),
+                );
+            }
+        }
+    }
+
+    /// Reports the references to model groups with an `all` compositor
+    /// within a sequence or choice, which the grammar cannot rule out.
+    fn nested_all_groups(
+        &mut self,
+        model: &ComponentModel,
+        particle: &ContentParticle,
+        span: &Span,
+    ) {
+        let Term::ModelGroup { particles, .. } = &particle.term else {
+            return;
+        };
+        for particle in particles {
+            match &particle.term {
+                Term::Group(id) => {
+                    let group = model.group_by_id(*id);
+                    let compositor = group.particle().and_then(|particle| match particle.term {
+                        Term::ModelGroup { compositor, .. } => Some(compositor),
+                        _ => None,
+                    });
+                    if compositor == Some(Compositor::All) {
+                        self.report(
+                            "cos-all-limited.1.2",
+                            span,
+                            format!(
+                                "the `all` group `{}` must not appear within a sequence or choice",
+                                group.name()
+                            ),
+                        );
+                    }
+                }
+                Term::ModelGroup { .. } => self.nested_all_groups(model, particle, span),
+                Term::Element(_) | Term::Any(_) => {}
+            }
+        }
+    }
+
+    fn type_definition(&mut self, ty: Type, span: &Span) {
+        if let Some(particle) = ty.particle() {
+            self.nested_all_groups(ty.model(), particle, span);
+        }
+        let chain = ty.derivation_chain();
+        if chain
+            .last()
+            .is_some_and(|step| step.base.base_type().is_some())
+        {
+            let rule = match ty.complex_type() {
+                Some(_) => "ct-props-correct.3",
+                None => "st-props-correct.2",
+            };
+            self.report(rule, span, "a type must not be derived from itself");
+            return;
+        }
+        if ty.simple_type().is_some() {
+            if refers_to_itself(ty, true) {
+                self.report(
+                    "cos-no-circular-unions",
+                    span,
+                    "a union must not be one of its own member types",
+                );
+                return;
+            }
+            if refers_to_itself(ty, false) {
+                self.report(
+                    "st-props-correct.2",
+                    span,
+                    "a type must not be defined in terms of itself",
+                );
+                return;
+            }
+        }
+        let (Some(base), Some(method)) = (ty.base_type(), ty.derivation_method()) else {
+            return;
+        };
+        match ty.complex_type() {
+            Some(definition) => self.complex_type_definition(ty, definition, base, method, span),
+            None => self.simple_type_definition(ty, base, method, span),
+        }
+    }
+
+    fn complex_type_definition(
+        &mut self,
+        ty: Type,
+        definition: &ComplexType,
+        base: Type,
+        method: DerivationMethod,
+        span: &Span,
+    ) {
+        let base_content = base.effective_content();
+        let content = ty.effective_content();
+        if definition.complex_content().is_some() && is_simple(base) {
+            self.report(
+                "src-ct.1",
+                span,
+                "the base type of complex content must be a complex type",
+            );
+        }
+        if definition.simple_content().is_some()
+            && !is_simple(base)
+            && base_content.as_ref().is_some_and(|base| {
+                base.simple_type.is_none()
+                    && !(method == DerivationMethod::Restriction && base.mixed)
+            })
+        {
+            self.report(
+                "src-ct.2",
+                span,
+                "the base type of simple content must be a simple type or have simple content",
+            );
+        }
+
+        match method {
+            DerivationMethod::Extension => {
+                if base.final_methods().contains(&method) {
+                    self.report(
+                        "cos-ct-extends.1.1",
+                        span,
+                        "the base type does not allow derivations by extension",
+                    );
+                }
+                if let (Some(base), Some(content), None) =
+                    (&base_content, &content, definition.simple_content())
+                {
+                    if base.simple_type.is_some() && ty.particle().is_some() {
+                        self.report(
+                            "cos-ct-extends.1.4",
+                            span,
+                            "an extension of a type with simple content must not add elements",
+                        );
+                    } else if base.particle.is_some()
+                        && ty.particle().is_some()
+                        && base.mixed != content.mixed
+                    {
+                        self.report(
+                            "cos-ct-extends.1.4",
+                            span,
+                            "an extension must be mixed if and only if its base type is",
+                        );
+                    }
+                }
+            }
+            DerivationMethod::Restriction => {
+                if base.final_methods().contains(&method) {
+                    self.report(
+                        "derivation-ok-restriction.1",
+                        span,
+                        "the base type does not allow derivations by restriction",
+                    );
+                }
+                if let (Some(base), Some(content)) = (&base_content, &content) {
+                    for inherited in &base.attribute_uses {
+                        let name = inherited.attribute().name();
+                        let restricted = content
+                            .attribute_uses
+                            .iter()
+                            .find(|attribute_use| attribute_use.attribute().name() == name);
+                        match restricted {
+                            None if inherited.is_required() => self.report(
+                                "derivation-ok-restriction.3",
+                                span,
+                                format!("the required attribute `{name}` must not be removed"),
+                            ),
+                            Some(restricted)
+                                if inherited.is_required() && !restricted.is_required() =>
+                            {
+                                self.report(
+                                    "derivation-ok-restriction.2.1.1",
+                                    span,
+                                    format!("the attribute `{name}` must remain required"),
+                                )
+                            }
+                            _ => {}
+                        }
+                    }
+                    for attribute_use in &content.attribute_uses {
+                        let name = attribute_use.attribute().name();
+                        let inherited = base.attribute_uses.iter();
+                        let allowed = inherited
+                            .map(|inherited| inherited.attribute().name())
+                            .any(|inherited| inherited == name)
+                            || base
+                                .attribute_wildcard
+                                .as_ref()
+                                .is_some_and(|wildcard| wildcard.allows(name.namespace()));
+                        if !allowed {
+                            self.report(
+                                "derivation-ok-restriction.2.2",
+                                span,
+                                format!("the attribute `{name}` is not allowed by the base type"),
+                            );
+                        }
+                    }
+                }
+            }
+            DerivationMethod::List | DerivationMethod::Union => {}
+        }
+        let names = attribute_names(ty.attribute_uses(), ty.attribute_groups());
+        self.duplicate_attributes("ct-props-correct.4", span, names);
+    }
+
+    fn simple_type_definition(
+        &mut self,
+        ty: Type,
+        base: Type,
+        method: DerivationMethod,
+        span: &Span,
+    ) {
+        match method {
+            DerivationMethod::Restriction => {
+                if !is_simple(base) {
+                    self.report(
+                        "cos-st-restricts.1.1",
+                        span,
+                        "the base type of a simple type must be a simple type",
+                    );
+                } else {
+                    if base.final_methods().contains(&method) {
+                        self.report(
+                            "st-props-correct.3",
+                            span,
+                            "the base type does not allow derivations by restriction",
+                        );
+                    }
+                    let content = ty.simple_type().map(SimpleType::content);
+                    if let Some(Ok(SimpleTypeContent::Restriction(restriction))) = content {
+                        self.facets(base, &restriction.facets());
+                    }
+                }
+            }
+            DerivationMethod::List => {
+                let Some(item_type) = ty.item_type() else {
+                    return;
+                };
+                if variety(item_type) == Some(Variety::List) {
+                    self.report(
+                        "cos-st-restricts.2.1",
+                        span,
+                        "the item type of a list must not be a list type",
+                    );
+                }
+                if item_type.final_methods().contains(&method) {
+                    self.report(
+                        "cos-st-restricts.2.2.1",
+                        span,
+                        "the item type does not allow derivations by list",
+                    );
+                }
+            }
+            DerivationMethod::Union => {
+                for member in ty.member_types() {
+                    if member.final_methods().contains(&method) {
+                        self.report(
+                            "cos-st-restricts.3.2.1",
+                            span,
+                            "a member type does not allow derivations by union",
+                        );
+                    }
+                }
+            }
+            DerivationMethod::Extension => {}
+        }
+    }
+
+    fn element_declaration(&mut self, element: ElementDeclaration) {
+        let span = element.declaration().span();
+        let ty = element.type_definition();
+        for head in element.substitution_group_affiliations() {
+            let excluded = substitution_group_exclusions(head);
+            let allowed = ty
+                .derivation_path(head.type_definition())
+                .is_some_and(|path| path.iter().all(|step| !excluded.contains(&step.method)));
+            if !allowed {
+                self.report(
+                    "e-props-correct.4",
+                    span,
+                    format!(
+                        "the type of `{}` must be derived from the type of its substitution group head `{}`",
+                        element.name(),
+                        head.name()
+                    ),
+                );
+            }
+        }
+        let mut heads = element.substitution_group_affiliations();
+        let mut seen = vec![];
+        while let Some(head) = heads.pop() {
+            if head == element {
+                self.report(
+                    "e-props-correct.6",
+                    span,
+                    format!(
+                        "`{}` must not be in its own substitution group",
+                        element.name()
+                    ),
+                );
+                break;
+            }
+            if !seen.contains(&head) {
+                seen.push(head);
+                heads.extend(head.substitution_group_affiliations());
+            }
+        }
+    }
+
+    fn duplicate_attributes(&mut self, rule: &'static str, span: &Span, names: Vec<String>) {
+        for (index, name) in names.iter().enumerate() {
+            if names[..index].contains(name) && !names[index + 1..].contains(name) {
+                self.report(
+                    rule,
+                    span,
+                    format!("the attribute `{name}` is declared twice"),
+                );
+            }
+        }
+    }
+}
+
+impl Checker {
+    /// Checks the facets of a simple type derived by restriction from
+    /// `base`: whether they apply to the base type, are consistent with
+    /// each other and do not loosen the facets of the base type.
+    fn facets(&mut self, base: Type, facets: &[Facet]) {
+        let applicable = applicable_facets(base);
+        let mut own = vec![];
+        for (index, facet) in facets.iter().enumerate() {
+            let (kind, span) = (facet.kind(), facet.span());
+            if applicable.is_some_and(|applicable| !applicable.contains(&kind)) {
+                self.report(
+                    "cos-applicable-facets",
+                    span,
+                    format!("`{}` does not apply to the base type", kind.name()),
+                );
+                continue;
+            }
+            let Some(value) = facet_value(facet) else {
+                continue;
+            };
+            if facets[..index].iter().any(|facet| facet.kind() == kind) {
+                self.report(
+                    "src-single-facet-value",
+                    span,
+                    format!("`{}` must not be specified more than once", kind.name()),
+                );
+                continue;
+            }
+            let inherited = inherited_facet(base, &[kind]);
+            if let Some(inherited) = inherited.filter(|inherited| inherited.fixed) {
+                if !same_value(base, &inherited, &value) {
+                    self.report(
+                        valid_restriction(kind),
+                        span,
+                        format!(
+                            "`{}` is fixed to `{}` in the base type",
+                            kind.name(),
+                            inherited.value
+                        ),
+                    );
+                    continue;
+                }
+            }
+            own.push((span, value));
+        }
+        let facets = Facets { base, own };
+
+        for (span, value) in &facets.own {
+            self.facet_restriction(&facets, span, value);
+        }
+        for (kinds, rule) in [
+            (
+                [FacetKind::MinInclusive, FacetKind::MinExclusive],
+                "minInclusive-minExclusive",
+            ),
+            (
+                [FacetKind::MaxInclusive, FacetKind::MaxExclusive],
+                "maxInclusive-maxExclusive",
+            ),
+        ] {
+            let both = kinds.iter().all(|kind| facets.own(&[*kind]).is_some());
+            if let (true, Some((span, _))) = (both, facets.own(&kinds[1..])) {
+                self.report(
+                    rule,
+                    span,
+                    format!(
+                        "`{}` and `{}` must not both be specified",
+                        kinds[0].name(),
+                        kinds[1].name()
+                    ),
+                );
+            }
+        }
+
+        let lengths = [
+            (
+                FacetKind::MinLength,
+                FacetKind::Length,
+                "length-minLength-maxLength.1",
+            ),
+            (
+                FacetKind::Length,
+                FacetKind::MaxLength,
+                "length-minLength-maxLength.2",
+            ),
+            (
+                FacetKind::MinLength,
+                FacetKind::MaxLength,
+                "minLength-less-than-equal-to-maxLength",
+            ),
+            (
+                FacetKind::FractionDigits,
+                FacetKind::TotalDigits,
+                "fractionDigits-totalDigits",
+            ),
+        ];
+        for (lower, upper, rule) in lengths {
+            let Some((span, lower, upper)) = facets.pair(&[lower], &[upper]) else {
+                continue;
+            };
+            let ordering = compare_integers(&lower.value, &upper.value);
+            if ordering.is_some_and(Ordering::is_gt) {
+                self.report(
+                    rule,
+                    span,
+                    format!(
+                        "`{}` ({}) must not be greater than `{}` ({})",
+                        lower.kind.name(),
+                        lower.value,
+                        upper.kind.name(),
+                        upper.value
+                    ),
+                );
+            }
+        }
+
+        let Some(primitive) = builtin_ancestor(base).and_then(|builtin| builtin.primitive_type())
+        else {
+            return;
+        };
+        let bounds = facets.pair(
+            &[FacetKind::MinInclusive, FacetKind::MinExclusive],
+            &[FacetKind::MaxInclusive, FacetKind::MaxExclusive],
+        );
+        let Some((span, lower, upper)) = bounds else {
+            return;
+        };
+        let Some(ordering) = compare_values(primitive, &lower.value, &upper.value) else {
+            return;
+        };
+        let (rule, valid) = match (lower.kind, upper.kind) {
+            (FacetKind::MinInclusive, FacetKind::MaxInclusive) => (
+                "minInclusive-less-than-equal-to-maxInclusive",
+                ordering.is_le(),
+            ),
+            (FacetKind::MinExclusive, FacetKind::MaxExclusive) => (
+                "minExclusive-less-than-equal-to-maxExclusive",
+                ordering.is_le(),
+            ),
+            (FacetKind::MinExclusive, _) => {
+                ("minExclusive-less-than-maxInclusive", ordering.is_lt())
+            }
+            (_, _) => ("minInclusive-less-than-maxExclusive", ordering.is_lt()),
+        };
+        if !valid {
+            self.report(
+                rule,
+                span,
+                format!(
+                    "`{}` ({}) and `{}` ({}) leave no values",
+                    lower.kind.name(),
+                    lower.value,
+                    upper.kind.name(),
+                    upper.value
+                ),
+            );
+        }
+    }
+
+    /// Checks that a facet of a restriction does not loosen the facet of
+    /// the same kind, or for a bound of the same direction, of the base
+    /// type.
+    fn facet_restriction(&mut self, facets: &Facets, span: &Span, value: &FacetValue) {
+        let kind = value.kind;
+        let lower = [FacetKind::MinInclusive, FacetKind::MinExclusive];
+        let upper = [FacetKind::MaxInclusive, FacetKind::MaxExclusive];
+        let kinds: &[FacetKind] = match kind {
+            FacetKind::MinInclusive | FacetKind::MinExclusive => &lower,
+            FacetKind::MaxInclusive | FacetKind::MaxExclusive => &upper,
+            _ => &[kind],
+        };
+        let Some(inherited) = inherited_facet(facets.base, kinds) else {
+            return;
+        };
+        let whitespace = |value: &FacetValue| match value.value.as_str() {
+            "preserve" => 0,
+            "replace" => 1,
+            _ => 2,
+        };
+        let valid = match kind {
+            FacetKind::Length
+            | FacetKind::MinLength
+            | FacetKind::MaxLength
+            | FacetKind::TotalDigits
+            | FacetKind::FractionDigits => {
+                let Some(ordering) = compare_integers(&value.value, &inherited.value) else {
+                    return;
+                };
+                match kind {
+                    FacetKind::Length => ordering.is_eq(),
+                    FacetKind::MinLength => ordering.is_ge(),
+                    _ => ordering.is_le(),
+                }
+            }
+            FacetKind::WhiteSpace => whitespace(value) >= whitespace(&inherited),
+            FacetKind::ExplicitTimezone => {
+                inherited.value == "optional" || inherited.value == value.value
+            }
+            FacetKind::MinInclusive
+            | FacetKind::MinExclusive
+            | FacetKind::MaxInclusive
+            | FacetKind::MaxExclusive => {
+                let primitive =
+                    builtin_ancestor(facets.base).and_then(|builtin| builtin.primitive_type());
+                let ordering = primitive.and_then(|primitive| {
+                    compare_values(primitive, &value.value, &inherited.value)
+                });
+                let Some(mut ordering) = ordering else {
+                    return;
+                };
+                if kinds == upper {
+                    ordering = ordering.reverse();
+                }
+                // A bound may only coincide with that of the base type if
+                // it excludes at least the same values.
+                let inclusive = matches!(kind, FacetKind::MinInclusive | FacetKind::MaxInclusive);
+                let inherited_inclusive = matches!(
+                    inherited.kind,
+                    FacetKind::MinInclusive | FacetKind::MaxInclusive
+                );
+                ordering.is_gt() || ordering.is_eq() && (!inclusive || inherited_inclusive)
+            }
+            FacetKind::Pattern | FacetKind::Enumeration | FacetKind::Assertion => true,
+        };
+        if !valid {
+            self.report(
+                valid_restriction(kind),
+                span,
+                format!(
+                    "`{}` ({}) is less restrictive than `{}` ({}) of the base type",
+                    kind.name(),
+                    value.value,
+                    inherited.kind.name(),
+                    inherited.value
+                ),
+            );
+        }
+    }
+}
+
+/// The value of a facet other than `pattern`, `enumeration` and
+/// `assertion`, as it would be written in a schema.
+#[derive(Debug, Clone)]
+struct FacetValue {
+    kind: FacetKind,
+    value: String,
+    fixed: bool,
+}
+
+/// The facets of a restriction that passed the checks on their own.
+struct Facets<'s, 'a> {
+    base: Type<'a>,
+    own: Vec<(&'s Span, FacetValue)>,
+}
+
+impl Facets<'_, '_> {
+    /// Returns the first facet of the restriction among `kinds`.
+    fn own(&self, kinds: &[FacetKind]) -> Option<&(&Span, FacetValue)> {
+        self.own
+            .iter()
+            .find(|(_, value)| kinds.contains(&value.kind))
+    }
+
+    /// Returns the first facet of the restriction among `kinds`, or else
+    /// the one the base type specifies.
+    fn effective(&self, kinds: &[FacetKind]) -> Option<FacetValue> {
+        match self.own(kinds) {
+            Some((_, value)) => Some(value.clone()),
+            None => inherited_facet(self.base, kinds),
+        }
+    }
+
+    /// Returns the effective facets among `first` and `second` if the
+    /// restriction specifies at least one of them, with the location of
+    /// that one.
+    fn pair(
+        &self,
+        first: &[FacetKind],
+        second: &[FacetKind],
+    ) -> Option<(&Span, FacetValue, FacetValue)> {
+        let (span, _) = self.own(first).or_else(|| self.own(second))?;
+        Some((span, self.effective(first)?, self.effective(second)?))
+    }
+}
+
+fn facet_value(facet: &Facet) -> Option<FacetValue> {
+    let value = match facet {
+        Facet::Length(e) | Facet::MinLength(e) | Facet::MaxLength(e) => e.value.to_string(),
+        Facet::TotalDigits(e) | Facet::FractionDigits(e) => e.value.to_string(),
+        Facet::MinInclusive(e)
+        | Facet::MaxInclusive(e)
+        | Facet::MinExclusive(e)
+        | Facet::MaxExclusive(e) => e.value.trim().to_string(),
+        Facet::WhiteSpace(e) => match e.value {
+            WhiteSpaceValue::Preserve => "preserve",
+            WhiteSpaceValue::Replace => "replace",
+            WhiteSpaceValue::Collapse => "collapse",
+        }
+        .to_string(),
+        Facet::ExplicitTimezone(e) => match e.value {
+            ExplicitTimezoneValue::Optional => "optional",
+            ExplicitTimezoneValue::Required => "required",
+            ExplicitTimezoneValue::Prohibited => "prohibited",
+        }
+        .to_string(),
+        Facet::Pattern(_) | Facet::Enumeration(_) | Facet::Assertion(_) => return None,
+    };
+    Some(FacetValue {
+        kind: facet.kind(),
+        value,
+        fixed: facet.is_fixed(),
+    })
+}
+
+/// Returns the facet among `kinds` that the nearest of a type and its
+/// ancestors specifies. Facets are not inherited through list and union
+/// types, which only apply them to their items or members.
+fn inherited_facet(ty: Type, kinds: &[FacetKind]) -> Option<FacetValue> {
+    let chain = ty.derivation_chain();
+    let ancestors = std::iter::once(ty).chain(chain.iter().map(|step| step.base));
+    for ancestor in ancestors {
+        if let Some(builtin) = ancestor.builtin() {
+            let mut current = Some(builtin);
+            while let Some(builtin) = current {
+                let facets = builtin.facets.iter();
+                if let Some(facet) = facets.into_iter().find(|facet| kinds.contains(&facet.kind)) {
+                    return Some(FacetValue {
+                        kind: facet.kind,
+                        value: facet.value.to_string(),
+                        fixed: facet.fixed,
+                    });
+                }
+                current = builtin.base_type();
+            }
+            return None;
+        }
+        let Ok(SimpleTypeContent::Restriction(restriction)) = ancestor.simple_type()?.content()
+        else {
+            return None;
+        };
+        let facets = restriction.facets();
+        let facets = facets.iter().filter(|facet| kinds.contains(&facet.kind()));
+        if let Some(value) = facets.filter_map(facet_value).next() {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Returns the nearest built-in ancestor of an atomic type, or `None` if
+/// the type is a list or union type or is derived from one.
+fn builtin_ancestor(ty: Type) -> Option<&'static BuiltinType> {
+    let chain = ty.derivation_chain();
+    let ancestors = std::iter::once(ty).chain(chain.iter().map(|step| step.base));
+    for ancestor in ancestors {
+        if let Some(builtin) = ancestor.builtin() {
+            return Some(builtin);
+        }
+        let Ok(SimpleTypeContent::Restriction(_)) = ancestor.simple_type()?.content() else {
+            return None;
+        };
+    }
+    None
+}
+
+/// Returns the facets that may restrict a type, or `None` if they are not
+/// known.
+fn applicable_facets(ty: Type) -> Option<&'static [FacetKind]> {
+    if let Some(builtin) = builtin_ancestor(ty) {
+        return Some(builtin.applicable_facets());
+    }
+    match variety(ty)? {
+        Variety::List => Some(LIST_FACETS),
+        Variety::Union => Some(UNION_FACETS),
+        Variety::Atomic => None,
+    }
+}
+
+/// Returns whether a facet has the same value as a facet of the same kind
+/// of a base type. Lengths and digits are compared as numbers, and count as
+/// the same if either is not a number. Bounds are compared as values of the
+/// primitive type, if it can be compared.
+fn same_value(base: Type, inherited: &FacetValue, value: &FacetValue) -> bool {
+    match value.kind {
+        FacetKind::Length
+        | FacetKind::MinLength
+        | FacetKind::MaxLength
+        | FacetKind::TotalDigits
+        | FacetKind::FractionDigits => match compare_integers(&inherited.value, &value.value) {
+            Some(ordering) => ordering.is_eq(),
+            None => true,
+        },
+        FacetKind::MinInclusive
+        | FacetKind::MinExclusive
+        | FacetKind::MaxInclusive
+        | FacetKind::MaxExclusive => {
+            let primitive = builtin_ancestor(base).and_then(|builtin| builtin.primitive_type());
+            let ordering = primitive
+                .and_then(|primitive| compare_values(primitive, &inherited.value, &value.value));
+            match ordering {
+                Some(ordering) => ordering.is_eq(),
+                None => inherited.value == value.value,
+            }
+        }
+        _ => inherited.value == value.value,
+    }
+}
+
+/// Returns the code of the constraint that a facet must not loosen the
+/// facet of the same kind of the base type.
+fn valid_restriction(kind: FacetKind) -> &'static str {
+    match kind {
+        FacetKind::Length => "length-valid-restriction",
+        FacetKind::MinLength => "minLength-valid-restriction",
+        FacetKind::MaxLength => "maxLength-valid-restriction",
+        FacetKind::Pattern => "pattern-valid-restriction",
+        FacetKind::WhiteSpace => "whiteSpace-valid-restriction",
+        FacetKind::Enumeration => "enumeration-valid-restriction",
+        FacetKind::MinInclusive => "minInclusive-valid-restriction",
+        FacetKind::MaxInclusive => "maxInclusive-valid-restriction",
+        FacetKind::MinExclusive => "minExclusive-valid-restriction",
+        FacetKind::MaxExclusive => "maxExclusive-valid-restriction",
+        FacetKind::TotalDigits => "totalDigits-valid-restriction",
+        FacetKind::FractionDigits => "fractionDigits-valid-restriction",
+        FacetKind::Assertion => "assertion-valid-restriction",
+        FacetKind::ExplicitTimezone => "explicitTimezone-valid-restriction",
+    }
+}
+
+/// Compares two values of a primitive type. Numbers and dates and times
+/// are compared, `None` is returned for other types, for invalid values and
+/// for dates and times of which only one has a timezone.
+fn compare_values(primitive: &BuiltinType, a: &str, b: &str) -> Option<Ordering> {
+    match primitive.name {
+        "decimal" => compare_decimals(a, b),
+        "float" | "double" => a.parse::<f64>().ok()?.partial_cmp(&b.parse().ok()?),
+        "dateTime" | "date" | "time" | "gYearMonth" | "gYear" | "gMonthDay" | "gDay" | "gMonth" => {
+            let (a_seconds, a_fraction, a_zoned) = date_time_value(primitive.name, a)?;
+            let (b_seconds, b_fraction, b_zoned) = date_time_value(primitive.name, b)?;
+            // Without timezone, a value may be up to 14 hours either way.
+            if a_zoned != b_zoned {
+                return None;
+            }
+            Some(a_seconds.cmp(&b_seconds).then(a_fraction.cmp(b_fraction)))
+        }
+        _ => None,
+    }
+}
+
+/// Places a value of a date or time type on the time line: returns its
+/// whole seconds in UTC if it has a timezone, the digits of its fraction
+/// of a second without trailing zeros, and whether it has a timezone.
+/// Missing parts are taken from 2000-01-01T00:00:00.
+fn date_time_value<'v>(primitive: &str, value: &'v str) -> Option<(i64, &'v str, bool)> {
+    let (value, offset) = split_timezone(value.trim())?;
+    let number = |digits: &str| -> Option<u32> {
+        let valid = !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit());
+        valid.then(|| digits.parse().ok()).flatten()
+    };
+    let (year, month, day, time) = match primitive {
+        "dateTime" => {
+            let (date, time) = value.split_once('T')?;
+            let (year, month, day) = split_date(date)?;
+            (year, month, day, Some(time))
+        }
+        "date" => {
+            let (year, month, day) = split_date(value)?;
+            (year, month, day, None)
+        }
+        "time" => ("2000", "01", "01", Some(value)),
+        "gYearMonth" => {
+            let (year, month) = value.rsplit_once('-')?;
+            (year, month, "01", None)
+        }
+        "gYear" => (value, "01", "01", None),
+        "gMonthDay" => {
+            let (month, day) = value.strip_prefix("--")?.split_once('-')?;
+            ("2000", month, day, None)
+        }
+        "gDay" => ("2000", "01", value.strip_prefix("---")?, None),
+        "gMonth" => ("2000", value.strip_prefix("--")?, "01", None),
+        _ => return None,
+    };
+    let year = match year.strip_prefix('-') {
+        Some(year) => -i64::from(number(year)?),
+        None => i64::from(number(year)?),
+    };
+    let (month, day) = (number(month)?, number(day)?);
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+    let (seconds, fraction) = match time {
+        Some(time) => {
+            let mut parts = time.splitn(3, ':');
+            let (hours, minutes) = (number(parts.next()?)?, number(parts.next()?)?);
+            let seconds = parts.next()?;
+            let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, ""));
+            let seconds = number(seconds)?;
+            if !fraction.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let seconds = i64::from(hours * 3600 + minutes * 60 + seconds);
+            (seconds, fraction.trim_end_matches('0'))
+        }
+        None => (0, ""),
+    };
+    let seconds = days_from_civil(year, month, day) * 86400 + seconds;
+    Some((
+        seconds - offset.unwrap_or(0) * 60,
+        fraction,
+        offset.is_some(),
+    ))
+}
+
+/// Splits a date such as `2024-02-29` into its year, month and day.
+fn split_date(date: &str) -> Option<(&str, &str, &str)> {
+    let (rest, day) = date.rsplit_once('-')?;
+    let (year, month) = rest.rsplit_once('-')?;
+    Some((year, month, day))
+}
+
+/// Splits the timezone off a date or time value, returning the offset in
+/// minutes.
+fn split_timezone(value: &str) -> Option<(&str, Option<i64>)> {
+    if let Some(value) = value.strip_suffix('Z') {
+        return Some((value, Some(0)));
+    }
+    let bytes = value.as_bytes();
+    let start = bytes.len().checked_sub(6);
+    let Some(start) = start.filter(|&start| matches!(bytes[start], b'+' | b'-')) else {
+        return Some((value, None));
+    };
+    if bytes[start + 3] != b':' {
+        return Some((value, None));
+    }
+    let hours: i64 = value[start + 1..start + 3].parse().ok()?;
+    let minutes: i64 = value[start + 4..].parse().ok()?;
+    let offset = hours * 60 + minutes;
+    let offset = if bytes[start] == b'-' {
+        -offset
+    } else {
+        offset
+    };
+    Some((&value[..start], Some(offset)))
+}
+
+/// Returns the number of days from 1970-01-01 to a date of the proleptic
+/// Gregorian calendar.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+/// Compares two decimal numbers of any precision.
+fn compare_decimals(a: &str, b: &str) -> Option<Ordering> {
+    let (a_negative, a_integer, a_fraction) = decimal_parts(a)?;
+    let (b_negative, b_integer, b_fraction) = decimal_parts(b)?;
+    let magnitude = a_integer
+        .len()
+        .cmp(&b_integer.len())
+        .then(a_integer.cmp(b_integer))
+        .then(a_fraction.cmp(b_fraction));
+    Some(match (a_negative, b_negative) {
+        (false, false) => magnitude,
+        (true, true) => magnitude.reverse(),
+        (false, true) => Ordering::Greater,
+        (true, false) => Ordering::Less,
+    })
+}
+
+/// Compares two non-negative integers of any precision, such as the values
+/// of length and digits facets. Returns `None` if either is not a valid
+/// `nonNegativeInteger`.
+fn compare_integers(a: &str, b: &str) -> Option<Ordering> {
+    fn digits(value: &str) -> Option<&str> {
+        let value = value.trim();
+        let (negative, integer, _) = decimal_parts(value)?;
+        (!negative && !value.contains('.')).then_some(integer)
+    }
+    let (a, b) = (digits(a)?, digits(b)?);
+    Some(a.len().cmp(&b.len()).then(a.cmp(b)))
+}
+
+/// Splits a decimal number into whether it is negative, its integer digits
+/// and its fraction digits, without insignificant zeros.
+fn decimal_parts(value: &str) -> Option<(bool, &str, &str)> {
+    let (negative, unsigned) = match value.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    let mut digits = integer.bytes().chain(fraction.bytes());
+    if integer.is_empty() && fraction.is_empty() || !digits.all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let integer = integer.trim_start_matches('0');
+    let fraction = fraction.trim_end_matches('0');
+    let zero = integer.is_empty() && fraction.is_empty();
+    Some((negative && !zero, integer, fraction))
+}
+
+/// Returns whether a type is a simple type, possibly a built-in one.
+fn is_simple(ty: Type) -> bool {
+    ty.simple_type().is_some() || ty.builtin().is_some_and(|builtin| !builtin.is_complex())
+}
+
+/// Returns whether a simple type refers to itself through its base, item
+/// and member types, or only through member types of unions if
+/// `unions_only`, directly or through other types.
+fn refers_to_itself(ty: Type, unions_only: bool) -> bool {
+    fn referenced(ty: Type, unions_only: bool) -> Vec<Type> {
+        match ty.derivation_method() {
+            Some(DerivationMethod::Union) => ty.member_types(),
+            _ if unions_only || ty.simple_type().is_none() => vec![],
+            Some(DerivationMethod::List) => ty.item_type().into_iter().collect(),
+            _ => ty.base_type().into_iter().collect(),
+        }
+    }
+    let mut pending = referenced(ty, unions_only);
+    let mut seen = vec![];
+    while let Some(next) = pending.pop() {
+        if next == ty {
+            return true;
+        }
+        if !seen.contains(&next) {
+            seen.push(next);
+            pending.extend(referenced(next, unions_only));
+        }
+    }
+    false
+}
+
+/// Returns the variety of a simple type, or `None` for a complex type and
+/// for `xs:anySimpleType` and `xs:anyAtomicType`.
+fn variety(ty: Type) -> Option<Variety> {
+    let chain = ty.derivation_chain();
+    let ancestors = std::iter::once(ty).chain(chain.iter().map(|step| step.base));
+    for ancestor in ancestors {
+        if let Some(builtin) = ancestor.builtin() {
+            return builtin.variety;
+        }
+        match ancestor.simple_type()?.content() {
+            Ok(SimpleTypeContent::List(_)) => return Some(Variety::List),
+            Ok(SimpleTypeContent::Union(_)) => return Some(Variety::Union),
+            Ok(SimpleTypeContent::Restriction(_)) | Err(_) => {}
+        }
+    }
+    None
+}
+
+/// Returns the methods by which the types of the members of the
+/// substitution group of an element must not be derived, given by its
+/// `final` or else by the `finalDefault` of its schema.
+fn substitution_group_exclusions(element: ElementDeclaration) -> Vec<DerivationMethod> {
+    let schema = element.document().schema();
+    let values = element.declaration().r#final.as_ref();
+    let values = values
+        .or(schema.final_default.as_ref())
+        .into_iter()
+        .flatten();
+    let methods = values.flat_map(DerivationMethod::from_final);
+    methods
+        .copied()
+        .filter(|method| {
+            matches!(
+                method,
+                DerivationMethod::Extension | DerivationMethod::Restriction
+            )
+        })
+        .collect()
+}
+
+/// Returns the names of attribute uses and of those of attribute groups,
+/// each group once.
+fn attribute_names(
+    attribute_uses: Vec<crate::model::AttributeUse>,
+    groups: Vec<AttributeGroupDefinition>,
+) -> Vec<String> {
+    let mut names: Vec<_> = attribute_uses
+        .iter()
+        .map(|attribute_use| attribute_use.attribute().name().to_string())
+        .collect();
+    let mut pending = groups;
+    let mut seen = vec![];
+    while let Some(group) = pending.pop() {
+        if seen.contains(&group) {
+            continue;
+        }
+        seen.push(group);
+        names.extend(
+            group
+                .attribute_uses()
+                .iter()
+                .map(|attribute_use| attribute_use.attribute().name().to_string()),
+        );
+        pending.extend(group.attribute_groups());
+    }
+    names
+}
+
+/// Returns whether `target` is referenced by `group`, directly or through
+/// other model groups.
+fn reaches_group<'a>(
+    group: ModelGroupDefinition<'a>,
+    target: ModelGroupDefinition<'a>,
+    seen: &mut Vec<ModelGroupDefinition<'a>>,
+) -> bool {
+    fn references(term: &Term, out: &mut Vec<crate::model::GroupId>) {
+        match term {
+            Term::Group(id) => out.push(*id),
+            Term::ModelGroup { particles, .. } => {
+                particles
+                    .iter()
+                    .for_each(|particle| references(&particle.term, out));
+            }
+            Term::Element(_) | Term::Any(_) => {}
+        }
+    }
+    if seen.contains(&group) {
+        return false;
+    }
+    seen.push(group);
+    let mut ids = vec![];
+    if let Some(particle) = group.particle() {
+        references(&particle.term, &mut ids);
+    }
+    let model = group.model();
+    ids.into_iter()
+        .map(|id| model.group_by_id(id))
+        .any(|referenced| referenced == target || reaches_group(referenced, target, seen))
+}
+
+/// Returns whether `group` is `target` or references it, directly or
+/// through other attribute groups.
+fn reaches_attribute_group<'a>(
+    group: AttributeGroupDefinition<'a>,
+    target: AttributeGroupDefinition<'a>,
+    seen: &mut Vec<AttributeGroupDefinition<'a>>,
+) -> bool {
+    if group == target {
+        return true;
+    }
+    if seen.contains(&group) {
+        return false;
+    }
+    seen.push(group);
+    let referenced = group.attribute_groups().into_iter();
+    referenced
+        .into_iter()
+        .any(|referenced| reaches_attribute_group(referenced, target, seen))
+}
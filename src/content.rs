@@ -0,0 +1,324 @@
+//! The effective content of complex types, i.e. their content once
+//! derivation has been applied.
+//!
+//! A complex type definition only gives what it adds to or changes in its
+//! base type. [Type::effective_content] combines the two: an extension
+//! appends its particle to that of the base type in a sequence and adds its
+//! attribute uses to those of the base type, while a restriction replaces
+//! the particle and the attribute uses it redeclares. References to model
+//! groups and attribute groups are expanded along the way, and the
+//! `defaultAttributes` of the schema are added unless the type opts out.
+//!
+//! The simple type of simple content that is derived by restriction is
+//! given as the simple type it restricts together with the facets of the
+//! restrictions, as the model has no type definition for the restricted
+//! type itself.
+use crate::{
+    facets::Facet,
+    model::{
+        AttributeGroupDefinition, AttributeUse, ComponentModel, Compositor, ContentParticle,
+        DerivationMethod, GroupId, Term, Type,
+    },
+    particles::MaxOccurs,
+    wildcards::Wildcard,
+    Derivation,
+};
+
+/// The content of a complex type after derivation.
+#[derive(Debug, Clone)]
+pub struct EffectiveContent<'a> {
+    /// Whether character data may appear between the child elements, given
+    /// by the `mixed` of the complex content or else of the type.
+    pub mixed: bool,
+    /// The type of the character data of a type with simple content,
+    /// before the [facets](EffectiveContent::facets) are applied.
+    pub simple_type: Option<Type<'a>>,
+    /// The facets by which the restrictions of simple content restrict the
+    /// simple type, in the order of derivation.
+    pub facets: Vec<Facet<'a>>,
+    /// The content model, without references to model groups, or `None` if
+    /// the type allows no child elements.
+    pub particle: Option<ContentParticle<'a>>,
+    /// The attribute uses, including those of the base type, of the
+    /// referenced attribute groups and of the default attributes, except
+    /// the prohibited ones.
+    pub attribute_uses: Vec<AttributeUse<'a>>,
+    /// The wildcard that allows attributes besides the attribute uses.
+    pub attribute_wildcard: Option<Wildcard>,
+}
+
+impl<'a> Type<'a> {
+    /// Computes the content of a complex type after derivation, see the
+    /// [module documentation](self).
+    ///
+    /// Returns `None` for a simple type and for `xs:anyType`, which allows
+    /// any content. A restriction of `xs:anyType`, including a type that
+    /// does not specify a derivation, does not inherit from it, while an
+    /// extension inherits its element and attribute wildcards.
+    pub fn effective_content(self) -> Option<EffectiveContent<'a>> {
+        self.content(&mut vec![])
+    }
+
+    fn content(self, seen: &mut Vec<Type<'a>>) -> Option<EffectiveContent<'a>> {
+        let definition = self.complex_type()?;
+        if seen.contains(&self) {
+            return None;
+        }
+        seen.push(self);
+        let extension = self.derivation_method() == Some(DerivationMethod::Extension);
+        let base_content = match self.base_type() {
+            Some(base) if base.is_builtin() => {
+                let any_type = extension && base == self.model().any_type();
+                any_type.then(|| any_type_content(self.model()))
+            }
+            Some(base) => base.content(seen),
+            None => None,
+        };
+
+        let complex_content = definition.complex_content();
+        let mixed = complex_content
+            .and_then(|content| content.mixed)
+            .or(definition.mixed)
+            .unwrap_or(false);
+        let simple_content = definition.simple_content();
+        let own_facets = match simple_content.and_then(|content| content.derivation()) {
+            Some(Derivation::Restriction(restriction)) => restriction.facets(),
+            _ => vec![],
+        };
+        let (simple_type, facets) = match (simple_content, self.base_type()) {
+            (Some(_), Some(base)) if base.complex_type().is_none() => (Some(base), own_facets),
+            (Some(_), Some(_)) => match (self.simple_content_type(), &base_content) {
+                (Some(inline), _) => (Some(inline), own_facets),
+                (None, Some(base)) => {
+                    let mut facets = base.facets.clone();
+                    facets.extend(own_facets);
+                    (base.simple_type, facets)
+                }
+                (None, None) => (None, own_facets),
+            },
+            _ => (None, vec![]),
+        };
+
+        let own = self
+            .particle()
+            .and_then(|particle| expand(self.model(), particle, &mut vec![]))
+            .filter(|particle| !is_empty(particle));
+        let particle = match (simple_type, extension) {
+            (Some(_), _) => None,
+            (None, true) => {
+                let inherited = base_content.as_ref().and_then(|base| base.particle.clone());
+                append(inherited, own)
+            }
+            (None, false) => own,
+        };
+
+        let mut groups = vec![];
+        let mut declared = self.attribute_uses();
+        let mut wildcard = self.attribute_wildcard().cloned();
+        for group in self
+            .attribute_groups()
+            .into_iter()
+            .chain(self.default_attributes())
+        {
+            collect_group(group, &mut groups, &mut declared, &mut wildcard);
+        }
+        let mut attribute_uses = vec![];
+        if let Some(base) = &base_content {
+            attribute_uses.extend(base.attribute_uses.iter().copied());
+        }
+        for attribute_use in declared {
+            let name = attribute_use.attribute().name();
+            let inherited = attribute_uses
+                .iter()
+                .position(|inherited| inherited.attribute().name() == name);
+            match (inherited, extension) {
+                // An extension cannot change the attribute uses of its base
+                // type.
+                (Some(_), true) => continue,
+                (Some(index), false) => {
+                    attribute_uses.remove(index);
+                }
+                (None, _) => {}
+            }
+            if !attribute_use.is_prohibited() {
+                attribute_uses.push(attribute_use);
+            }
+        }
+        let inherited_wildcard = base_content.and_then(|base| base.attribute_wildcard);
+        let attribute_wildcard = match (wildcard, inherited_wildcard, extension) {
+            (Some(own), Some(inherited), true) => Some(own.union(&inherited)),
+            (None, inherited, true) => inherited,
+            (own, _, _) => own,
+        };
+
+        seen.pop();
+        Some(EffectiveContent {
+            mixed,
+            simple_type,
+            facets,
+            particle,
+            attribute_uses,
+            attribute_wildcard,
+        })
+    }
+}
+
+/// Returns the content of the built-in `xs:anyType`: mixed content with
+/// any elements and attributes, which are validated laxly.
+fn any_type_content<'a>(model: &'a ComponentModel<'a>) -> EffectiveContent<'a> {
+    let wildcard = model.any_type_wildcard();
+    let particle = ContentParticle {
+        min_occurs: 1,
+        max_occurs: MaxOccurs::Bounded(1),
+        term: Term::ModelGroup {
+            compositor: Compositor::Sequence,
+            particles: vec![ContentParticle {
+                min_occurs: 0,
+                max_occurs: MaxOccurs::Unbounded("unbounded".to_string()),
+                term: Term::Any(wildcard),
+            }],
+        },
+    };
+    EffectiveContent {
+        mixed: true,
+        simple_type: None,
+        facets: vec![],
+        particle: Some(particle),
+        attribute_uses: vec![],
+        attribute_wildcard: Some(Wildcard::from_any(wildcard, None)),
+    }
+}
+
+/// Adds the attribute uses and the wildcard of an attribute group and of
+/// the attribute groups it references, each group once.
+fn collect_group<'a>(
+    group: AttributeGroupDefinition<'a>,
+    groups: &mut Vec<AttributeGroupDefinition<'a>>,
+    attribute_uses: &mut Vec<AttributeUse<'a>>,
+    wildcard: &mut Option<Wildcard>,
+) {
+    if groups.contains(&group) {
+        return;
+    }
+    groups.push(group);
+    for attribute_use in group.attribute_uses() {
+        let name = attribute_use.attribute().name();
+        if !attribute_uses
+            .iter()
+            .any(|existing| existing.attribute().name() == name)
+        {
+            attribute_uses.push(attribute_use);
+        }
+    }
+    if let Some(group_wildcard) = group.attribute_wildcard() {
+        *wildcard = Some(match wildcard {
+            Some(wildcard) => wildcard.intersection(group_wildcard),
+            None => group_wildcard.clone(),
+        });
+    }
+    for referenced in group.attribute_groups() {
+        collect_group(referenced, groups, attribute_uses, wildcard);
+    }
+}
+
+/// Replaces the references to model groups in a particle by the model
+/// groups, keeping the occurrences of the references. Circular references
+/// are dropped.
+fn expand<'a>(
+    model: &'a ComponentModel<'a>,
+    particle: &ContentParticle<'a>,
+    groups: &mut Vec<GroupId>,
+) -> Option<ContentParticle<'a>> {
+    let term = match &particle.term {
+        Term::Group(id) => {
+            if groups.contains(id) {
+                return None;
+            }
+            let definition = model.group_by_id(*id).particle()?;
+            groups.push(*id);
+            let expanded = expand(model, definition, groups);
+            groups.pop();
+            expanded?.term
+        }
+        Term::ModelGroup {
+            compositor,
+            particles,
+        } => Term::ModelGroup {
+            compositor: *compositor,
+            particles: particles
+                .iter()
+                .filter_map(|particle| expand(model, particle, groups))
+                .collect(),
+        },
+        term => term.clone(),
+    };
+    Some(ContentParticle {
+        min_occurs: particle.min_occurs,
+        max_occurs: particle.max_occurs.clone(),
+        term,
+    })
+}
+
+/// Returns whether a particle matches nothing but the empty sequence.
+fn is_empty(particle: &ContentParticle) -> bool {
+    match &particle.term {
+        _ if particle.max_occurs == MaxOccurs::Bounded(0) => true,
+        Term::ModelGroup {
+            compositor: Compositor::Choice,
+            particles,
+        } => particles.is_empty() && particle.min_occurs == 0,
+        Term::ModelGroup { particles, .. } => particles.iter().all(is_empty),
+        _ => false,
+    }
+}
+
+/// Appends the particle of an extension to that of its base type, in a
+/// sequence unless both are `xs:all` groups, which are merged.
+fn append<'a>(
+    base: Option<ContentParticle<'a>>,
+    extension: Option<ContentParticle<'a>>,
+) -> Option<ContentParticle<'a>> {
+    let (base, extension) = match (base, extension) {
+        (Some(base), Some(extension)) => (base, extension),
+        (base, extension) => return base.or(extension),
+    };
+    let term = match (base.term, extension.term) {
+        (
+            Term::ModelGroup {
+                compositor: Compositor::All,
+                particles: mut merged,
+            },
+            Term::ModelGroup {
+                compositor: Compositor::All,
+                particles,
+            },
+        ) => {
+            merged.extend(particles);
+            return Some(ContentParticle {
+                term: Term::ModelGroup {
+                    compositor: Compositor::All,
+                    particles: merged,
+                },
+                ..base
+            });
+        }
+        (base_term, extension_term) => Term::ModelGroup {
+            compositor: Compositor::Sequence,
+            particles: vec![
+                ContentParticle {
+                    term: base_term,
+                    ..base
+                },
+                ContentParticle {
+                    term: extension_term,
+                    ..extension
+                },
+            ],
+        },
+    };
+    Some(ContentParticle {
+        min_occurs: 1,
+        max_occurs: MaxOccurs::Bounded(1),
+        term,
+    })
+}
@@ -0,0 +1,1340 @@
+//! Deserialization of schema documents.
+//!
+//! A document is first read into a lightweight element tree, which is then
+//! handed to the [Deserialize] implementations of the schema model through
+//! the [serde::Deserializer] implementations in this module. Going through
+//! an intermediate tree (instead of streaming the document) means every
+//! error can be reported together with the path of the element it
+//! originates from.
+//!
+//! The mapping between XML and the serde data model follows the conventions
+//! of `quick_xml::de`:
+//!
+//!  * attributes are exposed as struct fields named `@` followed by the
+//!    local name of the attribute (namespace declarations keep their
+//!    `xmlns` prefix, e.g. `@xmlns:xs`),
+//!  * child elements and text are exposed through a single `$value` field,
+//!  * enum variants are selected by the local name of an element, or by the
+//!    value of an attribute for unit variants,
+//!  * list types (e.g. `Vec<Final>`) are read from whitespace separated
+//!    attribute values.
+//!
+//! In addition, structs can ask for information about the element itself
+//! through fields with a name starting with `#`, which never clash with
+//! attribute or element names:
+//!
+//!  * `#span` provides the [Span] of the element,
+//!  * `#attributes` provides the attributes from namespaces other than the
+//!    XML Schema namespace as a map from their expanded name (in Clark
+//!    notation) to their value. These attributes are not exposed as `@`
+//!    fields, unless they are in the `xml` namespace and the struct has a
+//!    field for them (e.g. `@lang` for `xml:lang`),
+//!  * `#namespaces` provides the namespace bindings in scope at the element
+//!    as a map from prefix (empty for the default namespace) to namespace.
+//!    Namespace declarations are only exposed as `@xmlns` fields if the
+//!    struct asks for them, they are never treated as unknown attributes,
+//!  * `#content` provides the content of the element as an
+//!    [XmlFragment](crate::fragment::XmlFragment), i.e. as a sequence of
+//!    text nodes (strings) and element nodes (maps).
+use std::{borrow::Cow, cell::RefCell, collections::BTreeMap, fmt, marker::PhantomData, sync::Arc};
+
+use quick_xml::{events::Event, Reader};
+use serde::de::{
+    self, Deserialize, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess,
+    SeqAccess, Unexpected, VariantAccess, Visitor,
+};
+
+use crate::{
+    error::{SchemaError, Warning},
+    namespaces::{ExpandedName, XML_NAMESPACE, XSD_NAMESPACE},
+    span::Span,
+};
+
+/// Parsed XML document, stored as a flat list of elements.
+///
+/// Elements reference their parent and children by index, the root element
+/// is always stored at index `0`.
+pub(crate) struct Document {
+    elements: Vec<ElementNode>,
+    source: String,
+    /// Name of the source the document was read from.
+    file: Option<Arc<str>>,
+    /// Byte offsets of the start of every line, used to turn byte offsets
+    /// into line and column numbers.
+    line_starts: Vec<usize>,
+    /// Whether unknown attributes, and child elements that fail to
+    /// deserialize, are skipped instead of rejected.
+    lenient: bool,
+    /// Constructs skipped so far in lenient mode.
+    skipped: RefCell<Vec<Warning>>,
+}
+
+struct ElementNode {
+    /// Qualified name of the element as written in the document.
+    name: String,
+    /// Attributes as (qualified name, unescaped value) pairs.
+    attributes: Vec<(String, String)>,
+    /// Namespace declarations made on this element as (prefix, namespace)
+    /// pairs, the default namespace is declared with an empty prefix.
+    namespaces: Vec<(String, String)>,
+    children: Vec<Child>,
+    parent: Option<usize>,
+    /// Byte offset of the start tag.
+    start: usize,
+    /// Byte offset just past the end tag.
+    end: usize,
+}
+
+enum Child {
+    Element(usize),
+    Text(String),
+}
+
+fn local_name(name: &str) -> &str {
+    name.split_once(':').map_or(name, |(_, local)| local)
+}
+
+fn prefix(name: &str) -> Option<&str> {
+    name.split_once(':').map(|(prefix, _)| prefix)
+}
+
+fn is_namespace_declaration(name: &str) -> bool {
+    name == "xmlns" || name.starts_with("xmlns:")
+}
+
+impl Document {
+    /// Reads the element tree of an XML document.
+    ///
+    /// The `file` name is recorded in the [Span] of every element.
+    pub(crate) fn parse(source: &str, file: Option<Arc<str>>) -> Result<Document, SchemaError> {
+        let bom = if source.starts_with('\u{feff}') { 3 } else { 0 };
+        let mut reader = Reader::from_str(&source[bom..]);
+        let mut document = Document {
+            elements: vec![],
+            source: source.to_string(),
+            file,
+            lenient: false,
+            skipped: RefCell::new(vec![]),
+            line_starts: std::iter::once(0)
+                .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+        };
+        let mut open: Vec<usize> = vec![];
+
+        loop {
+            let offset = bom + reader.buffer_position();
+            let event = reader
+                .read_event()
+                .map_err(|e| document.xml_error(open.last().copied(), e))?;
+            let is_empty = matches!(event, Event::Empty(_));
+            match event {
+                Event::Start(start) | Event::Empty(start) => {
+                    if open.is_empty() && !document.elements.is_empty() {
+                        return Err(document.xml_error(None, "multiple root elements"));
+                    }
+                    let mut attributes = vec![];
+                    let mut namespaces = vec![];
+                    for attribute in start.attributes() {
+                        let attribute =
+                            attribute.map_err(|e| document.xml_error(open.last().copied(), e))?;
+                        let value = attribute
+                            .unescape_value()
+                            .map_err(|e| document.xml_error(open.last().copied(), e))?;
+                        let name = String::from_utf8_lossy(attribute.key.as_ref()).into_owned();
+                        if is_namespace_declaration(&name) {
+                            let prefix = name.strip_prefix("xmlns:").unwrap_or_default();
+                            namespaces.push((prefix.to_string(), value.to_string()));
+                        }
+                        attributes.push((name, value.into_owned()));
+                    }
+                    let id = document.elements.len();
+                    let parent = open.last().copied();
+                    document.elements.push(ElementNode {
+                        name: String::from_utf8_lossy(start.name().as_ref()).into_owned(),
+                        attributes,
+                        namespaces,
+                        children: vec![],
+                        parent,
+                        start: offset,
+                        end: bom + reader.buffer_position(),
+                    });
+                    if let Some(parent) = parent {
+                        document.elements[parent].children.push(Child::Element(id));
+                    }
+                    document.check_prefixes(id)?;
+                    if !is_empty {
+                        open.push(id);
+                    }
+                }
+                Event::End(_) => {
+                    if let Some(id) = open.pop() {
+                        document.elements[id].end = bom + reader.buffer_position();
+                    }
+                }
+                Event::Text(text) => {
+                    let text = text
+                        .unescape()
+                        .map_err(|e| document.xml_error(open.last().copied(), e))?;
+                    document.push_text(open.last().copied(), &text);
+                }
+                Event::CData(text) => {
+                    let text = String::from_utf8_lossy(&text).into_owned();
+                    document.push_text(open.last().copied(), &text);
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        if document.elements.is_empty() {
+            return Err(document.xml_error(None, "document has no root element"));
+        }
+        Ok(document)
+    }
+
+    /// Checks that all prefixes used in the name and attributes of an
+    /// element are bound to a namespace.
+    fn check_prefixes(&self, id: usize) -> Result<(), SchemaError> {
+        let element = &self.elements[id];
+        let names = std::iter::once(&element.name).chain(
+            element
+                .attributes
+                .iter()
+                .map(|(name, _)| name)
+                .filter(|name| !is_namespace_declaration(name)),
+        );
+        for name in names {
+            if let Some(prefix) = prefix(name) {
+                if self.namespace_uri(id, prefix).is_none() {
+                    let message = format!("namespace prefix `{prefix}` is not declared");
+                    return Err(self.xml_error(Some(id), message));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the namespace bound to a prefix (or to the empty prefix for
+    /// the default namespace) in the scope of an element.
+    fn namespace_uri(&self, id: usize, prefix: &str) -> Option<&str> {
+        if prefix == "xml" {
+            return Some(XML_NAMESPACE);
+        }
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let element = &self.elements[id];
+            if let Some((_, namespace)) = element.namespaces.iter().find(|(p, _)| p == prefix) {
+                return (!namespace.is_empty()).then_some(namespace.as_str());
+            }
+            current = element.parent;
+        }
+        None
+    }
+
+    /// Collects the namespace bindings in scope at an element, as a map from
+    /// prefix to namespace.
+    fn namespace_bindings(&self, id: usize) -> BTreeMap<String, String> {
+        let mut bindings = BTreeMap::new();
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let element = &self.elements[id];
+            for (prefix, namespace) in &element.namespaces {
+                bindings
+                    .entry(prefix.clone())
+                    .or_insert_with(|| namespace.clone());
+            }
+            current = element.parent;
+        }
+        // `xmlns=""` undeclares the default namespace.
+        bindings.retain(|_, namespace| !namespace.is_empty());
+        bindings
+    }
+
+    /// Builds the [Value] of the content of an element, as expected by
+    /// [XmlFragment](crate::fragment::XmlFragment).
+    fn fragment(&self, id: usize) -> Value {
+        let nodes = self.elements[id].children.iter().map(|child| match child {
+            Child::Text(text) => Value::Str(text.clone()),
+            Child::Element(id) => self.fragment_element(*id),
+        });
+        Value::Seq(nodes.collect())
+    }
+
+    fn fragment_element(&self, id: usize) -> Value {
+        let element = &self.elements[id];
+        let optional = |value: Option<&str>| value.map_or(Value::Null, |v| Value::Str(v.into()));
+        let attributes = element
+            .attributes
+            .iter()
+            .filter(|(name, _)| !is_namespace_declaration(name))
+            .map(|(name, value)| {
+                Value::Map(vec![
+                    ("prefix", optional(prefix(name))),
+                    ("local_name", Value::Str(local_name(name).into())),
+                    ("namespace", optional(self.attribute_namespace(id, name))),
+                    ("value", Value::Str(value.clone())),
+                ])
+            });
+        let declarations = element.namespaces.iter().map(|(prefix, namespace)| {
+            Value::Seq(vec![
+                Value::Str(prefix.clone()),
+                Value::Str(namespace.clone()),
+            ])
+        });
+        let namespace = self.namespace_uri(id, prefix(&element.name).unwrap_or_default());
+        Value::Map(vec![
+            ("prefix", optional(prefix(&element.name))),
+            ("local_name", Value::Str(local_name(&element.name).into())),
+            ("namespace", optional(namespace)),
+            ("attributes", Value::Seq(attributes.collect())),
+            ("declarations", Value::Seq(declarations.collect())),
+            ("children", self.fragment(id)),
+        ])
+    }
+
+    /// Returns the namespace of an attribute of an element. Attributes
+    /// without a prefix are not in any namespace.
+    fn attribute_namespace(&self, id: usize, name: &str) -> Option<&str> {
+        prefix(name)
+            .filter(|_| !is_namespace_declaration(name))
+            .and_then(|prefix| self.namespace_uri(id, prefix))
+    }
+
+    /// Returns whether an attribute is from a namespace other than the XML
+    /// Schema namespace.
+    fn is_foreign(&self, id: usize, name: &str) -> bool {
+        self.attribute_namespace(id, name)
+            .is_some_and(|namespace| namespace != XSD_NAMESPACE)
+    }
+
+    /// Returns the struct field an attribute is exposed as.
+    ///
+    /// Foreign attributes use their expanded name, so that they never
+    /// clash with the attributes defined by the XML Schema grammar.
+    fn attribute_key(&self, id: usize, name: &str) -> String {
+        match self.attribute_namespace(id, name) {
+            _ if is_namespace_declaration(name) => format!("@{name}"),
+            Some(namespace) if namespace != XSD_NAMESPACE && namespace != XML_NAMESPACE => {
+                format!("@{}", ExpandedName::new(Some(namespace), local_name(name)))
+            }
+            _ => format!("@{}", local_name(name)),
+        }
+    }
+
+    fn push_text(&mut self, parent: Option<usize>, text: &str) {
+        // Text outside of the root element can only be whitespace.
+        let Some(parent) = parent else { return };
+        let children = &mut self.elements[parent].children;
+        if let Some(Child::Text(previous)) = children.last_mut() {
+            previous.push_str(text);
+        } else {
+            children.push(Child::Text(text.to_string()));
+        }
+    }
+
+    /// Returns the source location of an element.
+    fn span(&self, id: usize) -> Span {
+        let element = &self.elements[id];
+        let line = self
+            .line_starts
+            .partition_point(|start| *start <= element.start);
+        let line_start = self.line_starts[line - 1];
+        Span {
+            file: self.file.clone(),
+            line,
+            column: self.source[line_start..element.start].chars().count() + 1,
+            start: element.start,
+            end: element.end,
+        }
+    }
+
+    fn xml_error(&self, id: Option<usize>, message: impl fmt::Display) -> SchemaError {
+        SchemaError::Xml {
+            path: id.map_or_else(|| "/".to_string(), |id| self.path(id)),
+            message: message.to_string(),
+        }
+    }
+
+    /// Builds the element path of an element, e.g. `/xs:schema/xs:element[2]`.
+    pub(crate) fn path(&self, id: usize) -> String {
+        let mut steps = vec![];
+        let mut current = Some(id);
+        while let Some(id) = current {
+            let element = &self.elements[id];
+            match element.parent {
+                Some(parent) => {
+                    let position = self.elements[parent]
+                        .children
+                        .iter()
+                        .filter_map(|child| match child {
+                            Child::Element(sibling) => Some(*sibling),
+                            Child::Text(_) => None,
+                        })
+                        .take_while(|sibling| *sibling != id)
+                        .filter(|sibling| self.elements[*sibling].name == element.name)
+                        .count()
+                        + 1;
+                    steps.push(format!("{}[{}]", element.name, position));
+                }
+                None => steps.push(element.name.clone()),
+            }
+            current = element.parent;
+        }
+        steps.reverse();
+        format!("/{}", steps.join("/"))
+    }
+
+    /// Deserializes the root element of the document.
+    pub(crate) fn deserialize<T: DeserializeOwned>(&self) -> Result<T, SchemaError> {
+        T::deserialize(ElementDeserializer { doc: self, id: 0 })
+            .map_err(|e| e.at_element(self, 0).into_schema_error())
+    }
+
+    /// Deserializes the root element of the document, skipping everything
+    /// that cannot be deserialized.
+    ///
+    /// Unknown attributes are ignored, and a child element that fails to
+    /// deserialize is skipped where it occurs, see [children]. Only errors
+    /// on the root element itself are returned.
+    pub(crate) fn deserialize_lenient<T: DeserializeOwned>(
+        &mut self,
+    ) -> Result<(T, Vec<Warning>), SchemaError> {
+        self.lenient = true;
+        let value = self.deserialize()?;
+        let mut warnings = self.skipped.take();
+        warnings.sort_by_key(|warning| warning.span.start);
+        Ok((value, warnings))
+    }
+
+    fn skip_attribute(&self, id: usize, name: &str) {
+        self.skipped.borrow_mut().push(Warning {
+            span: self.span(id),
+            error: SchemaError::UnsupportedConstruct {
+                path: self.path(id),
+                name: self.attribute_key(id, name),
+            },
+        });
+    }
+
+    /// Reports a child element that failed to deserialize in lenient mode.
+    fn skip_element(&self, error: DeError) {
+        let (error, id) = match error {
+            DeError::Schema(error, id) => (error, id),
+            error => (error.into_schema_error(), 0),
+        };
+        self.skipped.borrow_mut().push(Warning {
+            span: self.span(id),
+            error,
+        });
+    }
+
+    /// Whitespace between elements carries no meaning in a schema document.
+    fn is_significant(&self, child: &Child) -> bool {
+        match child {
+            Child::Element(_) => true,
+            Child::Text(text) => !text.trim().is_empty(),
+        }
+    }
+
+    fn text(&self, id: usize) -> String {
+        let mut text = String::new();
+        for child in &self.elements[id].children {
+            match child {
+                Child::Element(child) => text.push_str(&self.text(*child)),
+                Child::Text(t) => text.push_str(t),
+            }
+        }
+        text
+    }
+}
+
+/// Error produced while deserializing the element tree.
+///
+/// Errors raised by the [Deserialize] implementations do not know where in
+/// the document they occurred. They are converted into a [SchemaError] by
+/// the innermost element or attribute deserializer they pass through.
+#[derive(Debug)]
+pub(crate) enum DeError {
+    Custom(String),
+    UnknownVariant(String, String),
+    UnknownField(String),
+    MissingField(&'static str),
+    /// An error located at the element with the given index.
+    Schema(SchemaError, usize),
+}
+
+impl DeError {
+    fn at_element(self, doc: &Document, id: usize) -> DeError {
+        let path = doc.path(id);
+        let error = match self {
+            DeError::Schema(..) => return self,
+            DeError::UnknownVariant(name, _) | DeError::UnknownField(name) => {
+                SchemaError::UnsupportedConstruct { path, name }
+            }
+            DeError::MissingField(field) => SchemaError::MissingAttribute {
+                path,
+                attribute: field.trim_start_matches('@').to_string(),
+            },
+            DeError::Custom(message) => SchemaError::InvalidValue {
+                path,
+                attribute: None,
+                message,
+            },
+        };
+        DeError::Schema(error, id)
+    }
+
+    fn at_attribute(self, doc: &Document, id: usize, attribute: &str) -> DeError {
+        let message = match self {
+            DeError::Schema(..) => return self,
+            error => error.to_string(),
+        };
+        let error = SchemaError::InvalidValue {
+            path: doc.path(id),
+            attribute: Some(attribute.to_string()),
+            message,
+        };
+        DeError::Schema(error, id)
+    }
+
+    fn into_schema_error(self) -> SchemaError {
+        match self {
+            DeError::Schema(error, _) => error,
+            error => SchemaError::InvalidValue {
+                path: "/".to_string(),
+                attribute: None,
+                message: error.to_string(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for DeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DeError::Custom(message) | DeError::UnknownVariant(_, message) => f.write_str(message),
+            DeError::UnknownField(field) => write!(f, "unknown field `{field}`"),
+            DeError::MissingField(field) => write!(f, "missing field `{field}`"),
+            DeError::Schema(error, _) => error.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for DeError {}
+
+impl de::Error for DeError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        DeError::Custom(msg.to_string())
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        let message = de::value::Error::unknown_variant(variant, expected).to_string();
+        DeError::UnknownVariant(variant.to_string(), message)
+    }
+
+    fn unknown_field(field: &str, _expected: &'static [&'static str]) -> Self {
+        DeError::UnknownField(field.to_string())
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        DeError::MissingField(field)
+    }
+}
+
+/// Deserializes an element of the document.
+#[derive(Clone, Copy)]
+struct ElementDeserializer<'d> {
+    doc: &'d Document,
+    id: usize,
+}
+
+impl<'d> ElementDeserializer<'d> {
+    fn node(&self) -> &'d ElementNode {
+        &self.doc.elements[self.id]
+    }
+
+    fn text(&self) -> TextDeserializer<'d> {
+        TextDeserializer(Cow::Owned(self.doc.text(self.id)))
+    }
+
+    fn map(&self, fields: Option<&'static [&'static str]>) -> ElementMap<'d> {
+        ElementMap {
+            doc: self.doc,
+            id: self.id,
+            fields,
+            attributes: self.node().attributes.iter(),
+            entries: ["#span", "#attributes", "#namespaces", "#content", "$value"]
+                .into_iter()
+                .filter(|entry| fields.map_or(*entry == "$value", |f| f.contains(entry)))
+                .collect::<Vec<_>>()
+                .into_iter(),
+            pending: None,
+        }
+    }
+}
+
+macro_rules! deserialize_element_text {
+    ($($method:ident)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+            self.text()
+                .$method(visitor)
+                .map_err(|e| e.at_element(self.doc, self.id))
+        }
+    )*};
+}
+
+impl<'de, 'd> de::Deserializer<'de> for ElementDeserializer<'d> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let node = self.node();
+        let has_elements = node
+            .children
+            .iter()
+            .any(|child| matches!(child, Child::Element(_)));
+        if node.attributes.is_empty() && !has_elements {
+            self.deserialize_string(visitor)
+        } else {
+            self.deserialize_map(visitor)
+        }
+    }
+
+    deserialize_element_text! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_f32
+        deserialize_f64 deserialize_char deserialize_str deserialize_string deserialize_bytes
+        deserialize_byte_buf deserialize_identifier
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        if name != SKIPPABLE || !self.doc.lenient {
+            return visitor.visit_newtype_struct(self);
+        }
+        let failure = RefCell::new(None);
+        let value = visitor.visit_some(SkippableElement {
+            element: self,
+            failure: &failure,
+        })?;
+        if let Some(error) = failure.into_inner() {
+            self.doc.skip_element(error);
+        }
+        Ok(value)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor
+            .visit_seq(ContentSeq::new(self.doc, self.id))
+            .map_err(|e| e.at_element(self.doc, self.id))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor
+            .visit_map(self.map(None))
+            .map_err(|e| e.at_element(self.doc, self.id))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor
+            .visit_map(self.map(Some(fields)))
+            .map_err(|e| e.at_element(self.doc, self.id))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor
+            .visit_enum(self)
+            .map_err(|e| e.at_element(self.doc, self.id))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+}
+
+impl<'de, 'd> EnumAccess<'de> for ElementDeserializer<'d> {
+    type Error = DeError;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), DeError> {
+        let name = local_name(&self.node().name);
+        let variant = seed.deserialize(name.into_deserializer())?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de, 'd> VariantAccess<'de> for ElementDeserializer<'d> {
+    type Error = DeError;
+
+    fn unit_variant(self) -> Result<(), DeError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, DeError> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, DeError> {
+        de::Deserializer::deserialize_seq(self, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        de::Deserializer::deserialize_struct(self, "", fields, visitor)
+    }
+}
+
+/// Presents the attributes and content of an element as a map.
+struct ElementMap<'d> {
+    doc: &'d Document,
+    id: usize,
+    /// Fields of the struct being deserialized, if known.
+    fields: Option<&'static [&'static str]>,
+    attributes: std::slice::Iter<'d, (String, String)>,
+    /// Entries that do not come from attributes and are still to be produced.
+    entries: std::vec::IntoIter<&'static str>,
+    pending: Option<Pending<'d>>,
+}
+
+enum Pending<'d> {
+    Attribute(&'d str, &'d str),
+    Entry(&'static str),
+}
+
+impl<'d> ElementMap<'d> {
+    fn has_field(&self, key: &str) -> bool {
+        self.fields.is_some_and(|fields| fields.contains(&key))
+    }
+
+    /// Returns the foreign attributes that are not exposed as fields, as
+    /// (expanded name, value) pairs.
+    fn foreign_attributes(&self) -> Vec<(String, String)> {
+        let doc = self.doc;
+        doc.elements[self.id]
+            .attributes
+            .iter()
+            .filter(|(name, _)| {
+                doc.is_foreign(self.id, name) && !self.has_field(&doc.attribute_key(self.id, name))
+            })
+            .map(|(name, value)| {
+                let namespace = doc.attribute_namespace(self.id, name);
+                let name = ExpandedName::new(namespace, local_name(name));
+                (name.to_string(), value.clone())
+            })
+            .collect()
+    }
+}
+
+impl<'de, 'd> MapAccess<'de> for ElementMap<'d> {
+    type Error = DeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, DeError> {
+        let fields = self.fields;
+        let has_field = |key: &str| fields.is_some_and(|fields| fields.contains(&key));
+        for (name, value) in self.attributes.by_ref() {
+            let key = self.doc.attribute_key(self.id, name);
+            if fields.is_some() && !has_field(&key) {
+                // Namespace declarations are collected in `#namespaces` and
+                // foreign attributes in `#attributes` instead.
+                if is_namespace_declaration(name)
+                    || self.doc.is_foreign(self.id, name) && has_field("#attributes")
+                {
+                    continue;
+                }
+                if self.doc.lenient {
+                    self.doc.skip_attribute(self.id, name);
+                    continue;
+                }
+            }
+            self.pending = Some(Pending::Attribute(name, value));
+            let key: de::value::StringDeserializer<DeError> = key.into_deserializer();
+            return seed.deserialize(key).map(Some);
+        }
+        match self.entries.next() {
+            Some(entry) => {
+                self.pending = Some(Pending::Entry(entry));
+                seed.deserialize(entry.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DeError> {
+        match self.pending.take() {
+            Some(Pending::Attribute(name, value)) => seed
+                .deserialize(TextDeserializer(Cow::Borrowed(value)))
+                .map_err(|e| e.at_attribute(self.doc, self.id, name)),
+            Some(Pending::Entry("#span")) => {
+                seed.deserialize(SpanDeserializer(self.doc.span(self.id)))
+            }
+            Some(Pending::Entry("#attributes")) => {
+                let attributes = self.foreign_attributes().into_iter();
+                seed.deserialize(de::value::MapDeserializer::new(attributes))
+            }
+            Some(Pending::Entry("#namespaces")) => {
+                let bindings = self.doc.namespace_bindings(self.id).into_iter();
+                seed.deserialize(de::value::MapDeserializer::new(bindings))
+            }
+            Some(Pending::Entry("#content")) => seed.deserialize(self.doc.fragment(self.id)),
+            Some(Pending::Entry(_)) => seed.deserialize(ContentDeserializer {
+                doc: self.doc,
+                id: self.id,
+            }),
+            None => Err(de::Error::custom("value requested before key")),
+        }
+    }
+}
+
+/// Deserializes the content (child elements and text) of an element.
+struct ContentDeserializer<'d> {
+    doc: &'d Document,
+    id: usize,
+}
+
+impl<'d> ContentDeserializer<'d> {
+    fn first(&self) -> Option<NodeDeserializer<'d>> {
+        ContentSeq::new(self.doc, self.id).next_node()
+    }
+}
+
+macro_rules! deserialize_first_child {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {$(
+        fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, DeError> {
+            match self.first() {
+                Some(node) => node.$method($($arg,)* visitor),
+                None => ElementDeserializer { doc: self.doc, id: self.id }
+                    .text()
+                    .$method($($arg,)* visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de, 'd> de::Deserializer<'de> for ContentDeserializer<'d> {
+    type Error = DeError;
+
+    deserialize_first_child! {
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16()
+        deserialize_i32() deserialize_i64() deserialize_u8() deserialize_u16()
+        deserialize_u32() deserialize_u64() deserialize_f32() deserialize_f64()
+        deserialize_char() deserialize_str() deserialize_string() deserialize_bytes()
+        deserialize_byte_buf() deserialize_unit() deserialize_identifier() deserialize_map()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        if self.first().is_some() {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_seq(ContentSeq::new(self.doc, self.id))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+}
+
+/// Iterates over the significant children of an element.
+struct ContentSeq<'d> {
+    doc: &'d Document,
+    children: std::slice::Iter<'d, Child>,
+}
+
+impl<'d> ContentSeq<'d> {
+    fn new(doc: &'d Document, id: usize) -> Self {
+        ContentSeq {
+            doc,
+            children: doc.elements[id].children.iter(),
+        }
+    }
+
+    fn next_node(&mut self) -> Option<NodeDeserializer<'d>> {
+        let doc = self.doc;
+        self.children
+            .find(|child| doc.is_significant(child))
+            .map(|child| match child {
+                Child::Element(id) => {
+                    NodeDeserializer::Element(ElementDeserializer { doc, id: *id })
+                }
+                Child::Text(text) => NodeDeserializer::Text(TextDeserializer(Cow::Borrowed(text))),
+            })
+    }
+}
+
+impl<'de, 'd> SeqAccess<'de> for ContentSeq<'d> {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DeError> {
+        match self.next_node() {
+            Some(node) => seed.deserialize(node).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Name of the newtype struct through which [children] deserializes a
+/// child that may be skipped.
+const SKIPPABLE: &str = "$skippable";
+
+/// Deserializes the child elements of an element, e.g. the `$value` body
+/// of a schema component.
+///
+/// In lenient mode, a child that fails to deserialize is skipped and
+/// reported as a warning, and its siblings are read as usual.
+pub(crate) fn children<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: de::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    let children = Vec::<Skippable<T>>::deserialize(deserializer)?;
+    Ok(children.into_iter().filter_map(|child| child.0).collect())
+}
+
+/// A child that is `None` if it was skipped.
+struct Skippable<T>(Option<T>);
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Skippable<T> {
+    fn deserialize<D: de::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_newtype_struct(SKIPPABLE, SkippableVisitor(PhantomData))
+    }
+}
+
+/// Reads a child, which is offered through `visit_some` if it may be
+/// skipped and through `visit_newtype_struct` otherwise.
+struct SkippableVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for SkippableVisitor<T> {
+    type Value = Skippable<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a child element")
+    }
+
+    fn visit_newtype_struct<D: de::Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        T::deserialize(d).map(|child| Skippable(Some(child)))
+    }
+
+    fn visit_some<D: de::Deserializer<'de>>(self, d: D) -> Result<Self::Value, D::Error> {
+        Ok(Skippable(T::deserialize(d).ok()))
+    }
+}
+
+/// Deserializes a child element that is skipped if it fails, keeping the
+/// error to report it.
+struct SkippableElement<'d, 'f> {
+    element: ElementDeserializer<'d>,
+    failure: &'f RefCell<Option<DeError>>,
+}
+
+macro_rules! deserialize_skippable {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {$(
+        fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, DeError> {
+            let ElementDeserializer { doc, id } = self.element;
+            self.element.$method($($arg,)* visitor).map_err(|error| {
+                self.failure.replace(Some(error.at_element(doc, id)));
+                de::Error::custom("skipped")
+            })
+        }
+    )*};
+}
+
+impl<'de> de::Deserializer<'de> for SkippableElement<'_, '_> {
+    type Error = DeError;
+
+    deserialize_skippable! {
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16()
+        deserialize_i32() deserialize_i64() deserialize_u8() deserialize_u16()
+        deserialize_u32() deserialize_u64() deserialize_f32() deserialize_f64()
+        deserialize_char() deserialize_str() deserialize_string() deserialize_bytes()
+        deserialize_byte_buf() deserialize_option() deserialize_unit() deserialize_seq()
+        deserialize_map() deserialize_identifier() deserialize_ignored_any()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_tuple(len: usize)
+        deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+}
+
+/// Deserializes a single child node, which is either an element or text.
+enum NodeDeserializer<'d> {
+    Element(ElementDeserializer<'d>),
+    Text(TextDeserializer<'d>),
+}
+
+macro_rules! deserialize_node {
+    ($($method:ident($($arg:ident: $ty:ty),*))*) => {$(
+        fn $method<V: Visitor<'de>>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, DeError> {
+            match self {
+                NodeDeserializer::Element(de) => de.$method($($arg,)* visitor),
+                NodeDeserializer::Text(de) => de.$method($($arg,)* visitor),
+            }
+        }
+    )*};
+}
+
+impl<'de, 'd> de::Deserializer<'de> for NodeDeserializer<'d> {
+    type Error = DeError;
+
+    deserialize_node! {
+        deserialize_any() deserialize_bool() deserialize_i8() deserialize_i16()
+        deserialize_i32() deserialize_i64() deserialize_u8() deserialize_u16()
+        deserialize_u32() deserialize_u64() deserialize_f32() deserialize_f64()
+        deserialize_char() deserialize_str() deserialize_string() deserialize_bytes()
+        deserialize_byte_buf() deserialize_option() deserialize_unit() deserialize_seq()
+        deserialize_map() deserialize_identifier() deserialize_ignored_any()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_tuple(len: usize)
+        deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+    }
+}
+
+/// Deserializes a piece of text, either an attribute value or the text
+/// content of an element.
+struct TextDeserializer<'a>(Cow<'a, str>);
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident,)*) => {$(
+        fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+            match self.0.trim().parse() {
+                Ok(value) => visitor.$visit(value),
+                Err(_) => Err(de::Error::invalid_value(Unexpected::Str(&self.0), &visitor)),
+            }
+        }
+    )*};
+}
+
+impl<'de, 'a> de::Deserializer<'de> for TextDeserializer<'a> {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0.trim().parse::<u64>() {
+            Ok(value) => visitor.visit_u64(value),
+            Err(_) => self.deserialize_string(visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self.0.trim() {
+            "true" | "1" => visitor.visit_bool(true),
+            "false" | "0" => visitor.visit_bool(false),
+            _ => Err(de::Error::invalid_value(Unexpected::Str(&self.0), &visitor)),
+        }
+    }
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_str(&self.0)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_string(self.0.into_owned())
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_bytes(self.0.as_bytes())
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_bytes(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    /// Lists are whitespace separated, as in `final="extension restriction"`.
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let items: Vec<String> = self.0.split_whitespace().map(str::to_string).collect();
+        visitor.visit_seq(ListSeq(items.into_iter()))
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        Err(de::Error::invalid_type(Unexpected::Str(&self.0), &visitor))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        self.deserialize_map(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DeError> {
+        let variant: de::value::StringDeserializer<DeError> =
+            self.0.trim().to_string().into_deserializer();
+        visitor.visit_enum(variant)
+    }
+
+    fn deserialize_identifier<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        visitor.visit_unit()
+    }
+}
+
+/// Items of a whitespace separated list value.
+struct ListSeq(std::vec::IntoIter<String>);
+
+impl<'de> SeqAccess<'de> for ListSeq {
+    type Error = DeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, DeError> {
+        match self.0.next() {
+            Some(item) => seed
+                .deserialize(TextDeserializer(Cow::Owned(item)))
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Deserializes the [Span] of an element.
+struct SpanDeserializer(Span);
+
+impl<'de> de::Deserializer<'de> for SpanDeserializer {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        let Span {
+            file,
+            line,
+            column,
+            start,
+            end,
+        } = self.0;
+        visitor.visit_map(de::value::MapDeserializer::new(
+            [
+                ("file", SpanField::File(file)),
+                ("line", SpanField::Offset(line)),
+                ("column", SpanField::Offset(column)),
+                ("start", SpanField::Offset(start)),
+                ("end", SpanField::Offset(end)),
+            ]
+            .into_iter(),
+        ))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+enum SpanField {
+    File(Option<Arc<str>>),
+    Offset(usize),
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for SpanField {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for SpanField {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self {
+            SpanField::File(Some(file)) => {
+                visitor.visit_some(TextDeserializer(Cow::Owned(file.to_string())))
+            }
+            SpanField::File(None) => visitor.visit_none(),
+            SpanField::Offset(offset) => visitor.visit_u64(offset as u64),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+/// A self-describing value, used to hand over structured data about the
+/// document that does not map onto elements and attributes.
+enum Value {
+    Null,
+    Str(String),
+    Seq(Vec<Value>),
+    Map(Vec<(&'static str, Value)>),
+}
+
+impl<'de> IntoDeserializer<'de, DeError> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Value {
+    type Error = DeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            Value::Str(value) => visitor.visit_string(value),
+            Value::Seq(items) => {
+                visitor.visit_seq(de::value::SeqDeserializer::new(items.into_iter()))
+            }
+            Value::Map(entries) => {
+                visitor.visit_map(de::value::MapDeserializer::new(entries.into_iter()))
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DeError> {
+        match self {
+            Value::Null => visitor.visit_none(),
+            value => visitor.visit_some(value),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
@@ -0,0 +1,234 @@
+//! Error types returned while reading XML Schema documents.
+use std::{error::Error, fmt, io};
+
+use crate::span::Span;
+
+/// Errors that can occur while reading an XML Schema document.
+///
+/// Every variant that originates from the document itself carries the
+/// element path of the offending element, written as a simple XPath
+/// expression such as `/xs:schema/xs:complexType[3]/xs:sequence[1]`.
+/// Each step is the element name as it appears in the document, followed
+/// by its 1-based position among the siblings with the same name.
+#[derive(Debug)]
+pub enum SchemaError {
+    /// The document could not be read from the underlying reader.
+    Io(io::Error),
+    /// The document is not well-formed XML.
+    Xml {
+        /// Path of the innermost element that was open when the error occurred.
+        path: String,
+        /// Description of the syntax error reported by the XML parser.
+        message: String,
+    },
+    /// The document contains an element or attribute that is not part of
+    /// the XML Schema grammar, or that is not supported by this crate.
+    UnsupportedConstruct {
+        /// Path of the unsupported element, or of the element that carries
+        /// the unsupported attribute.
+        path: String,
+        /// Local name of the unsupported element, or of the unsupported
+        /// attribute prefixed with `@`.
+        name: String,
+    },
+    /// An attribute (or text content) has a value that is not valid for
+    /// its type, e.g. a `form` attribute that is neither `qualified` nor
+    /// `unqualified`.
+    InvalidValue {
+        /// Path of the element that carries the value.
+        path: String,
+        /// Name of the attribute, or `None` if the value is element content.
+        attribute: Option<String>,
+        /// Description of why the value was rejected.
+        message: String,
+    },
+    /// A required attribute is missing.
+    MissingAttribute {
+        /// Path of the element that lacks the attribute.
+        path: String,
+        /// Name of the missing attribute.
+        attribute: String,
+    },
+    /// A schema document was included into a schema with a different
+    /// target namespace.
+    TargetNamespaceMismatch {
+        /// Target namespace of the including schema.
+        expected: Option<String>,
+        /// Target namespace of the included schema.
+        found: String,
+    },
+    /// A referenced schema document could not be located.
+    Unresolved {
+        /// URI of the document that contains the reference, `None` for the
+        /// root document.
+        base: Option<String>,
+        /// The `schemaLocation` of the reference.
+        location: String,
+        /// The error reported by the resolver.
+        error: io::Error,
+    },
+    /// Two components of the same kind have the same name in a
+    /// [SchemaSet](crate::SchemaSet).
+    DuplicateDefinition {
+        /// Kind of the components, e.g. `type definition`.
+        kind: &'static str,
+        /// Expanded name of the components, in Clark notation.
+        name: String,
+        /// Location of the first definition.
+        first: Box<Span>,
+        /// Location of the duplicate definition.
+        duplicate: Box<Span>,
+    },
+    /// A qualified name used in a component does not refer to a component
+    /// of the expected kind.
+    UnresolvedReference {
+        /// Kind of the referenced component, e.g. `type definition`.
+        kind: &'static str,
+        /// The name as written, or its expanded name in Clark notation if
+        /// its prefix is bound.
+        name: String,
+        /// Location of the component that uses the name.
+        span: Box<Span>,
+    },
+    /// An error occurred in a schema document loaded as part of a
+    /// [SchemaSet](crate::SchemaSet).
+    InDocument {
+        /// URI of the document.
+        uri: String,
+        /// The error that occurred in the document.
+        error: Box<SchemaError>,
+    },
+}
+
+impl SchemaError {
+    /// Returns the element path at which the error occurred, if the error
+    /// originates from the document content.
+    pub fn path(&self) -> Option<&str> {
+        match self {
+            SchemaError::Io(_)
+            | SchemaError::TargetNamespaceMismatch { .. }
+            | SchemaError::Unresolved { .. }
+            | SchemaError::DuplicateDefinition { .. }
+            | SchemaError::UnresolvedReference { .. } => None,
+            SchemaError::InDocument { error, .. } => error.path(),
+            SchemaError::Xml { path, .. }
+            | SchemaError::UnsupportedConstruct { path, .. }
+            | SchemaError::InvalidValue { path, .. }
+            | SchemaError::MissingAttribute { path, .. } => Some(path),
+        }
+    }
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaError::Io(e) => write!(f, "failed to read schema: {e}"),
+            SchemaError::Xml { path, message } => {
+                write!(f, "malformed XML at {path}: {message}")
+            }
+            SchemaError::UnsupportedConstruct { path, name } => {
+                write!(f, "unsupported construct `{name}` at {path}")
+            }
+            SchemaError::InvalidValue {
+                path,
+                attribute: Some(attribute),
+                message,
+            } => write!(f, "invalid value for `{attribute}` at {path}: {message}"),
+            SchemaError::InvalidValue {
+                path,
+                attribute: None,
+                message,
+            } => write!(f, "invalid content at {path}: {message}"),
+            SchemaError::MissingAttribute { path, attribute } => {
+                write!(f, "missing required attribute `{attribute}` at {path}")
+            }
+            SchemaError::TargetNamespaceMismatch { expected, found } => {
+                write!(f, "included schema has target namespace `{found}`, ")?;
+                match expected {
+                    Some(expected) => write!(f, "expected `{expected}`"),
+                    None => write!(f, "expected no target namespace"),
+                }
+            }
+            SchemaError::Unresolved {
+                base: Some(base),
+                location,
+                error,
+            } => write!(
+                f,
+                "cannot load `{location}` referenced from {base}: {error}"
+            ),
+            SchemaError::Unresolved {
+                base: None,
+                location,
+                error,
+            } => write!(f, "cannot load `{location}`: {error}"),
+            SchemaError::DuplicateDefinition {
+                kind,
+                name,
+                first,
+                duplicate,
+            } => write!(
+                f,
+                "duplicate {kind} `{name}` at {duplicate}, first defined at {first}"
+            ),
+            SchemaError::UnresolvedReference { kind, name, span } => {
+                write!(f, "unresolved reference to {kind} `{name}` at {span}")
+            }
+            SchemaError::InDocument { uri, error } => write!(f, "{uri}: {error}"),
+        }
+    }
+}
+
+impl Error for SchemaError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SchemaError::Io(e) | SchemaError::Unresolved { error: e, .. } => Some(e),
+            SchemaError::InDocument { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SchemaError {
+    fn from(error: io::Error) -> Self {
+        SchemaError::Io(error)
+    }
+}
+
+/// A construct that was skipped while reading a schema in lenient mode.
+///
+/// See [Schema::from_reader_lenient](crate::Schema::from_reader_lenient).
+#[derive(Debug)]
+pub struct Warning {
+    /// Location of the element that was dropped, or that carries the
+    /// attribute that was dropped.
+    pub span: Span,
+    /// The error that caused the construct to be dropped.
+    pub error: SchemaError,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.span, self.error)
+    }
+}
+
+/// A violation of a constraint the XML Schema specification puts on
+/// schemas, found by [Schema::check](crate::Schema::check) or
+/// [SchemaSet::check](crate::SchemaSet::check).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// The code of the violated constraint in the specification, e.g.
+    /// `src-element.3` or `cos-ct-extends.1.1`.
+    pub rule: &'static str,
+    /// Location of the offending element.
+    pub span: Span,
+    /// Description of the violation.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} [{}]", self.span, self.message, self.rule)
+    }
+}
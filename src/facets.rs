@@ -4,9 +4,25 @@
 //! They provide additional restrictions on the allowed values for elements
 //! of that simple type. This module provides definitions for different
 //! facet types supported by XML Schemas.
-use crate::{Annotation, AnyURI, ID};
+use crate::{
+    namespaces::{ForeignAttributes, NamespaceContext},
+    span::Span,
+    Annotation, AnyURI, ID,
+};
 use serde::Deserialize;
 
+crate::impl_component!(
+    Enumeration,
+    WhiteSpace,
+    Pattern,
+    Digits,
+    Length,
+    BoundaryFacet,
+    Assertion,
+    ExplicitTimezone
+);
+
+#[derive(Debug, Clone, Copy)]
 pub enum Facet<'a> {
     Length(&'a Length),
     MinLength(&'a Length),
@@ -24,6 +40,105 @@ pub enum Facet<'a> {
     ExplicitTimezone(&'a ExplicitTimezone),
 }
 
+impl Facet<'_> {
+    /// Returns the kind of the facet.
+    pub fn kind(&self) -> FacetKind {
+        match self {
+            Facet::Length(_) => FacetKind::Length,
+            Facet::MinLength(_) => FacetKind::MinLength,
+            Facet::MaxLength(_) => FacetKind::MaxLength,
+            Facet::Pattern(_) => FacetKind::Pattern,
+            Facet::WhiteSpace(_) => FacetKind::WhiteSpace,
+            Facet::Enumeration(_) => FacetKind::Enumeration,
+            Facet::MinInclusive(_) => FacetKind::MinInclusive,
+            Facet::MaxInclusive(_) => FacetKind::MaxInclusive,
+            Facet::MinExclusive(_) => FacetKind::MinExclusive,
+            Facet::MaxExclusive(_) => FacetKind::MaxExclusive,
+            Facet::TotalDigits(_) => FacetKind::TotalDigits,
+            Facet::FractionDigits(_) => FacetKind::FractionDigits,
+            Facet::Assertion(_) => FacetKind::Assertion,
+            Facet::ExplicitTimezone(_) => FacetKind::ExplicitTimezone,
+        }
+    }
+
+    /// Returns the location of the facet in its source document.
+    pub fn span(&self) -> &Span {
+        match self {
+            Facet::Length(e) | Facet::MinLength(e) | Facet::MaxLength(e) => e.span(),
+            Facet::Pattern(e) => e.span(),
+            Facet::WhiteSpace(e) => e.span(),
+            Facet::Enumeration(e) => e.span(),
+            Facet::MinInclusive(e)
+            | Facet::MaxInclusive(e)
+            | Facet::MinExclusive(e)
+            | Facet::MaxExclusive(e) => e.span(),
+            Facet::TotalDigits(e) | Facet::FractionDigits(e) => e.span(),
+            Facet::Assertion(e) => e.span(),
+            Facet::ExplicitTimezone(e) => e.span(),
+        }
+    }
+
+    /// Returns whether types derived from the restriction that specifies
+    /// the facet may not change its value. This is never the case for
+    /// `pattern`, `enumeration` and `assertion`.
+    pub fn is_fixed(&self) -> bool {
+        let fixed = match self {
+            Facet::Length(e) | Facet::MinLength(e) | Facet::MaxLength(e) => e.fixed,
+            Facet::WhiteSpace(e) => e.fixed,
+            Facet::MinInclusive(e)
+            | Facet::MaxInclusive(e)
+            | Facet::MinExclusive(e)
+            | Facet::MaxExclusive(e) => e.fixed,
+            Facet::TotalDigits(e) | Facet::FractionDigits(e) => e.fixed,
+            Facet::ExplicitTimezone(e) => Some(e.fixed),
+            Facet::Pattern(_) | Facet::Enumeration(_) | Facet::Assertion(_) => None,
+        };
+        fixed.unwrap_or(false)
+    }
+}
+
+/// The kinds of constraining facets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FacetKind {
+    Length,
+    MinLength,
+    MaxLength,
+    Pattern,
+    WhiteSpace,
+    Enumeration,
+    MinInclusive,
+    MaxInclusive,
+    MinExclusive,
+    MaxExclusive,
+    TotalDigits,
+    FractionDigits,
+    Assertion,
+    ExplicitTimezone,
+}
+
+impl FacetKind {
+    /// Returns the local name of the element that specifies the facet,
+    /// e.g. `minInclusive`.
+    pub fn name(self) -> &'static str {
+        match self {
+            FacetKind::Length => "length",
+            FacetKind::MinLength => "minLength",
+            FacetKind::MaxLength => "maxLength",
+            FacetKind::Pattern => "pattern",
+            FacetKind::WhiteSpace => "whiteSpace",
+            FacetKind::Enumeration => "enumeration",
+            FacetKind::MinInclusive => "minInclusive",
+            FacetKind::MaxInclusive => "maxInclusive",
+            FacetKind::MinExclusive => "minExclusive",
+            FacetKind::MaxExclusive => "maxExclusive",
+            FacetKind::TotalDigits => "totalDigits",
+            FacetKind::FractionDigits => "fractionDigits",
+            FacetKind::Assertion => "assertion",
+            FacetKind::ExplicitTimezone => "explicitTimezone",
+        }
+    }
+}
+
 /// Represents an enumeration facet value used in type restrictions.
 ///
 /// An enumeration facet value (`xs:enumeration`) defines a set of
@@ -65,6 +180,12 @@ pub struct Enumeration {
     /// about the permitted values defined by the enumeration.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Enumeration {
@@ -142,6 +263,12 @@ pub struct WhiteSpace {
     /// about the white space handling option.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 #[derive(Deserialize, Debug)]
@@ -196,6 +323,12 @@ pub struct Pattern {
     /// about the regular expression pattern.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Pattern {
@@ -274,6 +407,12 @@ pub struct Digits {
     /// constraint.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Digits {
@@ -361,6 +500,12 @@ pub struct Length {
     /// about the length constraint.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Length {
@@ -382,12 +527,12 @@ impl Length {
 }
 
 /// Represents a boundary facet value used in type restrictions.
-
+///
 /// A boundary facet value (`xs:minInclusive`, `xs:maxInclusive`,
 /// `xs:minExclusive`, `xs:maxExclusive`) defines the allowed range for
 /// element content within a type definition. This struct captures
 /// the attributes and content associated with a boundary facet value.
-
+///
 /// This struct can be used to represent both inclusive and exclusive
 /// boundaries depending on the context:
 ///  * When used with `MinInclusive` or `MaxInclusive`, it defines the
@@ -444,6 +589,12 @@ pub struct BoundaryFacet {
     /// descriptive information about the facet value.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl BoundaryFacet {
@@ -519,6 +670,12 @@ pub struct Assertion {
     /// documentation or other descriptive information about the assertion.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Assertion {
@@ -588,6 +745,12 @@ pub struct ExplicitTimezone {
     /// about the explicit time zone behavior.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl ExplicitTimezone {
@@ -0,0 +1,254 @@
+//! Free-form XML content, as found in `xs:appinfo` and `xs:documentation`.
+use std::collections::BTreeMap;
+
+use quick_xml::escape::{escape, partial_escape};
+use serde::Deserialize;
+
+use crate::namespaces::ExpandedName;
+
+/// A sequence of XML nodes copied from a schema document.
+///
+/// The content of `xs:appinfo` and `xs:documentation` is not part of the
+/// XML Schema language, so it is kept as a small element and text tree.
+/// Comments and processing instructions are not preserved, CDATA sections
+/// are merged into the surrounding text.
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct XmlFragment {
+    nodes: Vec<XmlNode>,
+}
+
+/// A node of an [XmlFragment].
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum XmlNode {
+    Text(String),
+    Element(XmlElement),
+}
+
+/// An element of an [XmlFragment].
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct XmlElement {
+    prefix: Option<String>,
+    local_name: String,
+    namespace: Option<String>,
+    attributes: Vec<XmlAttribute>,
+    /// Namespace declarations made on the element, the default namespace
+    /// is declared with an empty prefix.
+    declarations: Vec<(String, String)>,
+    children: Vec<XmlNode>,
+}
+
+/// An attribute of an [XmlElement].
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct XmlAttribute {
+    prefix: Option<String>,
+    local_name: String,
+    namespace: Option<String>,
+    value: String,
+}
+
+impl XmlFragment {
+    /// Returns the top-level nodes of the fragment.
+    pub fn nodes(&self) -> &[XmlNode] {
+        &self.nodes
+    }
+
+    /// Returns the top-level elements of the fragment, skipping text.
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.nodes.iter().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    /// Returns whether the fragment has no content at all.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns the concatenated text of the fragment and all its
+    /// descendants, without any markup.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.nodes {
+            node.push_text(&mut text);
+        }
+        text
+    }
+
+    /// Serializes the fragment back into XML.
+    ///
+    /// Top-level elements declare the namespaces they use, so each of them
+    /// can be parsed on its own.
+    pub fn to_xml(&self) -> String {
+        let mut xml = String::new();
+        for node in &self.nodes {
+            node.write(&mut xml, &BTreeMap::new());
+        }
+        xml
+    }
+}
+
+impl XmlNode {
+    fn push_text(&self, text: &mut String) {
+        match self {
+            XmlNode::Text(t) => text.push_str(t),
+            XmlNode::Element(element) => {
+                for child in &element.children {
+                    child.push_text(text);
+                }
+            }
+        }
+    }
+
+    fn write(&self, xml: &mut String, scope: &BTreeMap<String, String>) {
+        match self {
+            XmlNode::Text(text) => xml.push_str(&partial_escape(text)),
+            XmlNode::Element(element) => element.write(xml, scope),
+        }
+    }
+}
+
+impl XmlElement {
+    /// Returns the namespace and local name of the element.
+    pub fn name(&self) -> ExpandedName {
+        ExpandedName::new(self.namespace.as_deref(), &self.local_name)
+    }
+
+    /// Returns the name of the element as written in the document, e.g.
+    /// `jaxb:class`.
+    pub fn qualified_name(&self) -> String {
+        qualified_name(self.prefix.as_deref(), &self.local_name)
+    }
+
+    /// Returns the local name of the element.
+    pub fn local_name(&self) -> &str {
+        &self.local_name
+    }
+
+    /// Returns the namespace of the element.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Returns the attributes of the element, not including namespace
+    /// declarations.
+    pub fn attributes(&self) -> &[XmlAttribute] {
+        &self.attributes
+    }
+
+    /// Returns the value of the attribute with the given local name and no
+    /// namespace.
+    pub fn attribute(&self, local_name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.namespace.is_none() && attribute.local_name == local_name)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// Returns the child nodes of the element.
+    pub fn children(&self) -> &[XmlNode] {
+        &self.children
+    }
+
+    /// Returns the child elements of the element, skipping text.
+    pub fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            XmlNode::Text(_) => None,
+        })
+    }
+
+    /// Returns the concatenated text of all descendants of the element.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for child in &self.children {
+            child.push_text(&mut text);
+        }
+        text
+    }
+
+    fn write(&self, xml: &mut String, scope: &BTreeMap<String, String>) {
+        let mut scope = scope.clone();
+        let mut declarations = vec![];
+        for (prefix, namespace) in &self.declarations {
+            scope.insert(prefix.clone(), namespace.clone());
+            declarations.push((prefix.as_str(), namespace.as_str()));
+        }
+        // Declare the namespaces bound outside of the fragment.
+        let used = std::iter::once((self.prefix.as_deref(), self.namespace.as_deref())).chain(
+            self.attributes
+                .iter()
+                .filter(|attribute| attribute.prefix.is_some())
+                .map(|attribute| (attribute.prefix.as_deref(), attribute.namespace.as_deref())),
+        );
+        for (prefix, namespace) in used {
+            let prefix = prefix.unwrap_or_default();
+            let namespace = namespace.unwrap_or_default();
+            let bound = scope.get(prefix).map_or("", String::as_str);
+            if prefix != "xml" && bound != namespace {
+                scope.insert(prefix.to_string(), namespace.to_string());
+                declarations.push((prefix, namespace));
+            }
+        }
+
+        let name = self.qualified_name();
+        xml.push('<');
+        xml.push_str(&name);
+        for (prefix, namespace) in declarations {
+            match prefix {
+                "" => xml.push_str(" xmlns=\""),
+                prefix => {
+                    xml.push_str(" xmlns:");
+                    xml.push_str(prefix);
+                    xml.push_str("=\"");
+                }
+            }
+            xml.push_str(&escape(namespace));
+            xml.push('"');
+        }
+        for attribute in &self.attributes {
+            xml.push(' ');
+            xml.push_str(&attribute.qualified_name());
+            xml.push_str("=\"");
+            xml.push_str(&escape(&attribute.value));
+            xml.push('"');
+        }
+        if self.children.is_empty() {
+            xml.push_str("/>");
+            return;
+        }
+        xml.push('>');
+        for child in &self.children {
+            child.write(xml, &scope);
+        }
+        xml.push_str("</");
+        xml.push_str(&name);
+        xml.push('>');
+    }
+}
+
+impl XmlAttribute {
+    /// Returns the namespace and local name of the attribute.
+    pub fn name(&self) -> ExpandedName {
+        ExpandedName::new(self.namespace.as_deref(), &self.local_name)
+    }
+
+    /// Returns the name of the attribute as written in the document.
+    pub fn qualified_name(&self) -> String {
+        qualified_name(self.prefix.as_deref(), &self.local_name)
+    }
+
+    /// Returns the value of the attribute.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+fn qualified_name(prefix: Option<&str>, local_name: &str) -> String {
+    match prefix {
+        Some(prefix) => format!("{prefix}:{local_name}"),
+        None => local_name.to_string(),
+    }
+}
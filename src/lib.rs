@@ -1,14 +1,45 @@
 #![allow(dead_code)]
-use std::io::BufRead;
+use std::{io::BufRead, path::Path};
 
-use quick_xml::de::Deserializer;
 use serde::Deserialize;
 
+mod de;
+use de::Document;
+
+pub mod error;
+pub use error::{SchemaError, Violation, Warning};
+
+pub mod span;
+use span::Span;
+
+pub mod fragment;
+use fragment::XmlFragment;
+
+pub mod namespaces;
+use namespaces::{ForeignAttributes, NamespaceContext};
+
+pub mod resolver;
+
+pub mod catalog;
+
+pub mod set;
+pub use set::SchemaSet;
+
+pub mod model;
+
+pub mod builtins;
+
+pub mod content;
+
+mod check;
+
+pub mod wildcards;
+
 pub mod basics;
 use basics::{AnyURI, NCName, QName, Token, ID};
 
 pub mod particles;
-use particles::{All, Any, Choice, Element, Group, Sequence};
+use particles::{All, Any, Choice, Element, Group, Particle, Sequence};
 
 pub mod facets;
 use facets::{
@@ -35,6 +66,8 @@ macro_rules! element_from_body {
     }};
 }
 
+#[doc(hidden)]
+#[macro_export]
 macro_rules! elements_from_body {
     ($self:ident, $element_enum:ident::$variant:ident) => {{
         let mut elements = vec![];
@@ -47,6 +80,80 @@ macro_rules! elements_from_body {
     }};
 }
 
+/// Implements the accessors shared by all schema components.
+///
+/// Every component struct records the [Span] of the element it was read
+/// from in a private `span` field, the attributes from other namespaces in a
+/// private `foreign_attributes` field and the namespace bindings in scope in
+/// a private `namespaces` field.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_component {
+    ($($component:ty),* $(,)?) => {$(
+        impl $component {
+            /// Returns the location of the component in its source document.
+            pub fn span(&self) -> &$crate::span::Span {
+                &self.span
+            }
+
+            /// Returns the attributes of the component that are not from the
+            /// XML Schema namespace, such as `jaxb:` customizations.
+            pub fn foreign_attributes(&self) -> &$crate::namespaces::ForeignAttributes {
+                &self.foreign_attributes
+            }
+
+            /// Returns the namespace bindings in scope at the component.
+            pub fn namespaces(&self) -> &$crate::namespaces::NamespaceContext {
+                &self.namespaces
+            }
+
+            /// Resolves a qualified name used in the component, such as the
+            /// value of a `type` or `ref` attribute, into an expanded name.
+            ///
+            /// Returns `None` if the prefix of the name is not bound. Note
+            /// that unprefixed names in a chameleon schema are not adjusted
+            /// to the namespace of the including schema.
+            pub fn resolve_qname(
+                &self,
+                qname: &$crate::basics::QName,
+            ) -> Option<$crate::namespaces::ExpandedName> {
+                self.namespaces.resolve(qname)
+            }
+        }
+    )*};
+}
+
+impl_component!(
+    Schema,
+    Include,
+    Import,
+    Redefine,
+    Override,
+    Notation,
+    AttributeGroup,
+    Attribute,
+    DefaultOpenContent,
+    SimpleType,
+    Union,
+    List,
+    Restriction,
+    AnyAttribute,
+    ComplexType,
+    OpenContent,
+    SimpleContent,
+    ComplexContent,
+    AppInfo,
+    Annotation,
+    Extension,
+    Documentation,
+    Unique,
+    Selector,
+    Key,
+    Keyref,
+    Field,
+    Assert,
+);
+
 /// Represents the possible final derivation constraints used in complex types.
 ///
 /// The `final` attribute in XSD complex types allows you to control which
@@ -101,8 +208,6 @@ pub struct Schema {
     /// in XSD. It allows you to specify a unique identifier for the schema definition.
     #[serde(rename = "@id")]
     pub id: Option<ID>,
-    #[serde(rename = "@xmlns")]
-    xmlns: Option<String>,
     /// Default attribute form for elements within the schema.
     ///
     /// The `attributeFormDefault` attribute on the `xs:schema` element specifies
@@ -115,12 +220,13 @@ pub struct Schema {
     /// the default form (qualified or unqualified) for elements within the schema.
     #[serde(rename = "@elementFormDefault")]
     pub element_form_default: Option<FormChoice>,
-    /// Default block restriction for elements within the schema.
+    /// Vector of default block restrictions for elements within the schema.
     ///
-    /// The `blockDefault` attribute on the `xs:schema` element specifies
-    /// the default block restriction for elements within the schema.
+    /// The `blockDefault` attribute on the `xs:schema` element can specify
+    /// a set of default block restrictions that apply to elements and
+    /// complex types within the schema.
     #[serde(rename = "@blockDefault")]
-    pub block_default: Option<Block>,
+    pub block_default: Option<Vec<Block>>,
     /// Vector of default final restrictions for elements within the schema.
     ///
     /// The `finalDefault` attribute on the `xs:schema` element can specify
@@ -129,12 +235,14 @@ pub struct Schema {
     pub final_default: Option<Vec<Final>>,
     /// Target namespace for the schema definition.
     ///
-    /// The `targetNamespace` attribute is a required attribute on the
+    /// The `targetNamespace` attribute is an optional attribute on the
     /// `xs:schema` element. It specifies the target namespace for the schema
     /// definition. This namespace is used to qualify element and attribute names
-    /// within the schema.
+    /// within the schema. If it is absent, the components of the schema have
+    /// no namespace, unless the schema is included as a chameleon (see
+    /// [Schema::include_into]).
     #[serde(rename = "@targetNamespace")]
-    pub target_namespace: AnyURI,
+    pub target_namespace: Option<AnyURI>,
     /// Optional version information for the schema.
     ///
     /// The `version` attribute is an optional attribute on the `xs:schema` element.
@@ -168,14 +276,183 @@ pub struct Schema {
     /// specify the language of the schema definition itself.
     #[serde(rename = "@lang")]
     pub xml_lang: Option<String>,
-    #[serde(rename = "$value")]
+    #[serde(rename = "$value", deserialize_with = "crate::de::children")]
     body: Vec<SchemaBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+    /// Target namespace taken on from the including schema, if this schema
+    /// has no target namespace and was included as a chameleon.
+    #[serde(skip)]
+    chameleon_namespace: Option<AnyURI>,
 }
 
 impl Schema {
-    pub fn from_reader(reader: impl BufRead) -> Self {
-        let mut deserializer = Deserializer::from_reader(reader);
-        Schema::deserialize(&mut deserializer).unwrap()
+    /// Reads a schema document from a reader.
+    ///
+    /// The whole document is read and parsed before the schema components
+    /// are built. Any failure is reported as a [SchemaError] carrying the
+    /// path of the element where it occurred: malformed XML, elements or
+    /// attributes that are not part of the XML Schema grammar, and
+    /// attribute values that are not valid for their type (e.g. an unknown
+    /// `blockDefault` token).
+    ///
+    /// # Errors
+    ///
+    /// Returns a [SchemaError] if the document cannot be read or does not
+    /// describe a valid schema document.
+    pub fn from_reader(mut reader: impl BufRead) -> Result<Self, SchemaError> {
+        let mut source = String::new();
+        reader.read_to_string(&mut source)?;
+        Document::parse(&source, None)?.deserialize()
+    }
+
+    /// Reads a schema document from a file.
+    ///
+    /// This behaves like [Schema::from_reader], but additionally records
+    /// the path of the file in the [Span] of every component.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [SchemaError] if the file cannot be read or does not
+    /// describe a valid schema document.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, SchemaError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        let file = Some(path.to_string_lossy().into());
+        Document::parse(&source, file)?.deserialize()
+    }
+
+    /// Reads a schema document from a reader, skipping what cannot be read.
+    ///
+    /// In lenient mode, constructs that would make [Schema::from_reader]
+    /// fail are dropped instead, and the rest of the document is read as
+    /// usual:
+    ///
+    /// * unknown attributes are ignored,
+    /// * elements that are unknown, malformed (e.g. an attribute with an
+    ///   invalid value) or that lack a required attribute are skipped
+    ///   together with their content.
+    ///
+    /// Every dropped construct is reported as a [Warning]. Malformed XML
+    /// and errors on the `xs:schema` element itself are still fatal.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [SchemaError] if the document cannot be read, is not
+    /// well-formed, or if the root element cannot be read.
+    pub fn from_reader_lenient(
+        mut reader: impl BufRead,
+    ) -> Result<(Self, Vec<Warning>), SchemaError> {
+        let mut source = String::new();
+        reader.read_to_string(&mut source)?;
+        Document::parse(&source, None)?.deserialize_lenient()
+    }
+
+    /// Reads a schema document from a file, skipping what cannot be read.
+    ///
+    /// This behaves like [Schema::from_reader_lenient], but additionally
+    /// records the path of the file in the [Span] of every component and
+    /// warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [SchemaError] if the file cannot be read, is not
+    /// well-formed, or if the root element cannot be read.
+    pub fn from_file_lenient(path: impl AsRef<Path>) -> Result<(Self, Vec<Warning>), SchemaError> {
+        let path = path.as_ref();
+        let source = std::fs::read_to_string(path)?;
+        let file = Some(path.to_string_lossy().into());
+        Document::parse(&source, file)?.deserialize_lenient()
+    }
+
+    /// Checks the constraints the XML Schema specification puts on the
+    /// representation of a schema document, such as an element having both
+    /// a `type` and an inline type definition.
+    ///
+    /// Constraints that involve other components, such as those on
+    /// derivations and facets, are checked by [SchemaSet::check], which
+    /// also lists the constraints that are not checked.
+    pub fn check(&self) -> Vec<Violation> {
+        check::schema(self)
+    }
+
+    /// Returns the namespace the components of this schema belong to.
+    ///
+    /// This is the `targetNamespace` of the schema, or, for a schema without
+    /// a target namespace that was included as a chameleon, the target
+    /// namespace of the including schema.
+    pub fn effective_target_namespace(&self) -> Option<&str> {
+        self.target_namespace
+            .as_deref()
+            .or(self.chameleon_namespace.as_deref())
+    }
+
+    /// Returns the namespace the components of this schema belong to, see
+    /// [Schema::effective_target_namespace].
+    pub(crate) fn effective_target_namespace_uri(&self) -> Option<&AnyURI> {
+        self.target_namespace
+            .as_ref()
+            .or(self.chameleon_namespace.as_ref())
+    }
+
+    /// Returns the target namespace this schema took on as a chameleon.
+    pub(crate) fn chameleon_namespace(&self) -> Option<&AnyURI> {
+        self.chameleon_namespace.as_ref()
+    }
+
+    /// Returns whether this schema takes on the target namespace of the
+    /// schema that includes it.
+    pub fn is_chameleon(&self) -> bool {
+        self.chameleon_namespace.is_some()
+    }
+
+    /// Prepares this schema for being included into `including` through an
+    /// `xs:include`.
+    ///
+    /// An included schema must either have the same target namespace as
+    /// the including schema, or no target namespace at all. In the latter
+    /// case the included schema is a *chameleon*: its components take on
+    /// the target namespace of the including schema, which is then reported
+    /// by [Schema::effective_target_namespace].
+    ///
+    /// # Errors
+    ///
+    /// Returns [SchemaError::TargetNamespaceMismatch] if this schema has a
+    /// target namespace that differs from the one of `including`.
+    pub fn include_into(&mut self, including: &Schema) -> Result<(), SchemaError> {
+        self.include_into_namespace(including.effective_target_namespace_uri())
+    }
+
+    /// Prepares this schema for being included into a schema whose
+    /// components belong to `expected`, see [Schema::include_into].
+    pub(crate) fn include_into_namespace(
+        &mut self,
+        expected: Option<&AnyURI>,
+    ) -> Result<(), SchemaError> {
+        self.check_include_namespace(expected)?;
+        if self.target_namespace.is_none() {
+            self.chameleon_namespace = expected.cloned();
+        }
+        Ok(())
+    }
+
+    /// Checks that this schema can be included into a schema whose
+    /// components belong to `expected`.
+    pub(crate) fn check_include_namespace(
+        &self,
+        expected: Option<&AnyURI>,
+    ) -> Result<(), SchemaError> {
+        match &self.target_namespace {
+            Some(found) if Some(found) != expected => Err(SchemaError::TargetNamespaceMismatch {
+                expected: expected.map(ToString::to_string),
+                found: found.to_string(),
+            }),
+            _ => Ok(()),
+        }
     }
 
     /// Extracts all child elements defined within the schema.
@@ -337,6 +614,21 @@ impl Schema {
         elements_from_body!(self, SchemaBody::Redefine)
     }
 
+    /// Extracts all `Override` elements defined within the schema.
+    ///
+    /// Overrides (XSD 1.1) replace components of another schema document
+    /// with the components given in the override, without the restrictions
+    /// that apply to redefinitions.
+    ///
+    /// # Returns
+    ///
+    /// A vector containing references to all [Override] structs defined
+    /// within the schema. If no overrides are present, an empty vector is
+    /// returned.
+    pub fn overrides(&self) -> Vec<&Override> {
+        elements_from_body!(self, SchemaBody::Override)
+    }
+
     /// Extracts all `Group` elements defined within the schema.
     ///
     /// This method iterates through the schema's body elements (if present)
@@ -497,7 +789,7 @@ pub enum Block {
 enum SchemaBody {
     Include(Include),
     Import(Import),
-    Override,
+    Override(Override),
     Redefine(Redefine),
     Annotation(Annotation),
     DefaultOpenContent(DefaultOpenContent),
@@ -546,8 +838,14 @@ pub struct Include {
     ///
     /// The body of the `xs:include` element can optionally contain annotation
     /// elements that provide comments or documentation for the inclusion.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     pub annotations: Vec<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 /// Represents an XML Schema import element.
@@ -576,17 +874,24 @@ pub struct Import {
     pub namespace: Option<AnyURI>,
     /// Location of the imported schema document.
     ///
-    /// The `@schemaLocation` attribute is a required attribute on the
+    /// The `@schemaLocation` attribute is an optional attribute on the
     /// `xs:import` element. It specifies the URI (Uniform Resource Identifier)
-    /// of the schema document to be imported.
+    /// of the schema document to be imported. Without it, the document is
+    /// located by the namespace alone.
     #[serde(rename = "@schemaLocation")]
-    pub schema_location: AnyURI,
+    pub schema_location: Option<AnyURI>,
     /// Optional annotations associated with the import element.
     ///
     /// The body of the `xs:import` element can optionally contain annotation
     /// elements that provide comments or documentation for the import.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     pub annotations: Vec<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 #[derive(Deserialize, Debug)]
@@ -597,8 +902,41 @@ pub struct Redefine {
     pub id: Option<ID>,
     #[serde(rename = "@schemaLocation")]
     pub schema_location: AnyURI,
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<RedefineBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Redefine {
+    /// Extracts the annotations of the redefinition.
+    pub fn annotations(&self) -> Vec<&Annotation> {
+        elements_from_body!(self, RedefineBody::Annotation)
+    }
+
+    /// Extracts the redefined simple type definitions.
+    pub fn simple_types(&self) -> Vec<&SimpleType> {
+        elements_from_body!(self, RedefineBody::SimpleType)
+    }
+
+    /// Extracts the redefined complex type definitions.
+    pub fn complex_types(&self) -> Vec<&ComplexType> {
+        elements_from_body!(self, RedefineBody::ComplexType)
+    }
+
+    /// Extracts the redefined model group definitions.
+    pub fn groups(&self) -> Vec<&Group> {
+        elements_from_body!(self, RedefineBody::Group)
+    }
+
+    /// Extracts the redefined attribute group definitions.
+    pub fn attribute_groups(&self) -> Vec<&AttributeGroup> {
+        elements_from_body!(self, RedefineBody::AttributeGroup)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -612,22 +950,149 @@ enum RedefineBody {
     AttributeGroup(AttributeGroup),
 }
 
+/// Represents an XML Schema override element (XSD 1.1).
+///
+/// An override includes another schema document, replacing the components
+/// of that document that have the same name and kind as one of the
+/// components in the body of the override. Unlike a redefinition, the
+/// replacement does not have to be derived from the component it replaces.
+///
+/// ```xsd
+/// <override
+///   id = ID
+///   schemaLocation = anyURI
+///   {any attributes with non-schema namespace . . .}>
+///   Content: (annotation | (simpleType | complexType | group | attributeGroup | element | attribute | notation))*
+/// </override>
+/// ```
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct Override {
+    /// Optional identifier for the override element.
+    #[serde(rename = "@id")]
+    pub id: Option<ID>,
+    /// Location of the schema document whose components are overridden.
+    #[serde(rename = "@schemaLocation")]
+    pub schema_location: AnyURI,
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
+    body: Vec<OverrideBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Override {
+    /// Extracts the annotations of the override.
+    pub fn annotations(&self) -> Vec<&Annotation> {
+        elements_from_body!(self, OverrideBody::Annotation)
+    }
+
+    /// Extracts the replacement simple type definitions.
+    pub fn simple_types(&self) -> Vec<&SimpleType> {
+        elements_from_body!(self, OverrideBody::SimpleType)
+    }
+
+    /// Extracts the replacement complex type definitions.
+    pub fn complex_types(&self) -> Vec<&ComplexType> {
+        elements_from_body!(self, OverrideBody::ComplexType)
+    }
+
+    /// Extracts the replacement model group definitions.
+    pub fn groups(&self) -> Vec<&Group> {
+        elements_from_body!(self, OverrideBody::Group)
+    }
+
+    /// Extracts the replacement attribute group definitions.
+    pub fn attribute_groups(&self) -> Vec<&AttributeGroup> {
+        elements_from_body!(self, OverrideBody::AttributeGroup)
+    }
+
+    /// Extracts the replacement element declarations.
+    pub fn elements(&self) -> Vec<&Element> {
+        let mut elements = vec![];
+        for element in &self.body {
+            if let OverrideBody::Element(e) = element {
+                elements.push(e.as_ref());
+            }
+        }
+        elements
+    }
+
+    /// Extracts the replacement attribute declarations.
+    pub fn attributes(&self) -> Vec<&Attribute> {
+        elements_from_body!(self, OverrideBody::Attribute)
+    }
+
+    /// Extracts the replacement notation declarations.
+    pub fn notations(&self) -> Vec<&Notation> {
+        elements_from_body!(self, OverrideBody::Notation)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+enum OverrideBody {
+    Annotation(Annotation),
+    SimpleType(SimpleType),
+    ComplexType(ComplexType),
+    Group(Group),
+    AttributeGroup(AttributeGroup),
+    Element(Box<Element>),
+    Attribute(Attribute),
+    Notation(Notation),
+}
+
 /// Represents an XSD notation declaration within the schema. This struct
 /// corresponds to the `<xsd:notation>` element in the XSD. Notations
 /// provide a way to define external systems for processing data within an
 /// XML document.
+///
+/// ```xsd
+/// <notation
+///   id = ID
+///   name = NCName
+///   public = token
+///   system = anyURI
+///   {any attributes with non-schema namespace . . .}>
+///   Content: (annotation?)
+/// </notation>
+/// ```
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Notation {
+    /// Optional identifier for the notation declaration.
     #[serde(rename = "@id")]
-    id: Option<ID>,
+    pub id: Option<ID>,
+    /// Name of the notation.
     #[serde(rename = "@name")]
-    name: String,
+    pub name: NCName,
+    /// Public identifier of the notation, e.g. a MIME type.
     #[serde(rename = "@public")]
-    public: String,
+    pub public: Option<Token>,
+    /// System identifier of the notation.
     #[serde(rename = "@system")]
-    system: Option<String>,
+    pub system: Option<AnyURI>,
+    #[serde(rename = "$value")]
+    body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Notation {
+    /// Extracts the optional annotation element from the notation.
+    pub fn annotation(&self) -> Option<&Annotation> {
+        self.body.as_ref()
+    }
 }
 
 /// Represents an XSD attribute group definition within the schema. This struct
@@ -644,8 +1109,37 @@ pub struct AttributeGroup {
     pub name: Option<NCName>,
     #[serde(rename = "@ref")]
     pub r#ref: Option<QName>,
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<AttributeGroupBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl AttributeGroup {
+    /// Extracts the annotation element from the attribute group.
+    pub fn annotation(&self) -> Option<&Annotation> {
+        element_from_body!(self, AttributeGroupBody::Annotation)
+    }
+
+    /// Extracts the attribute declarations of the attribute group.
+    pub fn attributes(&self) -> Vec<&Attribute> {
+        elements_from_body!(self, AttributeGroupBody::Attribute)
+    }
+
+    /// Extracts the references to other attribute groups nested in the
+    /// attribute group.
+    pub fn attribute_groups(&self) -> Vec<&AttributeGroup> {
+        elements_from_body!(self, AttributeGroupBody::AttributeGroup)
+    }
+
+    /// Extracts the attribute wildcard of the attribute group.
+    pub fn any_attribute(&self) -> Option<&AnyAttribute> {
+        element_from_body!(self, AttributeGroupBody::AnyAttribute)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -767,8 +1261,14 @@ pub struct Attribute {
     /// The body of the `xs:attribute` element can optionally contain
     /// child elements that define additional aspects of the attribute,
     /// such as an annotation element for documentation.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<AttributeBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Attribute {
@@ -865,8 +1365,14 @@ pub struct DefaultOpenContent {
     /// complex type element has no child elements explicitly declared.
     #[serde(rename = "@appliesToEmpty")]
     pub applies_to_empty: Option<bool>,
-    #[serde(rename = "$value")]
+    #[serde(rename = "$value", deserialize_with = "crate::de::children")]
     body: Vec<OpenContentBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl DefaultOpenContent {
@@ -892,7 +1398,7 @@ impl DefaultOpenContent {
     /// content, potentially loosening validation constraints. This method is useful for
     /// accessing the wildcard element definition if present within the `defaultOpenContent`.
     pub fn any(&self) -> Option<&Any> {
-        element_from_body!(self, OpenContentBody::Any)
+        element_from_body!(self, OpenContentBody::Any).map(Box::as_ref)
     }
 }
 
@@ -900,7 +1406,7 @@ impl DefaultOpenContent {
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 enum OpenContentBody {
-    Any(Any),
+    Any(Box<Any>),
     Annotation(Annotation),
 }
 
@@ -942,11 +1448,10 @@ pub struct SimpleType {
     /// Final declaration restriction for the simple type.
     ///
     /// The `@final` attribute is an optional attribute on the `xs:simpleType`
-    /// element. It specifies whether the simple type can be derived from by
-    /// restriction. When set to `true`, the simple type cannot be used as a
-    /// base type for further type restrictions.
+    /// element. It specifies the set of methods, among restriction, list
+    /// and union, by which no type may be derived from the simple type.
     #[serde(rename = "@final")]
-    pub r#final: Option<Final>,
+    pub r#final: Option<Vec<Final>>,
     /// Name of the simple type definition.
     ///
     /// The `@name` attribute is an optional attribute on the `xs:simpleType`
@@ -960,8 +1465,14 @@ pub struct SimpleType {
     /// elements that define the specific constraints and content model for
     /// the simple type. These elements can include things like restrictions,
     /// lists, unions, and built-in types.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<SimpleTypeBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl SimpleType {
@@ -1005,7 +1516,7 @@ impl SimpleType {
     ///    if found.
     ///  * `Err(String)` containing an error message if the SimpleType has
     ///    no valid content or only contains an Annotation element (this violates the XSD specification).
-    pub fn content(&self) -> Result<SimpleTypeContent, String> {
+    pub fn content(&self) -> Result<SimpleTypeContent<'_>, String> {
         for element in &self.body {
             match element {
                 SimpleTypeBody::Annotation(_) => continue,
@@ -1069,13 +1580,19 @@ pub struct Union {
     /// model of one of the types listed in `member_types`.
     #[serde(rename = "@memberTypes")]
     pub member_types: Option<Vec<QName>>,
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<UnionBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Union {
     /// Extracts the annotation element from the union body.
-
+    ///
     /// This method iterates through the body of the union and
     /// collects all elements of type [Annotation]. If found, it returns
     /// a reference to that annotation element, otherwise, it returns `None`.
@@ -1150,13 +1667,19 @@ pub struct List {
     ///   the item type is not explicitly specified in the `@itemType`
     ///   attribute. In such cases, the body can contain references to the
     ///   allowed simple type.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<ListBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl List {
     /// Extracts the annotation element from the list body.
-
+    ///
     /// This method iterates through the body of the list and
     /// collects all elements of type [Annotation]. If found, it returns
     /// a reference to that annotation element, otherwise, it returns `None`.
@@ -1222,8 +1745,14 @@ pub struct Restriction {
     /// elements that define the specific restrictions applied to the base type.
     /// These can include elements like `minLength`, `maxLength`, `pattern`,
     /// and others depending on the base type.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<RestrictionBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Restriction {
@@ -1271,6 +1800,62 @@ impl Restriction {
         elements_from_body!(self, RestrictionBody::Assert)
     }
 
+    /// Extracts the open content of a complex content restriction.
+    pub fn open_content(&self) -> Option<&OpenContent> {
+        element_from_body!(self, RestrictionBody::OpenContent)
+    }
+
+    /// Extracts the `xs:sequence` content model of a complex content
+    /// restriction.
+    pub fn sequence(&self) -> Option<&Sequence> {
+        element_from_body!(self, RestrictionBody::Sequence)
+    }
+
+    /// Extracts the `xs:choice` content model of a complex content
+    /// restriction.
+    pub fn choice(&self) -> Option<&Choice> {
+        element_from_body!(self, RestrictionBody::Choice)
+    }
+
+    /// Extracts the `xs:all` content model of a complex content restriction.
+    pub fn all(&self) -> Option<&All> {
+        element_from_body!(self, RestrictionBody::All)
+    }
+
+    /// Extracts the model group reference that forms the content model of a
+    /// complex content restriction.
+    pub fn group(&self) -> Option<&Group> {
+        element_from_body!(self, RestrictionBody::Group)
+    }
+
+    /// Returns the content model of a complex content restriction, which is
+    /// one of [Restriction::sequence], [Restriction::choice],
+    /// [Restriction::all] or [Restriction::group].
+    pub fn particle(&self) -> Option<Particle<'_>> {
+        self.body.iter().find_map(|element| match element {
+            RestrictionBody::Sequence(e) => Some(Particle::Sequence(e)),
+            RestrictionBody::Choice(e) => Some(Particle::Choice(e)),
+            RestrictionBody::Group(e) => Some(Particle::Group(e)),
+            RestrictionBody::All(e) => Some(Particle::All(e)),
+            _ => None,
+        })
+    }
+
+    /// Extracts the attribute declarations of the restriction.
+    pub fn attributes(&self) -> Vec<&Attribute> {
+        elements_from_body!(self, RestrictionBody::Attribute)
+    }
+
+    /// Extracts the attribute group references of the restriction.
+    pub fn attribute_groups(&self) -> Vec<&AttributeGroup> {
+        elements_from_body!(self, RestrictionBody::AttributeGroup)
+    }
+
+    /// Extracts the attribute wildcard of the restriction.
+    pub fn any_attribute(&self) -> Option<&AnyAttribute> {
+        element_from_body!(self, RestrictionBody::AnyAttribute)
+    }
+
     /// Extracts the list of facets associated with the restriction.
     ///
     /// This method iterates through the elements within the `body` field
@@ -1283,7 +1868,7 @@ impl Restriction {
     /// element content. Each facet enforces a specific constraint, such as
     /// minimum/maximum length, allowed patterns, or enumeration of valid
     /// values.
-    pub fn facets(&self) -> Vec<Facet> {
+    pub fn facets(&self) -> Vec<Facet<'_>> {
         let mut elements = vec![];
         for element in &self.body {
             match element {
@@ -1293,7 +1878,7 @@ impl Restriction {
                 RestrictionBody::WhiteSpace(e) => elements.push(Facet::WhiteSpace(e)),
                 RestrictionBody::SimpleType(_) => continue,
                 RestrictionBody::AnyAttribute(_) => continue,
-                RestrictionBody::MinInclusive(e) => elements.push(Facet::MinExclusive(e)),
+                RestrictionBody::MinInclusive(e) => elements.push(Facet::MinInclusive(e)),
                 RestrictionBody::MaxInclusive(e) => elements.push(Facet::MaxInclusive(e)),
                 RestrictionBody::MinExclusive(e) => elements.push(Facet::MinExclusive(e)),
                 RestrictionBody::MaxExclusive(e) => elements.push(Facet::MaxExclusive(e)),
@@ -1311,6 +1896,7 @@ impl Restriction {
                 RestrictionBody::Assertion(e) => elements.push(Facet::Assertion(e)),
                 RestrictionBody::ExplicitTimezone(e) => elements.push(Facet::ExplicitTimezone(e)),
                 RestrictionBody::Assert(_) => continue,
+                RestrictionBody::OpenContent(_) => continue,
             }
         }
         elements
@@ -1345,6 +1931,7 @@ enum RestrictionBody {
     Assertion(Assertion),
     ExplicitTimezone(ExplicitTimezone),
     Assert(Assert),
+    OpenContent(OpenContent),
 }
 
 /// Represents an `anyAttribute` element within an XSD complex type definition.
@@ -1395,14 +1982,20 @@ pub struct AnyAttribute {
     /// definition.
     #[serde(rename = "$value", default)]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl AnyAttribute {
     /// Extracts the optional annotation element associated with the "anyAttribute".
-
+    ///
     /// This method retrieves the optional `Annotation` element stored within the `body` field
     /// of the `AnyAttribute` struct. Annotations provide comments or metadata about the wildcard element.
-
+    ///
     /// If an annotation is present, this method returns a reference to the contained `Annotation`
     /// struct. Otherwise, it returns `None`.
     pub fn annotation(&self) -> Option<&Annotation> {
@@ -1410,7 +2003,7 @@ impl AnyAttribute {
     }
 }
 
-#[derive(Deserialize, Debug)]
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub enum ProcessContents {
@@ -1467,12 +2060,12 @@ pub struct ComplexType {
     /// This allows for inheritance and building complex types on top of existing ones.
     #[serde(rename = "@type")]
     pub r#type: Option<String>,
-    /// Whether default attribute applies from base type.
+    /// Whether the default attributes of the schema apply.
     ///
-    /// The `@default_attributes_apply` attribute controls whether default attribute values from
-    /// the base type are inherited by elements using this complex type. If set to `false`,
-    /// default attribute values are not inherited.
-    #[serde(rename = "@default_attributes_apply")]
+    /// The `@defaultAttributesApply` attribute controls whether the attribute group named by
+    /// the `defaultAttributes` of the schema is added to the attributes of this complex type.
+    /// If set to `false`, the default attributes do not apply.
+    #[serde(rename = "@defaultAttributesApply")]
     pub default_attributes_apply: Option<bool>,
     /// Content model definition for the complex type.
     ///
@@ -1480,8 +2073,14 @@ pub struct ComplexType {
     /// This can include various components like `All`, `Sequence`, `Choice`, `Attribute`,
     /// and others, specifying the allowed elements, attributes, and their order within the
     /// complex type definition.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<ComplexTypeBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl ComplexType {
@@ -1717,6 +2316,26 @@ impl ComplexType {
     pub fn choice(&self) -> Option<&Choice> {
         element_from_body!(self, ComplexTypeBody::Choice)
     }
+
+    /// Extracts the open content of the complex type, which allows
+    /// elements matching its wildcard to appear in addition to the elements
+    /// of the content model.
+    pub fn open_content(&self) -> Option<&OpenContent> {
+        element_from_body!(self, ComplexTypeBody::OpenContent)
+    }
+
+    /// Returns the content model of a complex type without simple or
+    /// complex content, which is one of [ComplexType::sequence],
+    /// [ComplexType::choice], [ComplexType::all] or [ComplexType::group].
+    pub fn particle(&self) -> Option<Particle<'_>> {
+        self.body.iter().find_map(|element| match element {
+            ComplexTypeBody::Sequence(e) => Some(Particle::Sequence(e)),
+            ComplexTypeBody::Choice(e) => Some(Particle::Choice(e)),
+            ComplexTypeBody::Group(e) => Some(Particle::Group(e)),
+            ComplexTypeBody::All(e) => Some(Particle::All(e)),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -1788,8 +2407,14 @@ pub struct OpenContent {
     /// The `body` vector can contain elements of type `Any` or `Annotation`. This allows you
     /// to specify wildcard elements using `Any` or provide additional comments or metadata
     /// through `Annotation` elements.
-    #[serde(rename = "$value")]
+    #[serde(rename = "$value", deserialize_with = "crate::de::children")]
     body: Vec<OpenContentBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl OpenContent {
@@ -1815,7 +2440,7 @@ impl OpenContent {
     /// content, potentially loosening validation constraints. This method is useful for
     /// accessing the wildcard element definition if present within the `openContent`.
     pub fn any(&self) -> Option<&Any> {
-        element_from_body!(self, OpenContentBody::Any)
+        element_from_body!(self, OpenContentBody::Any).map(Box::as_ref)
     }
 }
 
@@ -1845,14 +2470,26 @@ pub struct SimpleContent {
     /// Annotations provide comments or metadata. Restrictions can further constrain the allowed
     /// values for the simple content based on the underlying simple type. Extensions allow for
     /// adding custom elements within the simple content model.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<ContentBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl SimpleContent {
     pub fn annotation(&self) -> Option<&Annotation> {
         element_from_body!(self, ContentBody::Annotation)
     }
+
+    /// Returns the restriction or extension that derives the type from
+    /// its base type.
+    pub fn derivation(&self) -> Option<Derivation<'_>> {
+        derivation(&self.body)
+    }
 }
 
 /// Represents a complex content model for a complex type definition within an XSD schema.
@@ -1888,14 +2525,52 @@ pub struct ComplexContent {
     /// The `body` vector can contain elements of type `Annotation`, `Restriction`, or `Extension`.
     /// Annotations provide comments or metadata. Restrictions can further constrain the inherited
     /// content model. Extensions allow for adding custom elements within the complex content.
-    #[serde(rename = "$value")]
+    #[serde(rename = "$value", deserialize_with = "crate::de::children")]
     body: Vec<ContentBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl ComplexContent {
     pub fn annotation(&self) -> Option<&Annotation> {
         element_from_body!(self, ContentBody::Annotation)
     }
+
+    /// Returns the restriction or extension that derives the type from
+    /// its base type.
+    pub fn derivation(&self) -> Option<Derivation<'_>> {
+        derivation(&self.body)
+    }
+}
+
+/// The way a type with simple or complex content is derived from its base
+/// type.
+pub enum Derivation<'a> {
+    Restriction(&'a Restriction),
+    Extension(&'a Extension),
+}
+
+impl<'a> Derivation<'a> {
+    /// Returns the base type of the derivation, which may be absent for a
+    /// restriction that defines an anonymous simple type inline.
+    pub fn base(&self) -> Option<&'a QName> {
+        match self {
+            Derivation::Restriction(restriction) => restriction.base.as_ref(),
+            Derivation::Extension(extension) => Some(&extension.base),
+        }
+    }
+}
+
+fn derivation(body: &[ContentBody]) -> Option<Derivation<'_>> {
+    body.iter().find_map(|element| match element {
+        ContentBody::Restriction(e) => Some(Derivation::Restriction(e)),
+        ContentBody::Extension(e) => Some(Derivation::Extension(e)),
+        ContentBody::Annotation(_) => None,
+    })
 }
 
 #[derive(Deserialize, Debug)]
@@ -1907,14 +2582,51 @@ enum ContentBody {
     Extension(Extension),
 }
 
+/// Represents an XML Schema appinfo element.
+///
+/// An appinfo element carries information for applications that process
+/// the schema, such as code generators. Its content can be any XML and is
+/// kept as an [XmlFragment].
+///
+/// ```xsd
+/// <appinfo
+///   source = anyURI
+///   {any attributes with non-schema namespace . . .}>
+///   Content: ({any})*
+/// </appinfo>
+/// ```
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
-// #[serde(deny_unknown_fields)]
+#[serde(deny_unknown_fields)]
 pub struct AppInfo {
+    /// Optional source of the application information.
     #[serde(rename = "@source")]
-    source: Option<AnyURI>,
-    // #[serde(rename = "$text")]
-    // pub body: Option<Vec<String>>,
+    pub source: Option<AnyURI>,
+    #[serde(rename = "#content")]
+    content: XmlFragment,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl AppInfo {
+    /// Returns the content of the application information.
+    pub fn content(&self) -> &XmlFragment {
+        &self.content
+    }
+
+    /// Returns the text of the application information without markup.
+    pub fn text(&self) -> String {
+        self.content.text()
+    }
+
+    /// Returns the content of the application information as XML.
+    pub fn to_xml(&self) -> String {
+        self.content.to_xml()
+    }
 }
 
 /// Represents an annotation element within an XSD schema.
@@ -1947,8 +2659,26 @@ pub struct Annotation {
     /// annotation type used within the `body` vector. Common annotation types include
     /// `appinfo` for application-specific information and `documentation` for human-readable
     /// comments.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<AnnotationBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Annotation {
+    /// Extracts the `xs:appinfo` elements of the annotation.
+    pub fn app_infos(&self) -> Vec<&AppInfo> {
+        elements_from_body!(self, AnnotationBody::AppInfo)
+    }
+
+    /// Extracts the `xs:documentation` elements of the annotation.
+    pub fn documentations(&self) -> Vec<&Documentation> {
+        elements_from_body!(self, AnnotationBody::Documentation)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -1995,8 +2725,14 @@ pub struct Extension {
     /// by the extension. This can include elements like `All`, `Sequence`, `Choice`, `Attribute`,
     /// `AnyAttribute`, and others, allowing you to specify the structure and allowed components
     /// within the extension.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<ExtensionBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Extension {
@@ -2011,6 +2747,57 @@ impl Extension {
     pub fn asserts(&self) -> Vec<&Assert> {
         elements_from_body!(self, ExtensionBody::Assert)
     }
+
+    /// Extracts the `xs:sequence` appended to the content model of the base
+    /// type.
+    pub fn sequence(&self) -> Option<&Sequence> {
+        element_from_body!(self, ExtensionBody::Sequence)
+    }
+
+    /// Extracts the `xs:choice` appended to the content model of the base
+    /// type.
+    pub fn choice(&self) -> Option<&Choice> {
+        element_from_body!(self, ExtensionBody::Choice)
+    }
+
+    /// Extracts the `xs:all` content model of the extension.
+    pub fn all(&self) -> Option<&All> {
+        element_from_body!(self, ExtensionBody::All)
+    }
+
+    /// Extracts the model group reference appended to the content model of
+    /// the base type.
+    pub fn group(&self) -> Option<&Group> {
+        element_from_body!(self, ExtensionBody::Group)
+    }
+
+    /// Returns the particle the extension adds to the content model of the
+    /// base type, which is one of [Extension::sequence],
+    /// [Extension::choice], [Extension::all] or [Extension::group].
+    pub fn particle(&self) -> Option<Particle<'_>> {
+        self.body.iter().find_map(|element| match element {
+            ExtensionBody::Sequence(e) => Some(Particle::Sequence(e)),
+            ExtensionBody::Choice(e) => Some(Particle::Choice(e)),
+            ExtensionBody::Group(e) => Some(Particle::Group(e)),
+            ExtensionBody::All(e) => Some(Particle::All(e)),
+            _ => None,
+        })
+    }
+
+    /// Extracts the attribute declarations added by the extension.
+    pub fn attributes(&self) -> Vec<&Attribute> {
+        elements_from_body!(self, ExtensionBody::Attribute)
+    }
+
+    /// Extracts the attribute group references added by the extension.
+    pub fn attribute_groups(&self) -> Vec<&AttributeGroup> {
+        elements_from_body!(self, ExtensionBody::AttributeGroup)
+    }
+
+    /// Extracts the attribute wildcard of the extension.
+    pub fn any_attribute(&self) -> Option<&AnyAttribute> {
+        element_from_body!(self, ExtensionBody::AnyAttribute)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -2057,10 +2844,33 @@ pub struct Documentation {
     ///
     /// The body of the `xs:documentation` element can contain text content
     /// representing the actual documentation for the schema component. This
-    /// can be plain text, formatted markup (depending on the schema processor),
-    /// or references to external documentation resources.
-    #[serde(rename = "$value", default)]
-    pub body: Vec<String>,
+    /// can be plain text, formatted markup such as XHTML, or references to
+    /// external documentation resources.
+    #[serde(rename = "#content")]
+    content: XmlFragment,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Documentation {
+    /// Returns the content of the documentation, including any markup.
+    pub fn content(&self) -> &XmlFragment {
+        &self.content
+    }
+
+    /// Returns the text of the documentation without markup.
+    pub fn text(&self) -> String {
+        self.content.text()
+    }
+
+    /// Returns the content of the documentation as XML.
+    pub fn to_xml(&self) -> String {
+        self.content.to_xml()
+    }
 }
 
 /// Represents a `unique` element within an XSD schema.
@@ -2086,9 +2896,9 @@ pub struct Unique {
     /// Optional identifier for the unique constraint.
     #[serde(rename = "@id")]
     pub id: Option<ID>,
-    /// Name of the unique constraint.
+    /// Name of the unique constraint, absent if it references another one.
     #[serde(rename = "@name")]
-    pub name: NCName,
+    pub name: Option<NCName>,
     /// Reference to an existing unique constraint (optional).
     ///
     /// The `@ref` attribute allows you to reference a pre-defined unique constraint by its name
@@ -2103,8 +2913,14 @@ pub struct Unique {
     /// to the element(s) for which uniqueness is enforced. The `field` element identifies
     /// the specific field(s) within the selected element(s) that must be unique. This is used
     /// for inline definitions only, and is mutually exclusive with the `@ref` attribute.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<UniqueBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Unique {
@@ -2119,6 +2935,17 @@ impl Unique {
     pub fn annotation(&self) -> Option<&Annotation> {
         element_from_body!(self, UniqueBody::Annotation)
     }
+
+    /// Extracts the selector of the constraint, absent if the constraint
+    /// references another one through `@ref`.
+    pub fn selector(&self) -> Option<&Selector> {
+        element_from_body!(self, UniqueBody::Selector)
+    }
+
+    /// Extracts the fields of the constraint, in document order.
+    pub fn fields(&self) -> Vec<&Field> {
+        elements_from_body!(self, UniqueBody::Field)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -2151,14 +2978,14 @@ enum UniqueBody {
 pub struct Selector {
     /// Optional identifier for the selector element.
     #[serde(rename = "@id")]
-    id: Option<String>,
+    pub id: Option<ID>,
     /// XPath expression to identify target elements.
     ///
     /// The `@xpath` attribute is mandatory and specifies the XPath expression that selects the
     /// element(s) for which the unique constraint applies. This expression must evaluate to
     /// one or more element nodes within the schema document.
     #[serde(rename = "@xpath")]
-    xpath: String,
+    pub xpath: String,
     /// Optional default namespace for the XPath expression.
     ///
     /// The `@xpathDefaultNamespace` attribute allows you to specify a default namespace for the
@@ -2171,7 +2998,14 @@ pub struct Selector {
     /// The `body` field can optionally contain an `Annotation` element. This can be used to
     /// provide additional information or documentation about the selector and its purpose within
     /// the unique constraint definition.
+    #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Selector {
@@ -2190,16 +3024,60 @@ impl Selector {
     }
 }
 
+/// Represents a `key` element within an XSD schema.
+///
+/// A key constraint requires the selected fields to be present and unique
+/// within the scope of the element the constraint is defined on. Keys can
+/// be referenced by `keyref` constraints.
+///
+/// ```xsd
+/// <key
+///   id = ID
+///   name = NCName
+///   ref = QName
+///   {any attributes with non-schema namespace . . .}>
+///   Content: (annotation?, (selector, field+)?)
+/// </key>
+/// ```
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Key {
+    /// Optional identifier for the key constraint.
     #[serde(rename = "@id")]
-    id: Option<String>,
+    pub id: Option<ID>,
+    /// Name of the key constraint, absent if it references another one.
     #[serde(rename = "@name")]
-    name: Option<String>,
-    #[serde(rename = "$value", default)]
+    pub name: Option<NCName>,
+    /// Reference to a key constraint defined elsewhere (XSD 1.1).
+    #[serde(rename = "@ref")]
+    pub r#ref: Option<QName>,
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<KeyBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Key {
+    /// Extracts the annotation element from the key.
+    pub fn annotation(&self) -> Option<&Annotation> {
+        element_from_body!(self, KeyBody::Annotation)
+    }
+
+    /// Extracts the selector of the key, absent if the key references
+    /// another one through `@ref`.
+    pub fn selector(&self) -> Option<&Selector> {
+        element_from_body!(self, KeyBody::Selector)
+    }
+
+    /// Extracts the fields of the key, in document order.
+    pub fn fields(&self) -> Vec<&Field> {
+        elements_from_body!(self, KeyBody::Field)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -2211,18 +3089,65 @@ enum KeyBody {
     Field(Field),
 }
 
+/// Represents a `keyref` element within an XSD schema.
+///
+/// A key reference constraint requires the values of the selected fields
+/// to match the values of a `key` or `unique` constraint.
+///
+/// ```xsd
+/// <keyref
+///   id = ID
+///   name = NCName
+///   ref = QName
+///   refer = QName
+///   {any attributes with non-schema namespace . . .}>
+///   Content: (annotation?, (selector, field+)?)
+/// </keyref>
+/// ```
 #[derive(Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
 #[serde(deny_unknown_fields)]
 pub struct Keyref {
+    /// Optional identifier for the key reference constraint.
     #[serde(rename = "@id")]
-    id: Option<String>,
+    pub id: Option<ID>,
+    /// Name of the key reference constraint, absent if it references
+    /// another one.
     #[serde(rename = "@name")]
-    name: NCName,
+    pub name: Option<NCName>,
+    /// Reference to a keyref constraint defined elsewhere (XSD 1.1).
+    #[serde(rename = "@ref")]
+    pub r#ref: Option<QName>,
+    /// Name of the `key` or `unique` constraint that is referenced, absent
+    /// if the keyref references another one through `@ref`.
     #[serde(rename = "@refer")]
-    refer: QName,
-    #[serde(rename = "$value")]
+    pub refer: Option<QName>,
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<KeyrefBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Keyref {
+    /// Extracts the annotation element from the key reference.
+    pub fn annotation(&self) -> Option<&Annotation> {
+        element_from_body!(self, KeyrefBody::Annotation)
+    }
+
+    /// Extracts the selector of the key reference, absent if the key
+    /// reference references another one through `@ref`.
+    pub fn selector(&self) -> Option<&Selector> {
+        element_from_body!(self, KeyrefBody::Selector)
+    }
+
+    /// Extracts the fields of the key reference, in document order.
+    pub fn fields(&self) -> Vec<&Field> {
+        elements_from_body!(self, KeyrefBody::Field)
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -2270,13 +3195,20 @@ pub struct Field {
     /// prefixes used within the XPath expression. This can help simplify the expression and avoid
     /// the need to explicitly declare prefixes for all namespaces used.
     #[serde(rename = "@xpathDefaultNamespace")]
+    pub xpath_default_namespace: Option<AnyURI>,
     /// Optional annotation element for comments or metadata.
     ///
     /// The `body` field can optionally contain an `Annotation` element. This can be used to
     /// provide additional information or documentation about the field and its purpose within
     /// the unique constraint definition.
-    pub xpath_default_namespace: Option<AnyURI>,
+    #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Field {
@@ -2325,4 +3257,10 @@ pub struct Assert {
     /// assertion.
     #[serde(rename = "$value")]
     pub annotation: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
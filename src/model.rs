@@ -0,0 +1,1988 @@
+//! The component model of a [SchemaSet], in which the references between
+//! components are resolved.
+//!
+//! In schema documents, components refer to each other through qualified
+//! names, e.g. the `type` of an element declaration or the `base` of a
+//! restriction. A [ComponentModel] resolves all of these names once and
+//! gives every component, named or anonymous, global or local, an
+//! identifier. Handles such as [ElementDeclaration] follow the references
+//! directly, e.g. [ElementDeclaration::type_definition] returns the simple
+//! or complex type of an element.
+//!
+//! Unprefixed names in a chameleon document refer to components in the
+//! namespace the document took on. In the redefinition of a component, a
+//! reference to the component itself refers to the redefined component.
+//! The model contains the [built-in types](crate::builtins) such as
+//! `xs:string`, unless the set defines them itself, e.g. because it
+//! contains the schema for schemas.
+//!
+//! Every type but `xs:anyType` is derived from its [base
+//! type](Type::base_type) by one of the [DerivationMethod]s.
+//! [Type::derivation_chain] follows the base types up to `xs:anyType` and
+//! [Type::check_derivation] tells whether a type may be used in place of
+//! another one, taking `final` and `block` into account.
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    basics::{NCName, QName},
+    builtins::{BuiltinType, Variety, BUILTIN_TYPES},
+    error::SchemaError,
+    namespaces::{ExpandedName, NamespaceContext, XSD_NAMESPACE},
+    particles::{Any, Element, Group, IdentityConstraint, MaxOccurs, Particle},
+    set::{component_at, Component, Location, SchemaDocument, SymbolSpace, TypeDefinition},
+    span::Span,
+    wildcards::Wildcard,
+    Attribute, AttributeGroup, AttributeUse as Use, Block, ComplexType, Derivation, Final,
+    FormChoice, Notation, SchemaSet, SimpleType, SimpleTypeContent,
+};
+
+/// Defines the identifiers of the components in a [ComponentModel].
+macro_rules! ids {
+    ($($(#[$doc:meta])* $id:ident),* $(,)?) => {$(
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        pub struct $id(usize);
+
+        impl $id {
+            /// Returns the position of the component among the components
+            /// of its kind in the model.
+            pub fn index(self) -> usize {
+                self.0
+            }
+        }
+    )*};
+}
+
+ids!(
+    /// Identifies a simple or complex type definition.
+    TypeId,
+    /// Identifies an element declaration.
+    ElementId,
+    /// Identifies an attribute declaration.
+    AttributeId,
+    /// Identifies a model group definition.
+    GroupId,
+    /// Identifies an attribute group definition.
+    AttributeGroupId,
+    /// Identifies an identity constraint definition.
+    IdentityConstraintId,
+    /// Identifies a notation declaration.
+    NotationId,
+);
+
+/// Defines the handles of the components in a [ComponentModel].
+macro_rules! handles {
+    ($($(#[$doc:meta])* $handle:ident($id:ident, $entries:ident, $entry:ident)),* $(,)?) => {$(
+        $(#[$doc])*
+        #[derive(Clone, Copy)]
+        pub struct $handle<'a> {
+            model: &'a ComponentModel<'a>,
+            id: $id,
+        }
+
+        impl<'a> $handle<'a> {
+            /// Returns the identifier of the component.
+            pub fn id(self) -> $id {
+                self.id
+            }
+
+            /// Returns the model the component belongs to.
+            pub fn model(self) -> &'a ComponentModel<'a> {
+                self.model
+            }
+
+            fn entry(self) -> &'a $entry<'a> {
+                &self.model.$entries[self.id.0]
+            }
+        }
+
+        impl PartialEq for $handle<'_> {
+            fn eq(&self, other: &Self) -> bool {
+                self.id == other.id && std::ptr::eq(self.model, other.model)
+            }
+        }
+
+        impl Eq for $handle<'_> {}
+
+        impl fmt::Debug for $handle<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.debug_struct(stringify!($handle))
+                    .field("id", &self.id.0)
+                    .field("name", &self.entry().name)
+                    .finish()
+            }
+        }
+    )*};
+}
+
+handles!(
+    /// A simple or complex type definition, possibly anonymous or built-in.
+    Type(TypeId, types, TypeEntry),
+    /// A top-level or local element declaration.
+    ElementDeclaration(ElementId, elements, ElementEntry),
+    /// A top-level or local attribute declaration.
+    AttributeDeclaration(AttributeId, attributes, AttributeEntry),
+    /// A model group definition, i.e. a top-level `xs:group`.
+    ModelGroupDefinition(GroupId, groups, GroupEntry),
+    /// An attribute group definition, i.e. a top-level `xs:attributeGroup`.
+    AttributeGroupDefinition(AttributeGroupId, attribute_groups, AttributeGroupEntry),
+    /// A `xs:unique`, `xs:key` or `xs:keyref` of an element declaration.
+    IdentityConstraintDefinition(
+        IdentityConstraintId,
+        identity_constraints,
+        IdentityConstraintEntry
+    ),
+    /// A notation declaration.
+    NotationDeclaration(NotationId, notations, NotationEntry),
+);
+
+/// The kind of a model group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compositor {
+    /// `xs:sequence`
+    Sequence,
+    /// `xs:choice`
+    Choice,
+    /// `xs:all`
+    All,
+}
+
+/// A particle of a content model, with its references resolved.
+#[derive(Debug, Clone)]
+pub struct ContentParticle<'a> {
+    /// The minimum number of occurrences of the term.
+    pub min_occurs: u32,
+    /// The maximum number of occurrences of the term.
+    pub max_occurs: MaxOccurs,
+    /// What the particle matches.
+    pub term: Term<'a>,
+}
+
+/// The term of a [ContentParticle].
+#[derive(Debug, Clone)]
+pub enum Term<'a> {
+    /// A local element declaration, or the top-level one referenced by
+    /// `ref`.
+    Element(ElementId),
+    /// A reference to a model group definition.
+    Group(GroupId),
+    /// A sequence, choice or all group.
+    ModelGroup {
+        compositor: Compositor,
+        particles: Vec<ContentParticle<'a>>,
+    },
+    /// An element wildcard.
+    Any(&'a Any),
+}
+
+/// An attribute of a complex type or attribute group definition, given by
+/// an `xs:attribute` that either declares a local attribute or refers to a
+/// top-level one.
+#[derive(Clone, Copy)]
+pub struct AttributeUse<'a> {
+    model: &'a ComponentModel<'a>,
+    entry: &'a UseEntry<'a>,
+}
+
+impl<'a> AttributeUse<'a> {
+    /// Returns the `xs:attribute` element of the attribute use.
+    pub fn element(self) -> &'a Attribute {
+        self.entry.element
+    }
+
+    /// Returns the declaration of the attribute, which is the top-level
+    /// declaration for a `ref`.
+    pub fn attribute(self) -> AttributeDeclaration<'a> {
+        self.model.attribute_by_id(self.entry.attribute)
+    }
+
+    /// Returns whether the attribute must be present.
+    pub fn is_required(self) -> bool {
+        matches!(self.entry.element.r#use, Some(Use::Required))
+    }
+
+    /// Returns whether the attribute must not be present, which removes an
+    /// attribute use of the base type in a restriction.
+    pub fn is_prohibited(self) -> bool {
+        matches!(self.entry.element.r#use, Some(Use::Prohibited))
+    }
+
+    /// Returns the default value of the attribute, which may be given by
+    /// the use or by the referenced declaration.
+    pub fn default(self) -> Option<&'a str> {
+        let declaration = self.attribute().declaration();
+        self.entry
+            .element
+            .default
+            .as_deref()
+            .or(declaration.default.as_deref())
+    }
+
+    /// Returns the fixed value of the attribute, which may be given by the
+    /// use or by the referenced declaration.
+    pub fn fixed(self) -> Option<&'a str> {
+        let declaration = self.attribute().declaration();
+        self.entry
+            .element
+            .fixed
+            .as_deref()
+            .or(declaration.fixed.as_deref())
+    }
+}
+
+/// The way a type is derived from its base type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DerivationMethod {
+    /// `xs:restriction`, which is also the method of a complex type that
+    /// does not name a base type and thus restricts `xs:anyType`.
+    Restriction,
+    /// `xs:extension`
+    Extension,
+    /// `xs:list`, which derives from `xs:anySimpleType`.
+    List,
+    /// `xs:union`, which derives from `xs:anySimpleType`.
+    Union,
+}
+
+impl DerivationMethod {
+    const ALL: [DerivationMethod; 4] = [
+        DerivationMethod::Restriction,
+        DerivationMethod::Extension,
+        DerivationMethod::List,
+        DerivationMethod::Union,
+    ];
+
+    pub(crate) fn from_final(value: &Final) -> &'static [DerivationMethod] {
+        match value {
+            Final::All => &Self::ALL,
+            Final::Restriction => &[DerivationMethod::Restriction],
+            Final::Extension => &[DerivationMethod::Extension],
+            Final::List => &[DerivationMethod::List],
+            Final::Union => &[DerivationMethod::Union],
+        }
+    }
+
+    pub(crate) fn from_block(value: &Block) -> &'static [DerivationMethod] {
+        match value {
+            Block::All => &Self::ALL[..2],
+            Block::Restriction => &[DerivationMethod::Restriction],
+            Block::Extension => &[DerivationMethod::Extension],
+            Block::Substitution => &[],
+        }
+    }
+}
+
+/// A step in the derivation of a type from one of its ancestors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DerivationStep<'a> {
+    /// The type derived in this step.
+    pub derived: Type<'a>,
+    /// How the type is derived.
+    pub method: DerivationMethod,
+    /// The base type of the derived type.
+    pub base: Type<'a>,
+}
+
+/// Why [Type::check_derivation] does not allow a type to be used in place
+/// of another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerivationConflict<'a> {
+    /// The type is not derived from the other one.
+    NotDerived,
+    /// The base type of the step does not allow derivations by the method
+    /// of the step through its `final` or the `finalDefault` of its schema.
+    Final(DerivationStep<'a>),
+    /// The method of the step is blocked, either by the caller or by the
+    /// `block` of the type being replaced or the `blockDefault` of its
+    /// schema.
+    Blocked(DerivationStep<'a>),
+}
+
+impl fmt::Debug for AttributeUse<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AttributeUse")
+            .field("attribute", &self.attribute())
+            .finish()
+    }
+}
+
+#[derive(Debug)]
+struct TypeEntry<'a> {
+    name: Option<ExpandedName>,
+    document: Option<usize>,
+    definition: Option<TypeDefinition<'a>>,
+    builtin: Option<&'static BuiltinType>,
+    base: Option<TypeId>,
+    method: Option<DerivationMethod>,
+    item_type: Option<TypeId>,
+    member_types: Vec<TypeId>,
+    particle: Option<ContentParticle<'a>>,
+    attribute_uses: Vec<UseEntry<'a>>,
+    attribute_groups: Vec<AttributeGroupId>,
+    attribute_wildcard: Option<Wildcard>,
+    default_attributes: Option<AttributeGroupId>,
+    simple_content_type: Option<TypeId>,
+}
+
+impl<'a> TypeEntry<'a> {
+    fn new(
+        name: Option<ExpandedName>,
+        document: Option<usize>,
+        definition: Option<TypeDefinition<'a>>,
+    ) -> Self {
+        TypeEntry {
+            name,
+            document,
+            definition,
+            builtin: None,
+            base: None,
+            method: None,
+            item_type: None,
+            member_types: vec![],
+            particle: None,
+            attribute_uses: vec![],
+            attribute_groups: vec![],
+            attribute_wildcard: None,
+            default_attributes: None,
+            simple_content_type: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ElementEntry<'a> {
+    name: ExpandedName,
+    document: usize,
+    declaration: &'a Element,
+    global: bool,
+    type_definition: TypeId,
+    /// The type of each alternative, `None` for the type of the element.
+    alternatives: Vec<Option<TypeId>>,
+    substitution_groups: Vec<ElementId>,
+    /// The elements whose substitution groups include this one.
+    members: Vec<ElementId>,
+    identity_constraints: Vec<IdentityConstraintId>,
+}
+
+#[derive(Debug)]
+struct AttributeEntry<'a> {
+    name: ExpandedName,
+    document: usize,
+    declaration: &'a Attribute,
+    global: bool,
+    type_definition: TypeId,
+}
+
+#[derive(Debug)]
+struct UseEntry<'a> {
+    element: &'a Attribute,
+    attribute: AttributeId,
+}
+
+#[derive(Debug)]
+struct GroupEntry<'a> {
+    name: ExpandedName,
+    document: usize,
+    definition: &'a Group,
+    particle: Option<ContentParticle<'a>>,
+}
+
+#[derive(Debug)]
+struct AttributeGroupEntry<'a> {
+    name: ExpandedName,
+    document: usize,
+    definition: &'a AttributeGroup,
+    attribute_uses: Vec<UseEntry<'a>>,
+    attribute_groups: Vec<AttributeGroupId>,
+    attribute_wildcard: Option<Wildcard>,
+}
+
+#[derive(Debug)]
+struct IdentityConstraintEntry<'a> {
+    name: Option<ExpandedName>,
+    document: usize,
+    constraint: IdentityConstraint<'a>,
+    element: ElementId,
+    reference: Option<IdentityConstraintId>,
+    refer: Option<IdentityConstraintId>,
+}
+
+#[derive(Debug)]
+struct NotationEntry<'a> {
+    name: ExpandedName,
+    document: usize,
+    declaration: &'a Notation,
+}
+
+/// The components of a [SchemaSet] with all references between them
+/// resolved, see the [module documentation](self).
+///
+/// A model is built by [SchemaSet::model] and borrows the set.
+#[derive(Debug)]
+pub struct ComponentModel<'a> {
+    set: &'a SchemaSet,
+    types: Vec<TypeEntry<'a>>,
+    elements: Vec<ElementEntry<'a>>,
+    attributes: Vec<AttributeEntry<'a>>,
+    groups: Vec<GroupEntry<'a>>,
+    attribute_groups: Vec<AttributeGroupEntry<'a>>,
+    identity_constraints: Vec<IdentityConstraintEntry<'a>>,
+    notations: Vec<NotationEntry<'a>>,
+    names: BTreeMap<(SymbolSpace, ExpandedName), usize>,
+    any_type: TypeId,
+    any_simple_type: TypeId,
+    any_type_wildcard: Any,
+}
+
+impl<'a> ComponentModel<'a> {
+    /// Returns the set the model was built from.
+    pub fn set(&self) -> &'a SchemaSet {
+        self.set
+    }
+
+    /// Looks up a top-level type definition, or a built-in type that is
+    /// referenced in the set.
+    pub fn type_definition(&self, name: &ExpandedName) -> Option<Type<'_>> {
+        self.lookup(SymbolSpace::Type, name)
+            .map(|id| self.type_definition_by_id(TypeId(id)))
+    }
+
+    /// Looks up a top-level element declaration.
+    pub fn element(&self, name: &ExpandedName) -> Option<ElementDeclaration<'_>> {
+        self.lookup(SymbolSpace::Element, name)
+            .map(|id| self.element_by_id(ElementId(id)))
+    }
+
+    /// Looks up a top-level attribute declaration.
+    pub fn attribute(&self, name: &ExpandedName) -> Option<AttributeDeclaration<'_>> {
+        self.lookup(SymbolSpace::Attribute, name)
+            .map(|id| self.attribute_by_id(AttributeId(id)))
+    }
+
+    /// Looks up a model group definition.
+    pub fn group(&self, name: &ExpandedName) -> Option<ModelGroupDefinition<'_>> {
+        self.lookup(SymbolSpace::Group, name)
+            .map(|id| self.group_by_id(GroupId(id)))
+    }
+
+    /// Looks up an attribute group definition.
+    pub fn attribute_group(&self, name: &ExpandedName) -> Option<AttributeGroupDefinition<'_>> {
+        self.lookup(SymbolSpace::AttributeGroup, name)
+            .map(|id| self.attribute_group_by_id(AttributeGroupId(id)))
+    }
+
+    /// Looks up a notation declaration.
+    pub fn notation(&self, name: &ExpandedName) -> Option<NotationDeclaration<'_>> {
+        self.lookup(SymbolSpace::Notation, name)
+            .map(|id| self.notation_by_id(NotationId(id)))
+    }
+
+    /// Looks up an identity constraint definition.
+    pub fn identity_constraint(
+        &self,
+        name: &ExpandedName,
+    ) -> Option<IdentityConstraintDefinition<'_>> {
+        self.lookup(SymbolSpace::IdentityConstraint, name)
+            .map(|id| self.identity_constraint_by_id(IdentityConstraintId(id)))
+    }
+
+    /// Returns `xs:anyType`, the root of the type hierarchy.
+    pub fn any_type(&self) -> Type<'_> {
+        self.type_definition_by_id(self.any_type)
+    }
+
+    /// Returns `xs:anySimpleType`, the base type of all simple types.
+    pub fn any_simple_type(&self) -> Type<'_> {
+        self.type_definition_by_id(self.any_simple_type)
+    }
+
+    /// Returns the wildcard in the content of the built-in `xs:anyType`.
+    pub(crate) fn any_type_wildcard(&self) -> &Any {
+        &self.any_type_wildcard
+    }
+
+    /// Returns the type definition with the identifier `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` belongs to another model.
+    pub fn type_definition_by_id(&self, id: TypeId) -> Type<'_> {
+        assert!(id.0 < self.types.len(), "no type definition {}", id.0);
+        Type { model: self, id }
+    }
+
+    /// Returns the element declaration with the identifier `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` belongs to another model.
+    pub fn element_by_id(&self, id: ElementId) -> ElementDeclaration<'_> {
+        assert!(
+            id.0 < self.elements.len(),
+            "no element declaration {}",
+            id.0
+        );
+        ElementDeclaration { model: self, id }
+    }
+
+    /// Returns the attribute declaration with the identifier `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` belongs to another model.
+    pub fn attribute_by_id(&self, id: AttributeId) -> AttributeDeclaration<'_> {
+        assert!(id.0 < self.attributes.len(), "no attribute {}", id.0);
+        AttributeDeclaration { model: self, id }
+    }
+
+    /// Returns the model group definition with the identifier `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` belongs to another model.
+    pub fn group_by_id(&self, id: GroupId) -> ModelGroupDefinition<'_> {
+        assert!(id.0 < self.groups.len(), "no group {}", id.0);
+        ModelGroupDefinition { model: self, id }
+    }
+
+    /// Returns the attribute group definition with the identifier `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` belongs to another model.
+    pub fn attribute_group_by_id(&self, id: AttributeGroupId) -> AttributeGroupDefinition<'_> {
+        assert!(
+            id.0 < self.attribute_groups.len(),
+            "no attribute group {}",
+            id.0
+        );
+        AttributeGroupDefinition { model: self, id }
+    }
+
+    /// Returns the identity constraint definition with the identifier
+    /// `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` belongs to another model.
+    pub fn identity_constraint_by_id(
+        &self,
+        id: IdentityConstraintId,
+    ) -> IdentityConstraintDefinition<'_> {
+        assert!(
+            id.0 < self.identity_constraints.len(),
+            "no identity constraint {}",
+            id.0
+        );
+        IdentityConstraintDefinition { model: self, id }
+    }
+
+    /// Returns the notation declaration with the identifier `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` belongs to another model.
+    pub fn notation_by_id(&self, id: NotationId) -> NotationDeclaration<'_> {
+        assert!(id.0 < self.notations.len(), "no notation {}", id.0);
+        NotationDeclaration { model: self, id }
+    }
+
+    /// Iterates over all type definitions, including anonymous and
+    /// built-in ones.
+    pub fn type_definitions(&self) -> impl Iterator<Item = Type<'_>> {
+        (0..self.types.len()).map(|id| self.type_definition_by_id(TypeId(id)))
+    }
+
+    /// Iterates over all element declarations, including local ones.
+    pub fn elements(&self) -> impl Iterator<Item = ElementDeclaration<'_>> {
+        (0..self.elements.len()).map(|id| self.element_by_id(ElementId(id)))
+    }
+
+    /// Iterates over all attribute declarations, including local ones.
+    pub fn attributes(&self) -> impl Iterator<Item = AttributeDeclaration<'_>> {
+        (0..self.attributes.len()).map(|id| self.attribute_by_id(AttributeId(id)))
+    }
+
+    /// Iterates over all model group definitions.
+    pub fn groups(&self) -> impl Iterator<Item = ModelGroupDefinition<'_>> {
+        (0..self.groups.len()).map(|id| self.group_by_id(GroupId(id)))
+    }
+
+    /// Iterates over all attribute group definitions.
+    pub fn attribute_groups(&self) -> impl Iterator<Item = AttributeGroupDefinition<'_>> {
+        (0..self.attribute_groups.len()).map(|id| self.attribute_group_by_id(AttributeGroupId(id)))
+    }
+
+    /// Iterates over all identity constraint definitions.
+    pub fn identity_constraints(&self) -> impl Iterator<Item = IdentityConstraintDefinition<'_>> {
+        (0..self.identity_constraints.len())
+            .map(|id| self.identity_constraint_by_id(IdentityConstraintId(id)))
+    }
+
+    /// Iterates over all notation declarations.
+    pub fn notations(&self) -> impl Iterator<Item = NotationDeclaration<'_>> {
+        (0..self.notations.len()).map(|id| self.notation_by_id(NotationId(id)))
+    }
+
+    fn lookup(&self, space: SymbolSpace, name: &ExpandedName) -> Option<usize> {
+        self.names.get(&(space, name.clone())).copied()
+    }
+
+    fn document(&self, index: usize) -> &'a SchemaDocument {
+        &self.set.documents()[index]
+    }
+
+    fn uses(&'a self, entries: &'a [UseEntry<'a>]) -> Vec<AttributeUse<'a>> {
+        let model = self;
+        entries
+            .iter()
+            .map(|entry| AttributeUse { model, entry })
+            .collect()
+    }
+}
+
+impl<'a> Type<'a> {
+    /// Returns the name of the type, or `None` for an anonymous type.
+    pub fn name(self) -> Option<&'a ExpandedName> {
+        self.entry().name.as_ref()
+    }
+
+    /// Returns the definition of the type in its schema document, or `None`
+    /// for a built-in type.
+    pub fn definition(self) -> Option<TypeDefinition<'a>> {
+        self.entry().definition
+    }
+
+    /// Returns the definition of a simple type.
+    pub fn simple_type(self) -> Option<&'a SimpleType> {
+        match self.definition()? {
+            TypeDefinition::Simple(e) => Some(e),
+            TypeDefinition::Complex(_) => None,
+        }
+    }
+
+    /// Returns the definition of a complex type.
+    pub fn complex_type(self) -> Option<&'a ComplexType> {
+        match self.definition()? {
+            TypeDefinition::Complex(e) => Some(e),
+            TypeDefinition::Simple(_) => None,
+        }
+    }
+
+    /// Returns the description of a built-in type.
+    pub fn builtin(self) -> Option<&'static BuiltinType> {
+        self.entry().builtin
+    }
+
+    /// Returns whether the type is a built-in type.
+    pub fn is_builtin(self) -> bool {
+        self.entry().builtin.is_some()
+    }
+
+    /// Returns the document that defines the type, or `None` for a
+    /// built-in type.
+    pub fn document(self) -> Option<&'a SchemaDocument> {
+        self.entry()
+            .document
+            .map(|index| self.model.document(index))
+    }
+
+    /// Returns the base type, from which the type is derived by
+    /// restriction, extension, list or union, or `None` for `xs:anyType`.
+    pub fn base_type(self) -> Option<Type<'a>> {
+        let id = self.entry().base?;
+        Some(self.model.type_definition_by_id(id))
+    }
+
+    /// Returns how the type is derived from its base type, or `None` for
+    /// `xs:anyType`.
+    pub fn derivation_method(self) -> Option<DerivationMethod> {
+        self.entry().method
+    }
+
+    /// Returns the steps from the type up to `xs:anyType`, starting with
+    /// the derivation of the type from its base type.
+    ///
+    /// The chain stops early if a type is, directly or indirectly, derived
+    /// from itself.
+    pub fn derivation_chain(self) -> Vec<DerivationStep<'a>> {
+        let mut chain = vec![];
+        let mut seen = HashSet::from([self.id]);
+        let mut derived = self;
+        while let (Some(base), Some(method)) = (derived.base_type(), derived.derivation_method()) {
+            chain.push(DerivationStep {
+                derived,
+                method,
+                base,
+            });
+            if !seen.insert(base.id) {
+                break;
+            }
+            derived = base;
+        }
+        chain
+    }
+
+    /// Returns the steps from the type up to `base`, or `None` if the type
+    /// is not derived from `base`. The steps are empty if `base` is the
+    /// type itself.
+    pub fn derivation_path(self, base: Type<'a>) -> Option<Vec<DerivationStep<'a>>> {
+        if self == base {
+            return Some(vec![]);
+        }
+        let mut chain = self.derivation_chain();
+        let end = chain.iter().position(|step| step.base == base)?;
+        chain.truncate(end + 1);
+        Some(chain)
+    }
+
+    /// Returns whether the type is `base` or derived from it, regardless
+    /// of `final` and `block`.
+    pub fn is_derived_from(self, base: Type<'a>) -> bool {
+        self.derivation_path(base).is_some()
+    }
+
+    /// Returns the types that are derived from the type, directly or
+    /// indirectly, in the order of their identifiers.
+    pub fn derived_types(self) -> Vec<Type<'a>> {
+        let types = self.model.type_definitions();
+        types
+            .filter(|derived| *derived != self && derived.is_derived_from(self))
+            .collect()
+    }
+
+    /// Returns the methods by which no type may be derived from the type,
+    /// given by its `final` or else by the `finalDefault` of its schema.
+    pub fn final_methods(self) -> Vec<DerivationMethod> {
+        let Some(definition) = self.definition() else {
+            return vec![];
+        };
+        let default = || {
+            let schema = self.document().map(|document| document.schema());
+            schema.and_then(|schema| schema.final_default.as_ref())
+        };
+        let (values, complex) = match definition {
+            TypeDefinition::Simple(e) => (&e.r#final, false),
+            TypeDefinition::Complex(e) => (&e.r#final, true),
+        };
+        let mut methods: Vec<_> = values
+            .as_ref()
+            .or_else(default)
+            .into_iter()
+            .flatten()
+            .flat_map(DerivationMethod::from_final)
+            .copied()
+            .filter(|method| {
+                !complex
+                    || matches!(
+                        method,
+                        DerivationMethod::Restriction | DerivationMethod::Extension
+                    )
+            })
+            .collect();
+        methods.sort();
+        methods.dedup();
+        methods
+    }
+
+    /// Returns the methods of the derived types that may not be used in
+    /// place of a complex type, given by its `block` or else by the
+    /// `blockDefault` of its schema.
+    pub fn blocked_methods(self) -> Vec<DerivationMethod> {
+        let Some(e) = self.complex_type() else {
+            return vec![];
+        };
+        let block = e.block.as_ref().or_else(|| {
+            let schema = self.document().map(|document| document.schema());
+            schema.and_then(|schema| schema.block_default.as_ref())
+        });
+        let mut methods: Vec<_> = block
+            .into_iter()
+            .flatten()
+            .flat_map(DerivationMethod::from_block)
+            .copied()
+            .collect();
+        methods.sort();
+        methods.dedup();
+        methods
+    }
+
+    /// Checks whether the type may be used in place of `base`, e.g. through
+    /// `xsi:type` or a substitution group, and returns the steps from the
+    /// type up to `base`.
+    ///
+    /// The derivation is not allowed if a step uses a method that its base
+    /// type is final for, or a method that is in `blocked` or in the
+    /// [blocked methods](Type::blocked_methods) of `base`. `blocked`
+    /// typically holds the methods blocked by an element declaration.
+    pub fn check_derivation(
+        self,
+        base: Type<'a>,
+        blocked: &[DerivationMethod],
+    ) -> Result<Vec<DerivationStep<'a>>, DerivationConflict<'a>> {
+        let path = self
+            .derivation_path(base)
+            .ok_or(DerivationConflict::NotDerived)?;
+        if let Some(step) = path
+            .iter()
+            .find(|step| step.base.final_methods().contains(&step.method))
+        {
+            return Err(DerivationConflict::Final(*step));
+        }
+        let blocked_by_base = base.blocked_methods();
+        if let Some(step) = path
+            .iter()
+            .find(|step| blocked.contains(&step.method) || blocked_by_base.contains(&step.method))
+        {
+            return Err(DerivationConflict::Blocked(*step));
+        }
+        Ok(path)
+    }
+
+    /// Returns the item type of a list type.
+    pub fn item_type(self) -> Option<Type<'a>> {
+        let id = self.entry().item_type?;
+        Some(self.model.type_definition_by_id(id))
+    }
+
+    /// Returns the member types of a union type, those of `memberTypes`
+    /// followed by the anonymous ones.
+    pub fn member_types(self) -> Vec<Type<'a>> {
+        let model = self.model;
+        let members = self.entry().member_types.iter();
+        members.map(|id| model.type_definition_by_id(*id)).collect()
+    }
+
+    /// Returns the particle given by a complex type definition, either
+    /// directly or in the restriction or extension of its complex content.
+    ///
+    /// For an extension, this is the particle added to the content of the
+    /// base type.
+    pub fn particle(self) -> Option<&'a ContentParticle<'a>> {
+        self.entry().particle.as_ref()
+    }
+
+    /// Returns the attribute uses given by a complex type definition,
+    /// either directly or in the restriction or extension of its content.
+    pub fn attribute_uses(self) -> Vec<AttributeUse<'a>> {
+        self.model.uses(&self.entry().attribute_uses)
+    }
+
+    /// Returns the attribute groups referenced by a complex type
+    /// definition, either directly or in the restriction or extension of
+    /// its content.
+    pub fn attribute_groups(self) -> Vec<AttributeGroupDefinition<'a>> {
+        let model = self.model;
+        let groups = self.entry().attribute_groups.iter();
+        groups.map(|id| model.attribute_group_by_id(*id)).collect()
+    }
+
+    /// Returns the wildcard given by the `xs:anyAttribute` of a complex
+    /// type definition, either directly or in the restriction or extension
+    /// of its content.
+    pub fn attribute_wildcard(self) -> Option<&'a Wildcard> {
+        self.entry().attribute_wildcard.as_ref()
+    }
+
+    /// Returns the attribute group named by the `defaultAttributes` of the
+    /// schema of a complex type definition, unless the type sets
+    /// `defaultAttributesApply` to `false`.
+    pub fn default_attributes(self) -> Option<AttributeGroupDefinition<'a>> {
+        let id = self.entry().default_attributes?;
+        Some(self.model.attribute_group_by_id(id))
+    }
+
+    /// Returns the simple type defined by an `xs:simpleType` in the
+    /// restriction of the simple content of a complex type definition.
+    pub fn simple_content_type(self) -> Option<Type<'a>> {
+        let id = self.entry().simple_content_type?;
+        Some(self.model.type_definition_by_id(id))
+    }
+}
+
+impl<'a> ElementDeclaration<'a> {
+    /// Returns the name of the element, qualified according to its `form`
+    /// for a local element.
+    pub fn name(self) -> &'a ExpandedName {
+        &self.entry().name
+    }
+
+    /// Returns the `xs:element` that declares the element.
+    pub fn declaration(self) -> &'a Element {
+        self.entry().declaration
+    }
+
+    /// Returns whether the element is declared at the top level of a
+    /// schema.
+    pub fn is_global(self) -> bool {
+        self.entry().global
+    }
+
+    /// Returns the document that declares the element.
+    pub fn document(self) -> &'a SchemaDocument {
+        self.model.document(self.entry().document)
+    }
+
+    /// Returns the type of the element.
+    ///
+    /// This is the type given by `type` or defined inline. Otherwise it is
+    /// the type of the head of the element's substitution group, if any,
+    /// and `xs:anyType` else.
+    pub fn type_definition(self) -> Type<'a> {
+        self.model
+            .type_definition_by_id(self.entry().type_definition)
+    }
+
+    /// Returns the types of the `xs:alternative`s of the element, in
+    /// document order.
+    pub fn alternative_types(self) -> Vec<Type<'a>> {
+        let entry = self.entry();
+        let types = entry.alternatives.iter();
+        types
+            .map(|id| {
+                let id = id.unwrap_or(entry.type_definition);
+                self.model.type_definition_by_id(id)
+            })
+            .collect()
+    }
+
+    /// Returns the heads of the substitution groups the element belongs to.
+    pub fn substitution_group_affiliations(self) -> Vec<ElementDeclaration<'a>> {
+        let model = self.model;
+        let heads = self.entry().substitution_groups.iter();
+        heads.map(|id| model.element_by_id(*id)).collect()
+    }
+
+    /// Returns the elements that name the element as the head of one of
+    /// their substitution groups.
+    pub fn substitution_group_members(self) -> Vec<ElementDeclaration<'a>> {
+        let model = self.model;
+        let members = self.entry().members.iter();
+        members.map(|id| model.element_by_id(*id)).collect()
+    }
+
+    /// Returns the substitution group headed by the element: the element
+    /// itself followed by its direct and indirect members, regardless of
+    /// `abstract` and `block`.
+    pub fn substitution_group(self) -> Vec<ElementDeclaration<'a>> {
+        let mut group = vec![self];
+        let mut next = 0;
+        while let Some(element) = group.get(next) {
+            for member in element.substitution_group_members() {
+                if !group.contains(&member) {
+                    group.push(member);
+                }
+            }
+            next += 1;
+        }
+        group
+    }
+
+    /// Returns the elements that may appear where the element is
+    /// referenced, in the order of [substitution_group](Self::substitution_group).
+    ///
+    /// These are the members of the substitution group that are not
+    /// abstract, unless the element blocks substitutions, and whose types
+    /// are derived from the type of the element by methods that neither the
+    /// element nor its type block. The element itself is included unless it
+    /// is abstract.
+    pub fn substitutable_elements(self) -> Vec<ElementDeclaration<'a>> {
+        if self.blocks_substitution() {
+            return if self.is_abstract() {
+                vec![]
+            } else {
+                vec![self]
+            };
+        }
+        let head_type = self.type_definition();
+        let blocked = self.blocked_methods();
+        let group = self.substitution_group().into_iter();
+        group
+            .filter(|member| !member.is_abstract())
+            .filter(|member| {
+                let member_type = member.type_definition();
+                *member == self || member_type.check_derivation(head_type, &blocked).is_ok()
+            })
+            .collect()
+    }
+
+    /// Returns whether the element is abstract, i.e. may only appear
+    /// through members of its substitution group.
+    pub fn is_abstract(self) -> bool {
+        self.entry().declaration.r#abstract.unwrap_or(false)
+    }
+
+    /// Returns whether no element may substitute the element, given by its
+    /// `block` or else by the `blockDefault` of its schema.
+    pub fn blocks_substitution(self) -> bool {
+        self.block()
+            .any(|block| matches!(block, Block::All | Block::Substitution))
+    }
+
+    /// Returns the derivation methods of the types that may not be used in
+    /// place of the type of the element, through a substitution group or
+    /// `xsi:type`, given by its `block` or else by the `blockDefault` of its
+    /// schema.
+    pub fn blocked_methods(self) -> Vec<DerivationMethod> {
+        let mut methods: Vec<_> = self
+            .block()
+            .flat_map(DerivationMethod::from_block)
+            .copied()
+            .collect();
+        methods.sort();
+        methods.dedup();
+        methods
+    }
+
+    fn block(self) -> impl Iterator<Item = &'a Block> {
+        let declaration = self.entry().declaration;
+        let schema = self.document().schema();
+        let block = match &declaration.block {
+            Some(block) => block.as_slice(),
+            None => schema.block_default.as_deref().unwrap_or_default(),
+        };
+        block.iter()
+    }
+
+    /// Returns the identity constraints defined on the element.
+    pub fn identity_constraints(self) -> Vec<IdentityConstraintDefinition<'a>> {
+        let model = self.model;
+        let constraints = self.entry().identity_constraints.iter();
+        constraints
+            .map(|id| model.identity_constraint_by_id(*id))
+            .collect()
+    }
+}
+
+impl<'a> AttributeDeclaration<'a> {
+    /// Returns the name of the attribute, qualified according to its
+    /// `form` for a local attribute.
+    pub fn name(self) -> &'a ExpandedName {
+        &self.entry().name
+    }
+
+    /// Returns the `xs:attribute` that declares the attribute.
+    pub fn declaration(self) -> &'a Attribute {
+        self.entry().declaration
+    }
+
+    /// Returns whether the attribute is declared at the top level of a
+    /// schema.
+    pub fn is_global(self) -> bool {
+        self.entry().global
+    }
+
+    /// Returns the document that declares the attribute.
+    pub fn document(self) -> &'a SchemaDocument {
+        self.model.document(self.entry().document)
+    }
+
+    /// Returns the simple type of the attribute, `xs:anySimpleType` if it
+    /// has none.
+    pub fn type_definition(self) -> Type<'a> {
+        self.model
+            .type_definition_by_id(self.entry().type_definition)
+    }
+}
+
+impl<'a> ModelGroupDefinition<'a> {
+    /// Returns the name of the group.
+    pub fn name(self) -> &'a ExpandedName {
+        &self.entry().name
+    }
+
+    /// Returns the `xs:group` that defines the group.
+    pub fn definition(self) -> &'a Group {
+        self.entry().definition
+    }
+
+    /// Returns the document that defines the group.
+    pub fn document(self) -> &'a SchemaDocument {
+        self.model.document(self.entry().document)
+    }
+
+    /// Returns the sequence, choice or all group of the definition.
+    pub fn particle(self) -> Option<&'a ContentParticle<'a>> {
+        self.entry().particle.as_ref()
+    }
+}
+
+impl<'a> AttributeGroupDefinition<'a> {
+    /// Returns the name of the attribute group.
+    pub fn name(self) -> &'a ExpandedName {
+        &self.entry().name
+    }
+
+    /// Returns the `xs:attributeGroup` that defines the attribute group.
+    pub fn definition(self) -> &'a AttributeGroup {
+        self.entry().definition
+    }
+
+    /// Returns the document that defines the attribute group.
+    pub fn document(self) -> &'a SchemaDocument {
+        self.model.document(self.entry().document)
+    }
+
+    /// Returns the attribute uses of the attribute group itself.
+    pub fn attribute_uses(self) -> Vec<AttributeUse<'a>> {
+        self.model.uses(&self.entry().attribute_uses)
+    }
+
+    /// Returns the attribute groups referenced by the attribute group.
+    pub fn attribute_groups(self) -> Vec<AttributeGroupDefinition<'a>> {
+        let model = self.model;
+        let groups = self.entry().attribute_groups.iter();
+        groups.map(|id| model.attribute_group_by_id(*id)).collect()
+    }
+
+    /// Returns the wildcard given by the `xs:anyAttribute` of the attribute
+    /// group itself.
+    pub fn attribute_wildcard(self) -> Option<&'a Wildcard> {
+        self.entry().attribute_wildcard.as_ref()
+    }
+}
+
+impl<'a> IdentityConstraintDefinition<'a> {
+    /// Returns the name of the identity constraint, or `None` for a key
+    /// that refers to another key.
+    pub fn name(self) -> Option<&'a ExpandedName> {
+        self.entry().name.as_ref()
+    }
+
+    /// Returns the `xs:unique`, `xs:key` or `xs:keyref` that defines the
+    /// identity constraint.
+    pub fn constraint(self) -> IdentityConstraint<'a> {
+        self.entry().constraint
+    }
+
+    /// Returns the document that defines the identity constraint.
+    pub fn document(self) -> &'a SchemaDocument {
+        self.model.document(self.entry().document)
+    }
+
+    /// Returns the element declaration the identity constraint is defined
+    /// on.
+    pub fn element(self) -> ElementDeclaration<'a> {
+        self.model.element_by_id(self.entry().element)
+    }
+
+    /// Returns the identity constraint referenced through `ref`.
+    pub fn reference(self) -> Option<IdentityConstraintDefinition<'a>> {
+        let id = self.entry().reference?;
+        Some(self.model.identity_constraint_by_id(id))
+    }
+
+    /// Returns the key or unique constraint referenced by the `refer` of a
+    /// keyref.
+    pub fn referenced_key(self) -> Option<IdentityConstraintDefinition<'a>> {
+        let id = self.entry().refer?;
+        Some(self.model.identity_constraint_by_id(id))
+    }
+}
+
+impl<'a> NotationDeclaration<'a> {
+    /// Returns the name of the notation.
+    pub fn name(self) -> &'a ExpandedName {
+        &self.entry().name
+    }
+
+    /// Returns the `xs:notation` that declares the notation.
+    pub fn declaration(self) -> &'a Notation {
+        self.entry().declaration
+    }
+
+    /// Returns the document that declares the notation.
+    pub fn document(self) -> &'a SchemaDocument {
+        self.model.document(self.entry().document)
+    }
+}
+
+/// Builds the component model of a set.
+///
+/// # Errors
+///
+/// Returns [SchemaError::UnresolvedReference] if a name does not refer to
+/// a component of the expected kind.
+pub(crate) fn build(set: &SchemaSet) -> Result<ComponentModel<'_>, SchemaError> {
+    let mut builder = Builder {
+        set,
+        types: vec![],
+        elements: vec![],
+        attributes: vec![],
+        groups: vec![],
+        attribute_groups: vec![],
+        identity_constraints: vec![],
+        notations: vec![],
+        names: BTreeMap::new(),
+        reserved: HashMap::new(),
+        inherited_types: vec![],
+        constraint_references: vec![],
+        any_type: TypeId(0),
+        any_simple_type: TypeId(0),
+    };
+    // Top-level components, including redefined ones, get their
+    // identifiers first, so that references to them can be resolved while
+    // walking the components.
+    for ((space, name), location) in &set.components {
+        if *space != SymbolSpace::IdentityConstraint {
+            let id = builder.reserve(*space, *location);
+            builder.names.insert((*space, name.clone()), id);
+        }
+    }
+    for original in set.redefined.values() {
+        if let Some(component) = component_at(set.documents(), original) {
+            builder.reserve(component.space(), *original);
+        }
+    }
+    builder.builtins();
+
+    let mut top_level: Vec<_> = builder.reserved.keys().copied().collect();
+    top_level.sort();
+    for (space, location) in top_level {
+        builder.top_level(space, location)?;
+    }
+    builder.finish()
+}
+
+/// Where the components being walked are defined.
+struct Context<'a> {
+    document: usize,
+    /// Location of the top-level component.
+    location: Location,
+    /// The number of identity constraints walked within the top-level
+    /// component so far.
+    position: usize,
+    /// The kind and name of a redefinition, and the identifier of the
+    /// component it redefines.
+    redefinition: Option<(SymbolSpace, &'a NCName, usize)>,
+}
+
+/// A `ref` or `refer` of an identity constraint, which is resolved once
+/// all identity constraints are known.
+struct ConstraintReference<'a> {
+    constraint: usize,
+    document: usize,
+    namespaces: &'a NamespaceContext,
+    name: &'a QName,
+    span: &'a Span,
+    refer: bool,
+}
+
+struct Builder<'a> {
+    set: &'a SchemaSet,
+    types: Vec<Option<TypeEntry<'a>>>,
+    elements: Vec<Option<ElementEntry<'a>>>,
+    attributes: Vec<Option<AttributeEntry<'a>>>,
+    groups: Vec<Option<GroupEntry<'a>>>,
+    attribute_groups: Vec<Option<AttributeGroupEntry<'a>>>,
+    identity_constraints: Vec<Option<IdentityConstraintEntry<'a>>>,
+    notations: Vec<Option<NotationEntry<'a>>>,
+    names: BTreeMap<(SymbolSpace, ExpandedName), usize>,
+    /// Identifiers of the top-level components and identity constraints.
+    reserved: HashMap<(SymbolSpace, Location), usize>,
+    /// Elements without type of their own.
+    inherited_types: Vec<usize>,
+    constraint_references: Vec<ConstraintReference<'a>>,
+    any_type: TypeId,
+    any_simple_type: TypeId,
+}
+
+/// Adds an empty slot, to be filled once the component has been walked.
+fn slot<T>(entries: &mut Vec<Option<T>>) -> usize {
+    entries.push(None);
+    entries.len() - 1
+}
+
+impl<'a> Builder<'a> {
+    /// Returns the identifier of the component at `location`, adding a
+    /// slot for it if needed.
+    fn reserve(&mut self, space: SymbolSpace, location: Location) -> usize {
+        if let Some(id) = self.reserved.get(&(space, location)) {
+            return *id;
+        }
+        let id = match space {
+            SymbolSpace::Type => slot(&mut self.types),
+            SymbolSpace::Element => slot(&mut self.elements),
+            SymbolSpace::Attribute => slot(&mut self.attributes),
+            SymbolSpace::Group => slot(&mut self.groups),
+            SymbolSpace::AttributeGroup => slot(&mut self.attribute_groups),
+            SymbolSpace::Notation => slot(&mut self.notations),
+            SymbolSpace::IdentityConstraint => slot(&mut self.identity_constraints),
+        };
+        self.reserved.insert((space, location), id);
+        id
+    }
+
+    /// Adds the built-in types that the set does not define itself.
+    fn builtins(&mut self) {
+        let key = |local| {
+            (
+                SymbolSpace::Type,
+                ExpandedName::new(Some(XSD_NAMESPACE), local),
+            )
+        };
+        let mut added = vec![];
+        for builtin in BUILTIN_TYPES {
+            let (space, name) = key(builtin.name);
+            if self.names.contains_key(&(space, name.clone())) {
+                continue;
+            }
+            let mut entry = TypeEntry::new(Some(name.clone()), None, None);
+            entry.builtin = Some(builtin);
+            entry.method = builtin.base.map(|_| match builtin.variety {
+                Some(Variety::List) => DerivationMethod::List,
+                Some(Variety::Union) => DerivationMethod::Union,
+                _ => DerivationMethod::Restriction,
+            });
+            self.types.push(Some(entry));
+            self.names.insert((space, name), self.types.len() - 1);
+            added.push((self.types.len() - 1, builtin));
+        }
+        let id = |local| self.names.get(&key(local)).map(|id| TypeId(*id));
+        for (index, builtin) in added {
+            let base = builtin.base.and_then(id);
+            let item_type = builtin.item_type.and_then(id);
+            if let Some(entry) = &mut self.types[index] {
+                entry.base = base;
+                entry.item_type = item_type;
+            }
+        }
+        let id = |local| self.names[&key(local)];
+        self.any_type = TypeId(id("anyType"));
+        self.any_simple_type = TypeId(id("anySimpleType"));
+    }
+
+    fn namespace(&self, document: usize) -> Option<&'a str> {
+        let set = self.set;
+        set.documents()[document]
+            .schema()
+            .effective_target_namespace()
+    }
+
+    /// Returns the name of a top-level component of `document`.
+    fn global_name(&self, document: usize, name: &str) -> ExpandedName {
+        ExpandedName::new(self.namespace(document), name)
+    }
+
+    /// Resolves a name used in a component into the identifier of the
+    /// component of kind `space` it refers to.
+    fn resolve(
+        &mut self,
+        cx: &Context<'a>,
+        space: SymbolSpace,
+        namespaces: &NamespaceContext,
+        qname: &QName,
+        span: &Span,
+    ) -> Result<usize, SchemaError> {
+        if let Some((redefined, own, original)) = cx.redefinition {
+            let name = self.expand(cx.document, namespaces, qname);
+            if redefined == space && name == Some(self.global_name(cx.document, own)) {
+                return Ok(original);
+            }
+        }
+        self.lookup(cx.document, space, namespaces, qname, span)
+    }
+
+    /// Resolves a qualified name used in `document` into an expanded name,
+    /// taking chameleon documents into account.
+    fn expand(
+        &self,
+        document: usize,
+        namespaces: &NamespaceContext,
+        qname: &QName,
+    ) -> Option<ExpandedName> {
+        let name = namespaces.resolve(qname)?;
+        let schema = self.set.documents()[document].schema();
+        match (name.namespace(), schema.chameleon_namespace()) {
+            (None, Some(namespace)) => Some(ExpandedName::new(
+                Some(namespace.as_str()),
+                name.local_name(),
+            )),
+            _ => Some(name),
+        }
+    }
+
+    /// Looks up the component of kind `space` a name used in `document`
+    /// refers to.
+    fn lookup(
+        &mut self,
+        document: usize,
+        space: SymbolSpace,
+        namespaces: &NamespaceContext,
+        qname: &QName,
+        span: &Span,
+    ) -> Result<usize, SchemaError> {
+        let unresolved = |name: String| SchemaError::UnresolvedReference {
+            kind: space.description(),
+            name,
+            span: Box::new(span.clone()),
+        };
+        let Some(name) = self.expand(document, namespaces, qname) else {
+            return Err(unresolved(qname.to_string()));
+        };
+        if let Some(id) = self.names.get(&(space, name.clone())) {
+            return Ok(*id);
+        }
+        Err(unresolved(name.to_string()))
+    }
+
+    fn resolve_type(
+        &mut self,
+        cx: &Context<'a>,
+        namespaces: &NamespaceContext,
+        qname: &QName,
+        span: &Span,
+    ) -> Result<TypeId, SchemaError> {
+        self.resolve(cx, SymbolSpace::Type, namespaces, qname, span)
+            .map(TypeId)
+    }
+
+    /// Walks the top-level component at `location`.
+    fn top_level(&mut self, space: SymbolSpace, location: Location) -> Result<(), SchemaError> {
+        let Some(component) = component_at(self.set.documents(), &location) else {
+            return Ok(());
+        };
+        let id = self.reserved[&(space, location)];
+        let redefinition = self.set.redefined.get(&location).and_then(|original| {
+            let name = component.name()?;
+            Some((space, name, self.reserved[&(space, *original)]))
+        });
+        let mut cx = Context {
+            document: location.document,
+            location,
+            position: 0,
+            redefinition,
+        };
+        match component {
+            Component::Type(TypeDefinition::Simple(e)) => {
+                self.simple_type(&mut cx, e, Some(id))?;
+            }
+            Component::Type(TypeDefinition::Complex(e)) => {
+                self.complex_type(&mut cx, e, Some(id))?;
+            }
+            Component::Element(e) => {
+                self.element(&mut cx, e, Some(id))?;
+            }
+            Component::Attribute(e) => {
+                self.attribute(&mut cx, e, Some(id))?;
+            }
+            Component::Group(e) => self.group(&mut cx, e, id)?,
+            Component::AttributeGroup(e) => self.attribute_group(&mut cx, e, id)?,
+            Component::Notation(e) => {
+                self.notations[id] = Some(NotationEntry {
+                    name: self.global_name(cx.document, &e.name),
+                    document: cx.document,
+                    declaration: e,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn simple_type(
+        &mut self,
+        cx: &mut Context<'a>,
+        e: &'a SimpleType,
+        id: Option<usize>,
+    ) -> Result<TypeId, SchemaError> {
+        let id = id.unwrap_or_else(|| slot(&mut self.types));
+        let name = e
+            .name
+            .as_ref()
+            .map(|name| self.global_name(cx.document, name));
+        let definition = Some(TypeDefinition::Simple(e));
+        let mut entry = TypeEntry::new(name, Some(cx.document), definition);
+        entry.base = Some(self.any_simple_type);
+        entry.method = Some(DerivationMethod::Restriction);
+        match e.content() {
+            Ok(SimpleTypeContent::Restriction(restriction)) => {
+                entry.base = match (&restriction.base, restriction.simple_type()) {
+                    (Some(base), _) => Some(self.resolve_type(
+                        cx,
+                        restriction.namespaces(),
+                        base,
+                        restriction.span(),
+                    )?),
+                    (None, Some(inline)) => Some(self.simple_type(cx, inline, None)?),
+                    (None, None) => entry.base,
+                };
+            }
+            Ok(SimpleTypeContent::List(list)) => {
+                entry.method = Some(DerivationMethod::List);
+                entry.item_type = match (&list.item_type, list.simple_types().first()) {
+                    (Some(item), _) => {
+                        Some(self.resolve_type(cx, list.namespaces(), item, list.span())?)
+                    }
+                    (None, Some(inline)) => Some(self.simple_type(cx, inline, None)?),
+                    (None, None) => None,
+                };
+            }
+            Ok(SimpleTypeContent::Union(union)) => {
+                entry.method = Some(DerivationMethod::Union);
+                for member in union.member_types.iter().flatten() {
+                    let member = self.resolve_type(cx, union.namespaces(), member, union.span())?;
+                    entry.member_types.push(member);
+                }
+                for inline in union.simple_types() {
+                    let member = self.simple_type(cx, inline, None)?;
+                    entry.member_types.push(member);
+                }
+            }
+            Err(_) => {}
+        }
+        self.types[id] = Some(entry);
+        Ok(TypeId(id))
+    }
+
+    fn complex_type(
+        &mut self,
+        cx: &mut Context<'a>,
+        e: &'a ComplexType,
+        id: Option<usize>,
+    ) -> Result<TypeId, SchemaError> {
+        let id = id.unwrap_or_else(|| slot(&mut self.types));
+        let name = e
+            .name
+            .as_ref()
+            .map(|name| self.global_name(cx.document, name));
+        let definition = Some(TypeDefinition::Complex(e));
+        let mut entry = TypeEntry::new(name, Some(cx.document), definition);
+
+        let complex = e.complex_content().and_then(|content| content.derivation());
+        let simple = e.simple_content().and_then(|content| content.derivation());
+        let (mut attributes, mut attribute_groups) = (e.attributes(), e.attribute_groups());
+        let mut any_attribute = e.any_attribute();
+        let mut particle = e.particle();
+        entry.method = Some(DerivationMethod::Restriction);
+        entry.base = Some(match complex.or(simple) {
+            Some(Derivation::Restriction(restriction)) => {
+                attributes.extend(restriction.attributes());
+                attribute_groups.extend(restriction.attribute_groups());
+                any_attribute = any_attribute.or_else(|| restriction.any_attribute());
+                particle = particle.or_else(|| restriction.particle());
+                if let (Some(_), Some(inline)) = (e.simple_content(), restriction.simple_type()) {
+                    entry.simple_content_type = Some(self.simple_type(cx, inline, None)?);
+                }
+                match &restriction.base {
+                    Some(base) => {
+                        self.resolve_type(cx, restriction.namespaces(), base, restriction.span())?
+                    }
+                    None => self.any_type,
+                }
+            }
+            Some(Derivation::Extension(extension)) => {
+                entry.method = Some(DerivationMethod::Extension);
+                attributes.extend(extension.attributes());
+                attribute_groups.extend(extension.attribute_groups());
+                any_attribute = any_attribute.or_else(|| extension.any_attribute());
+                particle = particle.or_else(|| extension.particle());
+                let (namespaces, span) = (extension.namespaces(), extension.span());
+                self.resolve_type(cx, namespaces, &extension.base, span)?
+            }
+            None => self.any_type,
+        });
+
+        if let Some(particle) = particle {
+            entry.particle = self.particle(cx, particle)?;
+        }
+        for attribute in attributes {
+            let attribute_use = self.attribute_use(cx, attribute)?;
+            entry.attribute_uses.push(attribute_use);
+        }
+        entry.attribute_groups = self.attribute_group_references(cx, attribute_groups)?;
+        entry.attribute_wildcard =
+            any_attribute.map(|any| Wildcard::from_any_attribute(any, self.namespace(cx.document)));
+        let schema = self.set.documents()[cx.document].schema();
+        if let (Some(name), true) = (
+            &schema.default_attributes,
+            e.default_attributes_apply.unwrap_or(true),
+        ) {
+            let space = SymbolSpace::AttributeGroup;
+            let (namespaces, span) = (schema.namespaces(), schema.span());
+            let id = match QName::new(name) {
+                Ok(qname) => self.lookup(cx.document, space, namespaces, &qname, span)?,
+                Err(_) => {
+                    return Err(SchemaError::UnresolvedReference {
+                        kind: space.description(),
+                        name: name.clone(),
+                        span: Box::new(span.clone()),
+                    })
+                }
+            };
+            entry.default_attributes = Some(AttributeGroupId(id));
+        }
+        self.types[id] = Some(entry);
+        Ok(TypeId(id))
+    }
+
+    fn element(
+        &mut self,
+        cx: &mut Context<'a>,
+        e: &'a Element,
+        id: Option<usize>,
+    ) -> Result<ElementId, SchemaError> {
+        let global = id.is_some();
+        let id = id.unwrap_or_else(|| slot(&mut self.elements));
+        let local_name = e.name.as_deref().unwrap_or_default();
+        let schema = self.set.documents()[cx.document].schema();
+        let qualified = match (&e.form, &schema.element_form_default) {
+            (Some(form), _) | (None, Some(form)) => matches!(form, FormChoice::Qualified),
+            (None, None) => false,
+        };
+        let name = if global || qualified {
+            self.global_name(cx.document, local_name)
+        } else {
+            ExpandedName::new(None::<String>, local_name)
+        };
+
+        // The identity constraints of an element come before those nested
+        // in its type, as in the lookup tables of the set.
+        let mut identity_constraints = vec![];
+        for constraint in e.identity_constraints() {
+            let location = Location {
+                position: cx.position,
+                ..cx.location
+            };
+            cx.position += 1;
+            let constraint_id = self.reserve(SymbolSpace::IdentityConstraint, location);
+            let name = constraint.name();
+            let references = match constraint {
+                IdentityConstraint::Unique(c) => [(c.r#ref.as_ref(), false), (None, true)],
+                IdentityConstraint::Key(c) => [(c.r#ref.as_ref(), false), (None, true)],
+                IdentityConstraint::Keyref(c) => {
+                    [(c.r#ref.as_ref(), false), (c.refer.as_ref(), true)]
+                }
+            };
+            let namespaces = match constraint {
+                IdentityConstraint::Unique(c) => c.namespaces(),
+                IdentityConstraint::Key(c) => c.namespaces(),
+                IdentityConstraint::Keyref(c) => c.namespaces(),
+            };
+            for (reference, refer) in references {
+                if let Some(name) = reference {
+                    self.constraint_references.push(ConstraintReference {
+                        constraint: constraint_id,
+                        document: cx.document,
+                        namespaces,
+                        name,
+                        span: constraint.span(),
+                        refer,
+                    });
+                }
+            }
+            self.identity_constraints[constraint_id] = Some(IdentityConstraintEntry {
+                name: name.map(|name| self.global_name(cx.document, name)),
+                document: cx.document,
+                constraint,
+                element: ElementId(id),
+                reference: None,
+                refer: None,
+            });
+            identity_constraints.push(IdentityConstraintId(constraint_id));
+        }
+
+        let type_definition = match (&e.r#type, e.simple_type(), e.complex_type()) {
+            (Some(name), _, _) => Some(self.resolve_type(cx, e.namespaces(), name, e.span())?),
+            (None, Some(inline), _) => Some(self.simple_type(cx, inline, None)?),
+            (None, None, Some(inline)) => Some(self.complex_type(cx, inline, None)?),
+            (None, None, None) => None,
+        };
+        let mut alternatives = vec![];
+        for alternative in e.alternatives() {
+            let (namespaces, span) = (alternative.namespaces(), alternative.span());
+            let inline = (alternative.simple_type(), alternative.complex_type());
+            alternatives.push(match (&alternative.r#type, inline) {
+                (Some(name), _) => Some(self.resolve_type(cx, namespaces, name, span)?),
+                (None, (Some(inline), _)) => Some(self.simple_type(cx, inline, None)?),
+                (None, (None, Some(inline))) => Some(self.complex_type(cx, inline, None)?),
+                (None, (None, None)) => None,
+            });
+        }
+        let mut substitution_groups = vec![];
+        for head in e.substitution_group.iter().flatten() {
+            let head = self.resolve(cx, SymbolSpace::Element, e.namespaces(), head, e.span())?;
+            substitution_groups.push(ElementId(head));
+        }
+        if type_definition.is_none() && !substitution_groups.is_empty() {
+            self.inherited_types.push(id);
+        }
+
+        self.elements[id] = Some(ElementEntry {
+            name,
+            document: cx.document,
+            declaration: e,
+            global,
+            type_definition: type_definition.unwrap_or(self.any_type),
+            alternatives,
+            substitution_groups,
+            members: vec![],
+            identity_constraints,
+        });
+        Ok(ElementId(id))
+    }
+
+    fn attribute(
+        &mut self,
+        cx: &mut Context<'a>,
+        e: &'a Attribute,
+        id: Option<usize>,
+    ) -> Result<AttributeId, SchemaError> {
+        let global = id.is_some();
+        let id = id.unwrap_or_else(|| slot(&mut self.attributes));
+        let local_name = e.name.as_deref().unwrap_or_default();
+        let schema = self.set.documents()[cx.document].schema();
+        let qualified = match (&e.form, &schema.attribute_form_default) {
+            (Some(form), _) | (None, Some(form)) => matches!(form, FormChoice::Qualified),
+            (None, None) => false,
+        };
+        let name = match (&e.target_namespace, global || qualified) {
+            (Some(namespace), false) => ExpandedName::new(Some(namespace.as_str()), local_name),
+            (_, true) => self.global_name(cx.document, local_name),
+            (None, false) => ExpandedName::new(None::<String>, local_name),
+        };
+        let type_definition = match (&e.r#type, e.simple_type()) {
+            (Some(name), _) => self.resolve_type(cx, e.namespaces(), name, e.span())?,
+            (None, Some(inline)) => self.simple_type(cx, inline, None)?,
+            (None, None) => self.any_simple_type,
+        };
+        self.attributes[id] = Some(AttributeEntry {
+            name,
+            document: cx.document,
+            declaration: e,
+            global,
+            type_definition,
+        });
+        Ok(AttributeId(id))
+    }
+
+    /// Walks an `xs:attribute` of a complex type or attribute group.
+    fn attribute_use(
+        &mut self,
+        cx: &mut Context<'a>,
+        e: &'a Attribute,
+    ) -> Result<UseEntry<'a>, SchemaError> {
+        let attribute = match &e.r#ref {
+            Some(name) => {
+                let space = SymbolSpace::Attribute;
+                AttributeId(self.resolve(cx, space, e.namespaces(), name, e.span())?)
+            }
+            None => self.attribute(cx, e, None)?,
+        };
+        Ok(UseEntry {
+            element: e,
+            attribute,
+        })
+    }
+
+    /// Resolves the `ref`s of the `xs:attributeGroup`s of a complex type or
+    /// attribute group.
+    fn attribute_group_references(
+        &mut self,
+        cx: &Context<'a>,
+        references: Vec<&'a AttributeGroup>,
+    ) -> Result<Vec<AttributeGroupId>, SchemaError> {
+        let mut groups = vec![];
+        for reference in references {
+            if let Some(name) = &reference.r#ref {
+                let space = SymbolSpace::AttributeGroup;
+                let (namespaces, span) = (reference.namespaces(), reference.span());
+                groups.push(AttributeGroupId(
+                    self.resolve(cx, space, namespaces, name, span)?,
+                ));
+            }
+        }
+        Ok(groups)
+    }
+
+    fn group(&mut self, cx: &mut Context<'a>, e: &'a Group, id: usize) -> Result<(), SchemaError> {
+        let particle = match e.particle() {
+            Some(particle) => self.particle(cx, particle)?,
+            None => None,
+        };
+        let local_name = e.name.as_deref().unwrap_or_default();
+        self.groups[id] = Some(GroupEntry {
+            name: self.global_name(cx.document, local_name),
+            document: cx.document,
+            definition: e,
+            particle,
+        });
+        Ok(())
+    }
+
+    fn attribute_group(
+        &mut self,
+        cx: &mut Context<'a>,
+        e: &'a AttributeGroup,
+        id: usize,
+    ) -> Result<(), SchemaError> {
+        let mut attribute_uses = vec![];
+        for attribute in e.attributes() {
+            attribute_uses.push(self.attribute_use(cx, attribute)?);
+        }
+        let attribute_groups = self.attribute_group_references(cx, e.attribute_groups())?;
+        let local_name = e.name.as_deref().unwrap_or_default();
+        let target_namespace = self.namespace(cx.document);
+        self.attribute_groups[id] = Some(AttributeGroupEntry {
+            name: self.global_name(cx.document, local_name),
+            document: cx.document,
+            definition: e,
+            attribute_uses,
+            attribute_groups,
+            attribute_wildcard: e
+                .any_attribute()
+                .map(|any| Wildcard::from_any_attribute(any, target_namespace)),
+        });
+        Ok(())
+    }
+
+    /// Walks a particle, returning `None` for a group without `ref`, which
+    /// is only allowed at the top level.
+    fn particle(
+        &mut self,
+        cx: &mut Context<'a>,
+        particle: Particle<'a>,
+    ) -> Result<Option<ContentParticle<'a>>, SchemaError> {
+        let occurs = |min: Option<u32>, max: &Option<MaxOccurs>| {
+            (
+                min.unwrap_or(1),
+                max.clone().unwrap_or(MaxOccurs::Bounded(1)),
+            )
+        };
+        let ((min_occurs, max_occurs), term) = match particle {
+            Particle::Element(e) => {
+                let id = match &e.r#ref {
+                    Some(name) => {
+                        let space = SymbolSpace::Element;
+                        ElementId(self.resolve(cx, space, e.namespaces(), name, e.span())?)
+                    }
+                    None => self.element(cx, e, None)?,
+                };
+                let occurs = (e.effective_min_occurs(), e.effective_max_occurs());
+                (occurs, Term::Element(id))
+            }
+            Particle::Group(e) => {
+                let Some(name) = &e.r#ref else {
+                    return Ok(None);
+                };
+                let id = self.resolve(cx, SymbolSpace::Group, e.namespaces(), name, e.span())?;
+                let occurs = (e.effective_min_occurs(), e.effective_max_occurs());
+                (occurs, Term::Group(GroupId(id)))
+            }
+            Particle::Sequence(e) => (
+                occurs(e.min_occurs, &e.max_occurs),
+                self.model_group(cx, Compositor::Sequence, e.items())?,
+            ),
+            Particle::Choice(e) => (
+                occurs(e.min_occurs, &e.max_occurs),
+                self.model_group(cx, Compositor::Choice, e.items())?,
+            ),
+            Particle::All(e) => (
+                occurs(e.min_occurs, &e.max_occurs.map(MaxOccurs::Bounded)),
+                self.model_group(cx, Compositor::All, e.items())?,
+            ),
+            Particle::Any(e) => (occurs(e.min_occurs, &e.max_occurs), Term::Any(e)),
+        };
+        Ok(Some(ContentParticle {
+            min_occurs,
+            max_occurs,
+            term,
+        }))
+    }
+
+    fn model_group(
+        &mut self,
+        cx: &mut Context<'a>,
+        compositor: Compositor,
+        items: Vec<Particle<'a>>,
+    ) -> Result<Term<'a>, SchemaError> {
+        let mut particles = vec![];
+        for item in items {
+            particles.extend(self.particle(cx, item)?);
+        }
+        Ok(Term::ModelGroup {
+            compositor,
+            particles,
+        })
+    }
+
+    /// Returns the type an element without type of its own takes from the
+    /// head of its substitution group.
+    fn inherited_type(&self, element: usize, seen: &mut Vec<usize>) -> TypeId {
+        let entry = self.elements[element].as_ref().expect("element is built");
+        if !self.inherited_types.contains(&element) {
+            return entry.type_definition;
+        }
+        if seen.contains(&element) {
+            return self.any_type;
+        }
+        seen.push(element);
+        match entry.substitution_groups.first() {
+            Some(head) => self.inherited_type(head.0, seen),
+            None => self.any_type,
+        }
+    }
+
+    fn finish(mut self) -> Result<ComponentModel<'a>, SchemaError> {
+        let set = self.set;
+        for ((space, name), location) in &set.components {
+            if *space == SymbolSpace::IdentityConstraint {
+                if let Some(id) = self.reserved.get(&(*space, *location)) {
+                    self.names.insert((*space, name.clone()), *id);
+                }
+            }
+        }
+        for reference in std::mem::take(&mut self.constraint_references) {
+            let id = self.lookup(
+                reference.document,
+                SymbolSpace::IdentityConstraint,
+                reference.namespaces,
+                reference.name,
+                reference.span,
+            )?;
+            let entry = self.identity_constraints[reference.constraint]
+                .as_mut()
+                .expect("identity constraint is built");
+            if reference.refer {
+                entry.refer = Some(IdentityConstraintId(id));
+            } else {
+                entry.reference = Some(IdentityConstraintId(id));
+            }
+        }
+        for element in self.inherited_types.clone() {
+            let type_definition = self.inherited_type(element, &mut vec![]);
+            if let Some(entry) = &mut self.elements[element] {
+                entry.type_definition = type_definition;
+            }
+        }
+
+        fn built<T>(entries: Vec<Option<T>>) -> Vec<T> {
+            entries
+                .into_iter()
+                .map(|entry| entry.expect("every component is built"))
+                .collect()
+        }
+        let mut elements = built(self.elements);
+        for member in 0..elements.len() {
+            for head in elements[member].substitution_groups.clone() {
+                elements[head.0].members.push(ElementId(member));
+            }
+        }
+        Ok(ComponentModel {
+            set,
+            types: built(self.types),
+            elements,
+            attributes: built(self.attributes),
+            groups: built(self.groups),
+            attribute_groups: built(self.attribute_groups),
+            identity_constraints: built(self.identity_constraints),
+            notations: built(self.notations),
+            names: self.names,
+            any_type: self.any_type,
+            any_simple_type: self.any_simple_type,
+            any_type_wildcard: Any::any_type(),
+        })
+    }
+}
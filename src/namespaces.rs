@@ -0,0 +1,140 @@
+//! Namespace-qualified names and the namespace bindings used to resolve them.
+use std::{collections::BTreeMap, fmt};
+
+use serde::{de, Deserialize, Deserializer};
+
+use crate::basics::QName;
+
+/// The namespace of the XML Schema language itself.
+pub const XSD_NAMESPACE: &str = "http://www.w3.org/2001/XMLSchema";
+
+/// The namespace bound to the `xml` prefix, e.g. for `xml:lang`.
+pub const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+/// A name together with the namespace it belongs to.
+///
+/// Unlike a qualified name such as `tns:Address`, an expanded name does not
+/// depend on the prefixes declared in a document, so expanded names can be
+/// compared across documents.
+///
+/// Expanded names are displayed in Clark notation, i.e. `{namespace}local`
+/// or just `local` for names without a namespace.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExpandedName {
+    namespace: Option<String>,
+    local_name: String,
+}
+
+impl ExpandedName {
+    /// Creates an expanded name from a namespace and a local name.
+    pub fn new(namespace: Option<impl Into<String>>, local_name: impl Into<String>) -> Self {
+        ExpandedName {
+            namespace: namespace.map(Into::into),
+            local_name: local_name.into(),
+        }
+    }
+
+    /// Returns the namespace of the name, or `None` if it has no namespace.
+    pub fn namespace(&self) -> Option<&str> {
+        self.namespace.as_deref()
+    }
+
+    /// Returns the local part of the name.
+    pub fn local_name(&self) -> &str {
+        &self.local_name
+    }
+
+    /// Parses a name in Clark notation.
+    pub(crate) fn from_clark(name: &str) -> Self {
+        match name.strip_prefix('{').and_then(|name| name.split_once('}')) {
+            Some((namespace, local_name)) => ExpandedName::new(Some(namespace), local_name),
+            None => ExpandedName::new(None::<String>, name),
+        }
+    }
+}
+
+impl fmt::Display for ExpandedName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.namespace {
+            Some(namespace) => write!(f, "{{{namespace}}}{}", self.local_name),
+            None => f.write_str(&self.local_name),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ExpandedName {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ClarkVisitor;
+
+        impl<'de> de::Visitor<'de> for ClarkVisitor {
+            type Value = ExpandedName;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a name in Clark notation")
+            }
+
+            fn visit_str<E: de::Error>(self, name: &str) -> Result<ExpandedName, E> {
+                Ok(ExpandedName::from_clark(name))
+            }
+        }
+
+        deserializer.deserialize_str(ClarkVisitor)
+    }
+}
+
+/// The namespace bindings in scope at a schema component.
+///
+/// The bindings are collected from the `xmlns` and `xmlns:prefix`
+/// declarations on the element that defines the component and on all its
+/// ancestors, where declarations on inner elements take precedence. The
+/// `xml` prefix is always bound to [XML_NAMESPACE].
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct NamespaceContext {
+    /// Namespace per prefix, the default namespace has an empty prefix.
+    bindings: BTreeMap<String, String>,
+}
+
+impl NamespaceContext {
+    /// Returns the namespace bound to a prefix.
+    pub fn namespace(&self, prefix: &str) -> Option<&str> {
+        match prefix {
+            "xml" => Some(XML_NAMESPACE),
+            prefix => self.bindings.get(prefix).map(String::as_str),
+        }
+    }
+
+    /// Returns the default namespace, which applies to unprefixed names.
+    pub fn default_namespace(&self) -> Option<&str> {
+        self.namespace("")
+    }
+
+    /// Iterates over the (prefix, namespace) bindings, the default
+    /// namespace is reported with an empty prefix.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.bindings
+            .iter()
+            .map(|(prefix, namespace)| (prefix.as_str(), namespace.as_str()))
+    }
+
+    /// Resolves a qualified name such as `tns:Address` into an expanded
+    /// name.
+    ///
+    /// A name without prefix is placed in the default namespace, or in no
+    /// namespace if there is no default namespace. Returns `None` if the
+    /// prefix of the name is not bound.
+    pub fn resolve(&self, qname: &QName) -> Option<ExpandedName> {
+        let namespace = match qname.prefix() {
+            Some(prefix) => Some(self.namespace(prefix)?),
+            None => self.default_namespace(),
+        };
+        Some(ExpandedName::new(namespace, qname.local_name()))
+    }
+}
+
+/// Attributes from namespaces other than the XML Schema namespace, keyed by
+/// their expanded name.
+///
+/// The XML Schema grammar allows such attributes on nearly every element,
+/// e.g. `jaxb:` or other vendor specific annotations.
+pub type ForeignAttributes = BTreeMap<ExpandedName, String>;
@@ -8,12 +8,19 @@
 use serde::Deserialize;
 
 use crate::{
-    basics::{NCName, QName, ID},
-    element_from_body, Annotation, Assert, Block, ComplexType, Final, FormChoice, Key, Keyref,
-    ProcessContents, SimpleType, Unique,
+    basics::{AnyURI, NCName, QName, ID},
+    element_from_body, elements_from_body, Annotation, Block, ComplexType, Field, Final,
+    FormChoice, Key, Keyref, ProcessContents, SimpleType, Unique,
+};
+use crate::{
+    namespaces::{ForeignAttributes, NamespaceContext},
+    span::Span,
 };
 
+crate::impl_component!(Sequence, All, Group, Choice, Any, Element, Alternative);
+
 pub enum Particle<'a> {
+    All(&'a All),
     Element(&'a Element),
     Choice(&'a Choice),
     Group(&'a Group),
@@ -21,7 +28,46 @@ pub enum Particle<'a> {
     Any(&'a Any),
 }
 
-#[derive(Deserialize, Debug)]
+/// An identity constraint defined on an element declaration.
+#[derive(Debug, Clone, Copy)]
+pub enum IdentityConstraint<'a> {
+    Unique(&'a Unique),
+    Key(&'a Key),
+    Keyref(&'a Keyref),
+}
+
+impl<'a> IdentityConstraint<'a> {
+    /// Returns the name of the identity constraint, or `None` for a key
+    /// that refers to another key.
+    pub fn name(&self) -> Option<&'a NCName> {
+        match *self {
+            IdentityConstraint::Unique(e) => e.name.as_ref(),
+            IdentityConstraint::Key(e) => e.name.as_ref(),
+            IdentityConstraint::Keyref(e) => e.name.as_ref(),
+        }
+    }
+
+    /// Returns the location of the identity constraint in its source
+    /// document.
+    pub fn span(&self) -> &'a Span {
+        match *self {
+            IdentityConstraint::Unique(e) => e.span(),
+            IdentityConstraint::Key(e) => e.span(),
+            IdentityConstraint::Keyref(e) => e.span(),
+        }
+    }
+
+    /// Returns the fields of the identity constraint.
+    pub fn fields(&self) -> Vec<&'a Field> {
+        match *self {
+            IdentityConstraint::Unique(e) => e.fields(),
+            IdentityConstraint::Key(e) => e.fields(),
+            IdentityConstraint::Keyref(e) => e.fields(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 #[serde(untagged)]
 pub enum MaxOccurs {
@@ -65,8 +111,14 @@ pub struct Sequence {
     /// Elements, groups, or wildcards that define the content of the sequence.
     /// The order of elements within this vector is significant and corresponds
     /// to the order in which they must appear in the complex type content model.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<SequenceBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Sequence {
@@ -93,7 +145,7 @@ impl Sequence {
     ///
     /// The resulting vector represents the ordered sequence of elements, groups, or wildcards
     /// that define the content model within the sequence particle.
-    pub fn items(&self) -> Vec<Particle> {
+    pub fn items(&self) -> Vec<Particle<'_>> {
         let mut particles = vec![];
         for element in &self.body {
             match element {
@@ -151,8 +203,14 @@ pub struct All {
     pub max_occurs: Option<u32>,
     /// Elements, groups, or wildcards that define the content of the all particle.
     /// The order within this vector is not significant.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<AllBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl All {
@@ -179,7 +237,7 @@ impl All {
     ///
     /// The resulting vector represents the ordered sequence of elements, groups, or wildcards
     /// that define the content model within the all particle.
-    pub fn items(&self) -> Vec<Particle> {
+    pub fn items(&self) -> Vec<Particle<'_>> {
         let mut particles = vec![];
         for element in &self.body {
             match element {
@@ -218,8 +276,60 @@ pub struct Group {
     // #[serde(default = "some_one_bounded")]
     #[serde(rename = "@maxOccurs")]
     pub max_occurs: Option<MaxOccurs>,
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<GroupBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Group {
+    /// Extracts the annotation element from the group.
+    pub fn annotation(&self) -> Option<&Annotation> {
+        element_from_body!(self, GroupBody::Annotation)
+    }
+
+    /// Extracts the `xs:sequence` model group of a group definition.
+    pub fn sequence(&self) -> Option<&Sequence> {
+        element_from_body!(self, GroupBody::Sequence)
+    }
+
+    /// Extracts the `xs:choice` model group of a group definition.
+    pub fn choice(&self) -> Option<&Choice> {
+        element_from_body!(self, GroupBody::Choice)
+    }
+
+    /// Extracts the `xs:all` model group of a group definition.
+    pub fn all(&self) -> Option<&All> {
+        element_from_body!(self, GroupBody::All)
+    }
+
+    /// Returns the model group of a group definition, which is one of
+    /// [Group::sequence], [Group::choice] or [Group::all]. Group
+    /// references (with `@ref`) have no model group of their own.
+    pub fn particle(&self) -> Option<Particle<'_>> {
+        self.body.iter().find_map(|element| match element {
+            GroupBody::Sequence(e) => Some(Particle::Sequence(e)),
+            GroupBody::Choice(e) => Some(Particle::Choice(e)),
+            GroupBody::All(e) => Some(Particle::All(e)),
+            GroupBody::Annotation(_) => None,
+        })
+    }
+
+    /// Returns the minimum number of occurrences of a group reference,
+    /// which defaults to 1 if `@minOccurs` is absent.
+    pub fn effective_min_occurs(&self) -> u32 {
+        self.min_occurs.unwrap_or(1)
+    }
+
+    /// Returns the maximum number of occurrences of a group reference,
+    /// which defaults to 1 if `@maxOccurs` is absent.
+    pub fn effective_max_occurs(&self) -> MaxOccurs {
+        self.max_occurs.clone().unwrap_or(MaxOccurs::Bounded(1))
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -228,7 +338,6 @@ pub struct Group {
 enum GroupBody {
     All(All),
     Annotation(Annotation),
-    Assert(Assert),
     Choice(Choice),
     Sequence(Sequence),
 }
@@ -264,8 +373,14 @@ pub struct Choice {
     #[serde(rename = "@maxOccurs")]
     pub max_occurs: Option<MaxOccurs>,
     /// Elements, groups, or other particles that define the available choices within the complex type element.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<ChoiceBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Choice {
@@ -292,7 +407,7 @@ impl Choice {
     ///
     /// The resulting vector represents the ordered sequence of elements, groups, or wildcards
     /// that define the content model within the choice particle.
-    pub fn items(&self) -> Vec<Particle> {
+    pub fn items(&self) -> Vec<Particle<'_>> {
         let mut particles = vec![];
         for element in &self.body {
             match element {
@@ -387,14 +502,38 @@ pub struct Any {
     /// This can be used to provide additional comments or metadata about the wildcard element.
     #[serde(rename = "$value")]
     body: Option<Annotation>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
 }
 
 impl Any {
-    /// Extracts the optional annotation element associated with the "any" particle.
+    /// Returns the wildcard in the content of `xs:anyType`, which allows
+    /// any number of elements of any namespace and validates them laxly.
+    pub(crate) fn any_type() -> Self {
+        Any {
+            id: None,
+            namespace: None,
+            not_namespace: None,
+            not_q_name: None,
+            process_contents: Some(ProcessContents::Lax),
+            min_occurs: Some(0),
+            max_occurs: Some(MaxOccurs::Unbounded("unbounded".to_string())),
+            body: None,
+            span: Span::default(),
+            foreign_attributes: ForeignAttributes::default(),
+            namespaces: NamespaceContext::default(),
+        }
+    }
 
+    /// Extracts the optional annotation element associated with the "any" particle.
+    ///
     /// This method retrieves the optional `Annotation` element stored within the `body` field
     /// of the `Any` struct. Annotations provide comments or metadata about the wildcard element.
-
+    ///
     /// If an annotation is present, this method returns a reference to the contained `Annotation`
     /// struct. Otherwise, it returns `None`.
     pub fn annotation(&self) -> Option<&Annotation> {
@@ -467,11 +606,11 @@ pub struct Element {
     /// Final declaration restriction.
     ///
     /// The `@final` attribute is an optional attribute on the `xs:element`
-    /// element. It specifies whether the element can be derived from by
-    /// complex type extensions or restrictions. When set to `true`, the
-    /// element cannot be used as a base type for complex type derivations.
+    /// element. It specifies the set of methods, among extension and
+    /// restriction, by which the types of the members of the substitution
+    /// group of the element may not be derived.
     #[serde(rename = "@final")]
-    pub r#final: Option<Final>,
+    pub r#final: Option<Vec<Final>>,
     /// Block declaration restricting content model.
     ///
     /// The `@block` attribute is an optional attribute on the `xs:element`
@@ -512,15 +651,16 @@ pub struct Element {
     /// in the schema or a built-in XML Schema type.
     #[serde(rename = "@type")]
     pub r#type: Option<QName>,
-    /// Substitution group for element.
+    /// Substitution groups for element.
     ///
     /// The `@substitutionGroup` attribute is an optional attribute on the
     /// `xs:element` element. It specifies that the current element belongs
-    /// to a substitution group identified by the QName value. This allows
+    /// to the substitution groups identified by the QName values. This allows
     /// elements from the same substitution group to be used interchangeably
-    /// in certain contexts.
+    /// in certain contexts. XML Schema 1.1 allows a space-separated list of
+    /// several heads.
     #[serde(rename = "@substitutionGroup")]
-    pub substitution_group: Option<QName>,
+    pub substitution_group: Option<Vec<QName>>,
     /// Minimum occurrence constraint.
     ///
     /// The `@minOccurs` attribute is an optional attribute on the `xs:element`
@@ -530,7 +670,7 @@ pub struct Element {
     #[serde(rename = "@minOccurs")]
     pub min_occurs: Option<u32>,
     /// Maximum occurrence constraint.
-
+    ///
     /// The `@maxOccurs` attribute is an optional attribute on the `xs:element`
     /// element. It specifies the maximum number of times the element can
     /// appear in an instance document. The value can be either a non-negative
@@ -552,8 +692,84 @@ pub struct Element {
     /// elements that define the content model of the element. This can include
     /// elements like `xs:complexType`, `xs:simpleType`, `xs:annotation`,
     /// and others depending on the specific element type and schema design.
-    #[serde(rename = "$value", default)]
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
     body: Vec<ElementBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Element {
+    /// Extracts the annotation element from the element body.
+    ///
+    /// Annotations can be used within element declarations to provide
+    /// documentation or other descriptive information about the element.
+    pub fn annotation(&self) -> Option<&Annotation> {
+        element_from_body!(self, ElementBody::Annotation)
+    }
+
+    /// Extracts the anonymous simple type defined inline in the element
+    /// declaration, if any.
+    pub fn simple_type(&self) -> Option<&SimpleType> {
+        element_from_body!(self, ElementBody::SimpleType)
+    }
+
+    /// Extracts the anonymous complex type defined inline in the element
+    /// declaration, if any.
+    pub fn complex_type(&self) -> Option<&ComplexType> {
+        element_from_body!(self, ElementBody::ComplexType)
+    }
+
+    /// Extracts all `xs:unique` constraints of the element declaration.
+    pub fn uniques(&self) -> Vec<&Unique> {
+        elements_from_body!(self, ElementBody::Unique)
+    }
+
+    /// Extracts all `xs:key` constraints of the element declaration.
+    pub fn keys(&self) -> Vec<&Key> {
+        elements_from_body!(self, ElementBody::Key)
+    }
+
+    /// Extracts all `xs:keyref` constraints of the element declaration.
+    pub fn keyrefs(&self) -> Vec<&Keyref> {
+        elements_from_body!(self, ElementBody::Keyref)
+    }
+
+    /// Extracts the type alternatives of the element declaration, in
+    /// document order, which is the order in which they are tested.
+    pub fn alternatives(&self) -> Vec<&Alternative> {
+        elements_from_body!(self, ElementBody::Alternative)
+    }
+
+    /// Extracts all identity constraints (`xs:unique`, `xs:key` and
+    /// `xs:keyref`) of the element declaration, in document order.
+    pub fn identity_constraints(&self) -> Vec<IdentityConstraint<'_>> {
+        let mut constraints = vec![];
+        for element in &self.body {
+            match element {
+                ElementBody::Unique(e) => constraints.push(IdentityConstraint::Unique(e)),
+                ElementBody::Key(e) => constraints.push(IdentityConstraint::Key(e)),
+                ElementBody::Keyref(e) => constraints.push(IdentityConstraint::Keyref(e)),
+                _ => continue,
+            }
+        }
+        constraints
+    }
+
+    /// Returns the minimum number of occurrences of the element, which
+    /// defaults to 1 if `@minOccurs` is absent.
+    pub fn effective_min_occurs(&self) -> u32 {
+        self.min_occurs.unwrap_or(1)
+    }
+
+    /// Returns the maximum number of occurrences of the element, which
+    /// defaults to 1 if `@maxOccurs` is absent.
+    pub fn effective_max_occurs(&self) -> MaxOccurs {
+        self.max_occurs.clone().unwrap_or(MaxOccurs::Bounded(1))
+    }
 }
 
 #[derive(Deserialize, Debug)]
@@ -566,6 +782,82 @@ enum ElementBody {
     Unique(Unique),
     Key(Key),
     Keyref(Keyref),
-    // TODO: Not supported yet
-    Alternative,
+    Alternative(Alternative),
+}
+
+/// Represents a type alternative of an element declaration (XSD 1.1).
+///
+/// Type alternatives implement conditional type assignment: the type of an
+/// element in an instance document is the type of the first alternative
+/// whose `@test` evaluates to true. An alternative without `@test` always
+/// applies and is typically the last one, acting as the default.
+///
+/// ```xsd
+/// <alternative
+///   id = ID
+///   test = an XPath expression
+///   type = QName
+///   xpathDefaultNamespace = (anyURI | (##defaultNamespace | ##targetNamespace | ##local))
+///   {any attributes with non-schema namespace . . .}>
+///   Content: (annotation?, (simpleType | complexType)?)
+/// </alternative>
+/// ```
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+pub struct Alternative {
+    /// Optional identifier for the type alternative.
+    #[serde(rename = "@id")]
+    pub id: Option<ID>,
+    /// XPath expression selecting the instances the alternative applies to.
+    ///
+    /// The `@test` attribute is evaluated against the element in the
+    /// instance document (with its attributes, but without its children).
+    /// If absent, the alternative applies unconditionally.
+    #[serde(rename = "@test")]
+    pub test: Option<String>,
+    /// Reference to the type assigned by the alternative.
+    ///
+    /// Either `@type` or an inline simple or complex type must be given.
+    #[serde(rename = "@type")]
+    pub r#type: Option<QName>,
+    /// Default namespace for unprefixed names in the `@test` expression.
+    #[serde(rename = "@xpathDefaultNamespace")]
+    pub xpath_default_namespace: Option<AnyURI>,
+    #[serde(rename = "$value", default, deserialize_with = "crate::de::children")]
+    body: Vec<AlternativeBody>,
+    #[serde(rename = "#span")]
+    span: Span,
+    #[serde(rename = "#attributes")]
+    foreign_attributes: ForeignAttributes,
+    #[serde(rename = "#namespaces")]
+    namespaces: NamespaceContext,
+}
+
+impl Alternative {
+    /// Extracts the annotation element from the alternative body.
+    pub fn annotation(&self) -> Option<&Annotation> {
+        element_from_body!(self, AlternativeBody::Annotation)
+    }
+
+    /// Extracts the anonymous simple type assigned by the alternative, if
+    /// any.
+    pub fn simple_type(&self) -> Option<&SimpleType> {
+        element_from_body!(self, AlternativeBody::SimpleType)
+    }
+
+    /// Extracts the anonymous complex type assigned by the alternative, if
+    /// any.
+    pub fn complex_type(&self) -> Option<&ComplexType> {
+        element_from_body!(self, AlternativeBody::ComplexType)
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+#[serde(deny_unknown_fields)]
+enum AlternativeBody {
+    Annotation(Annotation),
+    SimpleType(SimpleType),
+    ComplexType(ComplexType),
 }
@@ -0,0 +1,244 @@
+//! Locating the schema documents referenced by `xs:include`, `xs:import`,
+//! `xs:redefine` and `xs:override`.
+//!
+//! A [SchemaResolver] turns the `schemaLocation` of such a reference into
+//! the URI and the content of a schema document. The location is usually
+//! relative to the document that contains the reference, whose URI is
+//! passed to the resolver as base. See [SchemaSet](crate::SchemaSet) for
+//! loading all documents of a schema.
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufRead, BufReader, Cursor, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::error::SchemaError;
+
+/// A schema document located by a [SchemaResolver].
+pub struct SchemaSource {
+    /// The URI of the document.
+    ///
+    /// Documents with the same URI are loaded only once, so a resolver
+    /// should return the same URI for all locations of a document. The URI
+    /// is the base for resolving the references made by the document.
+    pub uri: String,
+    /// The content of the document.
+    pub reader: Box<dyn BufRead>,
+}
+
+/// Maps schema locations to schema documents.
+pub trait SchemaResolver {
+    /// Locates the schema document at `location`.
+    ///
+    /// `base` is the URI of the document that references `location`, or
+    /// `None` for the root document of a schema.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no document at `location` or if it
+    /// cannot be opened.
+    fn resolve(&self, base: Option<&str>, location: &str) -> io::Result<SchemaSource>;
+
+    /// Locates the schema document imported for `namespace` from
+    /// `location`, which is `None` for an import without `schemaLocation`.
+    ///
+    /// Resolvers can use the namespace to find a local copy of a well-known
+    /// schema, the default implementation ignores it and calls
+    /// [SchemaResolver::resolve] if there is a location.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no document can be found for the import.
+    fn resolve_import(
+        &self,
+        base: Option<&str>,
+        namespace: Option<&str>,
+        location: Option<&str>,
+    ) -> io::Result<SchemaSource> {
+        match location {
+            Some(location) => self.resolve(base, location),
+            None => Err(no_location(namespace)),
+        }
+    }
+}
+
+/// Reads schema documents from the file system.
+///
+/// Relative locations are resolved against the directory of the including
+/// document, the location of the root document against the current
+/// directory. Locations may be plain paths or `file://` URIs, other URIs
+/// such as `http://` are not supported. The URI of a document is its
+/// canonical path.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FileResolver;
+
+impl SchemaResolver for FileResolver {
+    fn resolve(&self, base: Option<&str>, location: &str) -> io::Result<SchemaSource> {
+        let location = location.strip_prefix("file://").unwrap_or(location);
+        if has_scheme(location) {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("`{location}` is not on the file system"),
+            ));
+        }
+        let path = match base.and_then(|base| Path::new(base).parent()) {
+            Some(directory) => directory.join(location),
+            None => PathBuf::from(location),
+        };
+        let file = File::open(&path)?;
+        Ok(SchemaSource {
+            uri: path.canonicalize()?.to_string_lossy().into(),
+            reader: Box::new(BufReader::new(file)),
+        })
+    }
+}
+
+/// Serves schema documents from memory, e.g. for tests or for schemas
+/// that are embedded in a program.
+///
+/// Documents are registered under a URI, and locations are resolved
+/// against the URI of the including document like relative URI
+/// references, e.g. `../common/types.xsd` referenced from
+/// `schemas/main/order.xsd` is `schemas/common/types.xsd`.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryResolver {
+    documents: HashMap<String, String>,
+}
+
+impl MemoryResolver {
+    /// Creates a resolver without any documents.
+    pub fn new() -> Self {
+        MemoryResolver::default()
+    }
+
+    /// Registers the `source` of a schema document under `uri`.
+    pub fn insert(&mut self, uri: impl Into<String>, source: impl Into<String>) {
+        self.documents.insert(uri.into(), source.into());
+    }
+}
+
+impl SchemaResolver for MemoryResolver {
+    fn resolve(&self, base: Option<&str>, location: &str) -> io::Result<SchemaSource> {
+        let uri = resolve_uri(base, location);
+        match self.documents.get(&uri) {
+            Some(source) => Ok(SchemaSource {
+                reader: Box::new(Cursor::new(source.clone().into_bytes())),
+                uri,
+            }),
+            None => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("no schema document at `{uri}`"),
+            )),
+        }
+    }
+}
+
+/// Reads the content of a located document.
+pub(crate) fn read(
+    mut source: SchemaSource,
+    base: Option<&str>,
+    location: &str,
+) -> Result<String, SchemaError> {
+    let mut content = String::new();
+    source
+        .reader
+        .read_to_string(&mut content)
+        .map_err(|error| unresolved(base, location, error))?;
+    Ok(content)
+}
+
+/// Reports that an import without `schemaLocation` cannot be resolved.
+pub(crate) fn no_location(namespace: Option<&str>) -> io::Error {
+    let message = match namespace {
+        Some(namespace) => format!("no schema document for namespace `{namespace}`"),
+        None => "no schema document for names without namespace".to_string(),
+    };
+    io::Error::new(io::ErrorKind::NotFound, message)
+}
+
+/// Reports that the document at `location` cannot be loaded.
+pub(crate) fn unresolved(base: Option<&str>, location: &str, error: io::Error) -> SchemaError {
+    SchemaError::Unresolved {
+        base: base.map(str::to_string),
+        location: location.to_string(),
+        error,
+    }
+}
+
+/// Resolves a URI reference against a base URI.
+///
+/// This covers the common forms of `schemaLocation` values: absolute URIs,
+/// absolute paths and relative paths. `.` and `..` segments are removed
+/// from the result, query strings and fragments are not treated specially.
+pub fn resolve_uri(base: Option<&str>, location: &str) -> String {
+    let base = match base {
+        Some(base) if !has_scheme(location) => base,
+        _ => return remove_dot_segments(location),
+    };
+    if location.starts_with('/') {
+        // Keep the scheme and authority of the base, if any.
+        let authority = base.find("://").map_or(0, |start| {
+            let rest = &base[start + 3..];
+            start + 3 + rest.find('/').unwrap_or(rest.len())
+        });
+        return format!("{}{}", &base[..authority], remove_dot_segments(location));
+    }
+    let directory = base.rfind('/').map_or("", |end| &base[..=end]);
+    remove_dot_segments(&format!("{directory}{location}"))
+}
+
+/// Returns whether a URI reference starts with a scheme such as `http:`.
+///
+/// Single letters are not taken as schemes, so that Windows paths such as
+/// `C:\schemas` are treated as paths.
+fn has_scheme(uri: &str) -> bool {
+    match uri.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.len() > 1
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+/// Removes `.` and `..` segments from the path of a URI reference.
+///
+/// Leading `..` segments of a relative path are kept, as there is nothing
+/// to remove them against.
+fn remove_dot_segments(uri: &str) -> String {
+    // Keep the scheme and authority as they are.
+    let (prefix, path) = match uri.find("://") {
+        Some(start) => {
+            let end = uri[start + 3..]
+                .find('/')
+                .map_or(uri.len(), |end| start + 3 + end);
+            uri.split_at(end)
+        }
+        None => ("", uri),
+    };
+    let mut segments: Vec<&str> = vec![];
+    let mut parts = path.split('/').peekable();
+    while let Some(segment) = parts.next() {
+        let last = parts.peek().is_none();
+        match segment {
+            "." if last => segments.push(""),
+            "." => {}
+            ".." => match segments.last() {
+                Some(&"..") | None => segments.push(".."),
+                Some(&"") if segments.len() == 1 => {}
+                Some(_) => {
+                    segments.pop();
+                    if last {
+                        segments.push("");
+                    }
+                }
+            },
+            segment => segments.push(segment),
+        }
+    }
+    format!("{prefix}{}", segments.join("/"))
+}
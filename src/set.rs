@@ -0,0 +1,1052 @@
+//! Schemas that span several schema documents.
+use std::{
+    collections::{btree_map::Entry, BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use crate::{
+    basics::{AnyURI, NCName},
+    check,
+    de::Document,
+    error::{SchemaError, Violation},
+    model::{self, ComponentModel},
+    namespaces::ExpandedName,
+    particles::{Element, Group, IdentityConstraint, Particle},
+    resolver::{read, unresolved, SchemaResolver},
+    span::Span,
+    Attribute, AttributeGroup, ComplexType, Derivation, Notation, OverrideBody, RedefineBody,
+    Schema, SchemaBody, SimpleType,
+};
+
+/// How a schema document refers to another one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// `xs:include`
+    Include,
+    /// `xs:import`
+    Import,
+    /// `xs:redefine`
+    Redefine,
+    /// `xs:override`
+    Override,
+}
+
+/// A reference from one document of a [SchemaSet] to another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// The element that makes the reference.
+    pub kind: ReferenceKind,
+    /// The `schemaLocation` of the reference, as written in the document,
+    /// or `None` for an import without one.
+    pub location: Option<AnyURI>,
+    /// Index of the referenced document in [SchemaSet::documents].
+    pub document: usize,
+}
+
+/// A schema document that is part of a [SchemaSet].
+#[derive(Debug)]
+pub struct SchemaDocument {
+    uri: String,
+    schema: Schema,
+    references: Vec<Reference>,
+    /// The components of `xs:override` elements that replace components of
+    /// this document.
+    overrides: Vec<Location>,
+}
+
+impl SchemaDocument {
+    /// Returns the URI the document was loaded from.
+    pub fn uri(&self) -> &str {
+        &self.uri
+    }
+
+    /// Returns the content of the document.
+    pub fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Returns the documents referenced by this document through
+    /// `xs:include`, `xs:import`, `xs:redefine` and `xs:override`
+    /// elements, in document order.
+    pub fn references(&self) -> &[Reference] {
+        &self.references
+    }
+
+    /// Returns whether components of this document are replaced by the
+    /// components of an `xs:override`, either of an override of this
+    /// document or of a document that includes or overrides it.
+    pub fn is_overridden(&self) -> bool {
+        !self.overrides.is_empty()
+    }
+}
+
+/// A type definition, which is either simple or complex.
+#[derive(Debug, Clone, Copy)]
+pub enum TypeDefinition<'a> {
+    Simple(&'a SimpleType),
+    Complex(&'a ComplexType),
+}
+
+impl<'a> TypeDefinition<'a> {
+    /// Returns the name of the type definition, or `None` for an
+    /// anonymous type.
+    pub fn name(&self) -> Option<&'a NCName> {
+        match *self {
+            TypeDefinition::Simple(e) => e.name.as_ref(),
+            TypeDefinition::Complex(e) => e.name.as_ref(),
+        }
+    }
+
+    /// Returns the location of the type definition in its source document.
+    pub fn span(&self) -> &'a Span {
+        match *self {
+            TypeDefinition::Simple(e) => e.span(),
+            TypeDefinition::Complex(e) => e.span(),
+        }
+    }
+}
+
+/// All schema documents that make up a schema.
+///
+/// A set is loaded from a root document by following its `xs:include`,
+/// `xs:import`, `xs:redefine` and `xs:override` elements, and those of the
+/// documents they refer to, using a
+/// [SchemaResolver](crate::resolver::SchemaResolver) to locate the
+/// documents.
+///
+/// Every document is loaded only once, even if it is referenced several
+/// times, so cyclic references are fine. The exception are documents
+/// without target namespace that are included into schemas with different
+/// target namespaces: such a chameleon document is part of the set once
+/// per namespace it takes on.
+///
+/// The named components of all documents can be looked up by their
+/// expanded name, i.e. by the target namespace of the document that
+/// defines them and their name. Each kind of component has its own symbol
+/// space, e.g. a simple type and an element may have the same name, but
+/// two elements may not.
+///
+/// The simple and complex types, model groups and attribute groups in the
+/// body of an `xs:redefine` replace the components with the same name
+/// from the redefined document, so lookups return the redefinitions. The
+/// components they replace remain available, e.g. through
+/// [SchemaSet::redefined_type_definition].
+///
+/// Likewise, the components in the body of an `xs:override` replace the
+/// components with the same kind and name of the overridden document, and
+/// of the documents that it includes or overrides, transitively. Components
+/// of an override that do not replace anything are ignored. As described
+/// in section 4.2.5 of XML Schema 1.1, a document that is overridden in
+/// different ways, or that is both overridden and included, is part of the
+/// set several times, which usually results in duplicate definitions.
+#[derive(Debug)]
+pub struct SchemaSet {
+    documents: Vec<SchemaDocument>,
+    pub(crate) components: BTreeMap<(SymbolSpace, ExpandedName), Location>,
+    /// The component each redefinition replaced.
+    pub(crate) redefined: HashMap<Location, Location>,
+}
+
+impl SchemaSet {
+    /// Loads the schema document at `root` and all documents it refers to,
+    /// directly or indirectly.
+    ///
+    /// # Errors
+    ///
+    /// Returns [SchemaError::Unresolved] if a document cannot be located,
+    /// [SchemaError::InDocument] if a document cannot be read, and
+    /// [SchemaError::TargetNamespaceMismatch] (wrapped in
+    /// [SchemaError::InDocument] for the including document) if an
+    /// included, redefined or overridden document has another target
+    /// namespace than the including document.
+    pub fn load(root: &str, resolver: &impl SchemaResolver) -> Result<Self, SchemaError> {
+        let mut loader = Loader {
+            resolver,
+            documents: vec![],
+            index: HashMap::new(),
+            summaries: HashMap::new(),
+        };
+        let source = resolver
+            .resolve(None, root)
+            .map_err(|error| unresolved(None, root, error))?;
+        let uri = source.uri.clone();
+        let schema = parse(&uri, &read(source, None, root)?)?;
+        loader.add(uri, None, vec![], schema);
+
+        let mut next = 0;
+        while next < loader.documents.len() {
+            let references = referenced_locations(&loader.documents[next].schema);
+            let mut resolved = vec![];
+            for (index, kind, namespace, location) in references {
+                let overrides = loader.overrides(next, index, kind);
+                let imported = namespace.as_deref();
+                let loaded = loader.load(next, kind, imported, location.as_deref(), overrides)?;
+                let Some(document) = loaded else {
+                    continue;
+                };
+                resolved.push(Reference {
+                    kind,
+                    location,
+                    document,
+                });
+            }
+            loader.documents[next].references = resolved;
+            next += 1;
+        }
+        let Tables {
+            components,
+            redefined,
+        } = tables(&loader.documents)?;
+        Ok(SchemaSet {
+            documents: loader.documents,
+            components,
+            redefined,
+        })
+    }
+
+    /// Returns the root document of the set.
+    pub fn root(&self) -> &SchemaDocument {
+        &self.documents[0]
+    }
+
+    /// Returns all documents of the set, starting with the root document.
+    pub fn documents(&self) -> &[SchemaDocument] {
+        &self.documents
+    }
+
+    /// Returns the document loaded from `uri`.
+    ///
+    /// If a chameleon document is part of the set several times, the first
+    /// one is returned.
+    pub fn document(&self, uri: &str) -> Option<&SchemaDocument> {
+        self.documents.iter().find(|document| document.uri == uri)
+    }
+
+    /// Returns the target namespaces of the documents in the set, `None`
+    /// standing for documents without target namespace.
+    pub fn target_namespaces(&self) -> BTreeSet<Option<&str>> {
+        self.documents
+            .iter()
+            .map(|document| document.schema.effective_target_namespace())
+            .collect()
+    }
+
+    /// Returns the documents whose components belong to `namespace`,
+    /// including chameleon documents that took on `namespace`.
+    pub fn documents_in<'a>(
+        &'a self,
+        namespace: Option<&'a str>,
+    ) -> impl Iterator<Item = &'a SchemaDocument> {
+        self.documents
+            .iter()
+            .filter(move |document| document.schema.effective_target_namespace() == namespace)
+    }
+
+    /// Builds the [ComponentModel] of the set, in which the references
+    /// between components are resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns [SchemaError::UnresolvedReference] if a component refers to
+    /// a component that is not part of the set.
+    pub fn model(&self) -> Result<ComponentModel<'_>, SchemaError> {
+        model::build(self)
+    }
+
+    /// Checks the constraints the XML Schema specification puts on the
+    /// documents of the set, see [Schema::check], and on their components,
+    /// such as derivations being allowed by the base type and facets being
+    /// consistent with those of the base type.
+    ///
+    /// A reference that cannot be resolved is reported under `src-resolve`,
+    /// in which case the constraints on components are not checked. A
+    /// component in the body of an `xs:redefine` that does not replace a
+    /// component of the redefined document is reported under
+    /// `src-redefine.2`, and its component constraints are not checked.
+    ///
+    /// Bounds such as `minInclusive` are compared for numeric, date and
+    /// time types. They are not compared for durations, nor for a date or
+    /// time with a timezone against one without, so inconsistent bounds of
+    /// those go unreported. Constraints on values, such as a default value
+    /// being valid for its type, are not checked.
+    pub fn check(&self) -> Vec<Violation> {
+        check::set(self)
+    }
+
+    /// Looks up a top-level simple or complex type definition.
+    pub fn type_definition(&self, name: &ExpandedName) -> Option<TypeDefinition<'_>> {
+        self.lookup(SymbolSpace::Type, name)
+            .and_then(|location| self.type_definition_at(location))
+    }
+
+    /// Looks up a top-level element declaration.
+    pub fn element(&self, name: &ExpandedName) -> Option<&Element> {
+        self.lookup(SymbolSpace::Element, name)
+            .and_then(|location| self.element_at(location))
+    }
+
+    /// Looks up a top-level attribute declaration.
+    pub fn attribute(&self, name: &ExpandedName) -> Option<&Attribute> {
+        self.lookup(SymbolSpace::Attribute, name)
+            .and_then(|location| self.attribute_at(location))
+    }
+
+    /// Looks up a model group definition.
+    pub fn group(&self, name: &ExpandedName) -> Option<&Group> {
+        self.lookup(SymbolSpace::Group, name)
+            .and_then(|location| self.group_at(location))
+    }
+
+    /// Looks up an attribute group definition.
+    pub fn attribute_group(&self, name: &ExpandedName) -> Option<&AttributeGroup> {
+        self.lookup(SymbolSpace::AttributeGroup, name)
+            .and_then(|location| self.attribute_group_at(location))
+    }
+
+    /// Looks up a notation declaration.
+    pub fn notation(&self, name: &ExpandedName) -> Option<&Notation> {
+        self.lookup(SymbolSpace::Notation, name)
+            .and_then(|location| self.notation_at(location))
+    }
+
+    /// Looks up an identity constraint, which may be defined on any element
+    /// declaration, including local ones.
+    pub fn identity_constraint(&self, name: &ExpandedName) -> Option<IdentityConstraint<'_>> {
+        self.lookup(SymbolSpace::IdentityConstraint, name)
+            .and_then(|location| self.identity_constraint_at(location))
+    }
+
+    /// Looks up the type definition replaced by the redefinition of the
+    /// type named `name`.
+    ///
+    /// This is the type that the redefinition refers to through its own
+    /// name, as in `<xs:extension base="name">`, while
+    /// [SchemaSet::type_definition] returns the redefinition. Returns
+    /// `None` if the type has not been redefined.
+    pub fn redefined_type_definition(&self, name: &ExpandedName) -> Option<TypeDefinition<'_>> {
+        self.redefined(SymbolSpace::Type, name)
+            .and_then(|location| self.type_definition_at(location))
+    }
+
+    /// Looks up the model group definition replaced by the redefinition of
+    /// the group named `name`, see [SchemaSet::redefined_type_definition].
+    pub fn redefined_group(&self, name: &ExpandedName) -> Option<&Group> {
+        self.redefined(SymbolSpace::Group, name)
+            .and_then(|location| self.group_at(location))
+    }
+
+    /// Looks up the attribute group definition replaced by the
+    /// redefinition of the attribute group named `name`, see
+    /// [SchemaSet::redefined_type_definition].
+    pub fn redefined_attribute_group(&self, name: &ExpandedName) -> Option<&AttributeGroup> {
+        self.redefined(SymbolSpace::AttributeGroup, name)
+            .and_then(|location| self.attribute_group_at(location))
+    }
+
+    /// Iterates over the type definitions of the set, ordered by name.
+    pub fn type_definitions(&self) -> impl Iterator<Item = (&ExpandedName, TypeDefinition<'_>)> {
+        self.space(SymbolSpace::Type)
+            .filter_map(|(name, location)| Some((name, self.type_definition_at(location)?)))
+    }
+
+    /// Iterates over the top-level element declarations of the set,
+    /// ordered by name.
+    pub fn elements(&self) -> impl Iterator<Item = (&ExpandedName, &Element)> {
+        self.space(SymbolSpace::Element)
+            .filter_map(|(name, location)| Some((name, self.element_at(location)?)))
+    }
+
+    /// Iterates over the top-level attribute declarations of the set,
+    /// ordered by name.
+    pub fn attributes(&self) -> impl Iterator<Item = (&ExpandedName, &Attribute)> {
+        self.space(SymbolSpace::Attribute)
+            .filter_map(|(name, location)| Some((name, self.attribute_at(location)?)))
+    }
+
+    /// Iterates over the model group definitions of the set, ordered by
+    /// name.
+    pub fn groups(&self) -> impl Iterator<Item = (&ExpandedName, &Group)> {
+        self.space(SymbolSpace::Group)
+            .filter_map(|(name, location)| Some((name, self.group_at(location)?)))
+    }
+
+    /// Iterates over the attribute group definitions of the set, ordered by
+    /// name.
+    pub fn attribute_groups(&self) -> impl Iterator<Item = (&ExpandedName, &AttributeGroup)> {
+        self.space(SymbolSpace::AttributeGroup)
+            .filter_map(|(name, location)| Some((name, self.attribute_group_at(location)?)))
+    }
+
+    /// Iterates over the notation declarations of the set, ordered by name.
+    pub fn notations(&self) -> impl Iterator<Item = (&ExpandedName, &Notation)> {
+        self.space(SymbolSpace::Notation)
+            .filter_map(|(name, location)| Some((name, self.notation_at(location)?)))
+    }
+
+    /// Iterates over the identity constraints of the set, ordered by name.
+    pub fn identity_constraints(
+        &self,
+    ) -> impl Iterator<Item = (&ExpandedName, IdentityConstraint<'_>)> {
+        self.space(SymbolSpace::IdentityConstraint)
+            .filter_map(|(name, location)| Some((name, self.identity_constraint_at(location)?)))
+    }
+
+    /// Lists the components in the body of an `xs:redefine` that do not
+    /// replace a component of the redefined document, with their span.
+    pub(crate) fn unmatched_redefinitions(&self) -> Vec<(Component<'_>, &Span)> {
+        let mut unmatched = vec![];
+        for (document, schema_document) in self.documents.iter().enumerate() {
+            for (index, item) in schema_document.schema.body.iter().enumerate() {
+                let SchemaBody::Redefine(redefine) = item else {
+                    continue;
+                };
+                for (member, item) in redefine.body.iter().enumerate() {
+                    let location = Location {
+                        document,
+                        index,
+                        member: Some(member),
+                        position: 0,
+                    };
+                    let Some(component) = redefined_component(item) else {
+                        continue;
+                    };
+                    if component.name().is_some() && !self.redefined.contains_key(&location) {
+                        unmatched.push((component, component.span()));
+                    }
+                }
+            }
+        }
+        unmatched
+    }
+
+    fn lookup(&self, space: SymbolSpace, name: &ExpandedName) -> Option<&Location> {
+        self.components.get(&(space, name.clone()))
+    }
+
+    fn redefined(&self, space: SymbolSpace, name: &ExpandedName) -> Option<&Location> {
+        self.lookup(space, name)
+            .and_then(|location| self.redefined.get(location))
+    }
+
+    fn space(&self, space: SymbolSpace) -> impl Iterator<Item = (&ExpandedName, &Location)> {
+        let first = (space, ExpandedName::new(None::<String>, ""));
+        self.components
+            .range(first..)
+            .take_while(move |((candidate, _), _)| *candidate == space)
+            .map(|((_, name), location)| (name, location))
+    }
+
+    fn component(&self, location: &Location) -> Option<Component<'_>> {
+        component_at(&self.documents, location)
+    }
+
+    fn type_definition_at(&self, location: &Location) -> Option<TypeDefinition<'_>> {
+        match self.component(location)? {
+            Component::Type(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn element_at(&self, location: &Location) -> Option<&Element> {
+        match self.component(location)? {
+            Component::Element(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn attribute_at(&self, location: &Location) -> Option<&Attribute> {
+        match self.component(location)? {
+            Component::Attribute(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn group_at(&self, location: &Location) -> Option<&Group> {
+        match self.component(location)? {
+            Component::Group(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn attribute_group_at(&self, location: &Location) -> Option<&AttributeGroup> {
+        match self.component(location)? {
+            Component::AttributeGroup(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn notation_at(&self, location: &Location) -> Option<&Notation> {
+        match self.component(location)? {
+            Component::Notation(e) => Some(e),
+            _ => None,
+        }
+    }
+
+    fn identity_constraint_at(&self, location: &Location) -> Option<IdentityConstraint<'_>> {
+        self.component(location)?
+            .identity_constraints()
+            .into_iter()
+            .nth(location.position)
+    }
+}
+
+/// The kinds of named components, each of which has its own set of names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum SymbolSpace {
+    Type,
+    Element,
+    Attribute,
+    Group,
+    AttributeGroup,
+    Notation,
+    IdentityConstraint,
+}
+
+impl SymbolSpace {
+    pub(crate) fn description(self) -> &'static str {
+        match self {
+            SymbolSpace::Type => "type definition",
+            SymbolSpace::Element => "element declaration",
+            SymbolSpace::Attribute => "attribute declaration",
+            SymbolSpace::Group => "model group definition",
+            SymbolSpace::AttributeGroup => "attribute group definition",
+            SymbolSpace::Notation => "notation declaration",
+            SymbolSpace::IdentityConstraint => "identity constraint",
+        }
+    }
+}
+
+/// Location of a named component in a [SchemaSet].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Location {
+    pub(crate) document: usize,
+    /// Index of the top-level element in the body of the schema.
+    pub(crate) index: usize,
+    /// Index of the component in the body of the `xs:redefine` or
+    /// `xs:override` at `index`.
+    pub(crate) member: Option<usize>,
+    /// Position of an identity constraint among those defined within the
+    /// component.
+    pub(crate) position: usize,
+}
+
+/// A named component, as found in the body of a schema or of a
+/// redefinition.
+#[derive(Clone, Copy)]
+pub(crate) enum Component<'a> {
+    Type(TypeDefinition<'a>),
+    Element(&'a Element),
+    Attribute(&'a Attribute),
+    Group(&'a Group),
+    AttributeGroup(&'a AttributeGroup),
+    Notation(&'a Notation),
+}
+
+impl<'a> Component<'a> {
+    pub(crate) fn space(self) -> SymbolSpace {
+        match self {
+            Component::Type(_) => SymbolSpace::Type,
+            Component::Element(_) => SymbolSpace::Element,
+            Component::Attribute(_) => SymbolSpace::Attribute,
+            Component::Group(_) => SymbolSpace::Group,
+            Component::AttributeGroup(_) => SymbolSpace::AttributeGroup,
+            Component::Notation(_) => SymbolSpace::Notation,
+        }
+    }
+
+    pub(crate) fn name(self) -> Option<&'a NCName> {
+        match self {
+            Component::Type(e) => e.name(),
+            Component::Element(e) => e.name.as_ref(),
+            Component::Attribute(e) => e.name.as_ref(),
+            Component::Group(e) => e.name.as_ref(),
+            Component::AttributeGroup(e) => e.name.as_ref(),
+            Component::Notation(e) => Some(&e.name),
+        }
+    }
+
+    fn span(self) -> &'a Span {
+        match self {
+            Component::Type(e) => e.span(),
+            Component::Element(e) => e.span(),
+            Component::Attribute(e) => e.span(),
+            Component::Group(e) => e.span(),
+            Component::AttributeGroup(e) => e.span(),
+            Component::Notation(e) => e.span(),
+        }
+    }
+
+    /// Lists the identity constraints defined within the component, in
+    /// document order.
+    fn identity_constraints(self) -> Vec<IdentityConstraint<'a>> {
+        let mut elements = vec![];
+        match self {
+            Component::Element(e) => element_declarations(e, &mut elements),
+            Component::Type(TypeDefinition::Complex(e)) => {
+                complex_type_declarations(e, &mut elements)
+            }
+            Component::Group(e) => {
+                if let Some(particle) = e.particle() {
+                    particle_declarations(particle, &mut elements);
+                }
+            }
+            _ => {}
+        }
+        elements
+            .into_iter()
+            .flat_map(Element::identity_constraints)
+            .collect()
+    }
+}
+
+/// Returns the component at `location`.
+pub(crate) fn component_at<'a>(
+    documents: &'a [SchemaDocument],
+    location: &Location,
+) -> Option<Component<'a>> {
+    let item = &documents[location.document].schema.body[location.index];
+    match (item, location.member) {
+        (SchemaBody::Redefine(e), Some(member)) => redefined_component(&e.body[member]),
+        (SchemaBody::Override(e), Some(member)) => overriding_component(&e.body[member]),
+        (item, None) => top_level_component(item),
+        _ => None,
+    }
+}
+
+/// Returns the kind and name of the component at `location`.
+fn component_key<'a>(
+    documents: &'a [SchemaDocument],
+    location: &Location,
+) -> Option<(SymbolSpace, &'a NCName)> {
+    let component = component_at(documents, location)?;
+    Some((component.space(), component.name()?))
+}
+
+fn top_level_component(item: &SchemaBody) -> Option<Component<'_>> {
+    match item {
+        SchemaBody::SimpleType(e) => Some(Component::Type(TypeDefinition::Simple(e))),
+        SchemaBody::ComplexType(e) => Some(Component::Type(TypeDefinition::Complex(e))),
+        SchemaBody::Element(e) => Some(Component::Element(e)),
+        SchemaBody::Attribute(e) => Some(Component::Attribute(e)),
+        SchemaBody::Group(e) => Some(Component::Group(e)),
+        SchemaBody::AttributeGroup(e) => Some(Component::AttributeGroup(e)),
+        SchemaBody::Notation(e) => Some(Component::Notation(e)),
+        _ => None,
+    }
+}
+
+fn redefined_component(item: &RedefineBody) -> Option<Component<'_>> {
+    match item {
+        RedefineBody::SimpleType(e) => Some(Component::Type(TypeDefinition::Simple(e))),
+        RedefineBody::ComplexType(e) => Some(Component::Type(TypeDefinition::Complex(e))),
+        RedefineBody::Group(e) => Some(Component::Group(e)),
+        RedefineBody::AttributeGroup(e) => Some(Component::AttributeGroup(e)),
+        RedefineBody::Annotation(_) => None,
+    }
+}
+
+fn overriding_component(item: &OverrideBody) -> Option<Component<'_>> {
+    match item {
+        OverrideBody::SimpleType(e) => Some(Component::Type(TypeDefinition::Simple(e))),
+        OverrideBody::ComplexType(e) => Some(Component::Type(TypeDefinition::Complex(e))),
+        OverrideBody::Element(e) => Some(Component::Element(e)),
+        OverrideBody::Attribute(e) => Some(Component::Attribute(e)),
+        OverrideBody::Group(e) => Some(Component::Group(e)),
+        OverrideBody::AttributeGroup(e) => Some(Component::AttributeGroup(e)),
+        OverrideBody::Notation(e) => Some(Component::Notation(e)),
+        OverrideBody::Annotation(_) => None,
+    }
+}
+
+/// The named components of a set, and the components replaced by
+/// redefinitions.
+struct Tables {
+    components: BTreeMap<(SymbolSpace, ExpandedName), Location>,
+    redefined: HashMap<Location, Location>,
+}
+
+/// Builds the lookup tables of the named components of all documents.
+///
+/// The components of a document are replaced by the overriding components
+/// that apply to the document, if any. The components in the body of an
+/// `xs:redefine` replace the components
+/// with the same name once all documents have been read. Redefinitions in
+/// documents loaded later are applied first, so that a document that
+/// redefines a document that redefines another one gets the last word.
+///
+/// # Errors
+///
+/// Returns [SchemaError::DuplicateDefinition] if two components of the
+/// same kind have the same expanded name.
+fn tables(documents: &[SchemaDocument]) -> Result<Tables, SchemaError> {
+    let mut components: BTreeMap<_, (Location, &Span)> = BTreeMap::new();
+    let mut redefinitions = vec![];
+    for (document, schema_document) in documents.iter().enumerate() {
+        let schema = &schema_document.schema;
+        let namespace = schema.effective_target_namespace();
+        let mut named = vec![];
+        for (index, item) in schema.body.iter().enumerate() {
+            let location = Location {
+                document,
+                index,
+                member: None,
+                position: 0,
+            };
+            if let SchemaBody::Redefine(redefine) = item {
+                for (member, item) in redefine.body.iter().enumerate() {
+                    let location = Location {
+                        member: Some(member),
+                        ..location
+                    };
+                    if let Some(component) = redefined_component(item) {
+                        // Replacements are applied once all originals are known.
+                        redefinitions.push((namespace, location, component));
+                        named.extend(nested_names(location, component));
+                    }
+                }
+            } else if let Some(component) = top_level_component(item) {
+                let key = component.name().map(|name| (component.space(), name));
+                let overriding = schema_document.overrides.iter().find_map(|location| {
+                    let matches = key.is_some() && component_key(documents, location) == key;
+                    matches.then(|| Some((*location, component_at(documents, location)?)))?
+                });
+                let (location, component) = overriding.unwrap_or((location, component));
+                let name = component.name();
+                named.push((location, component.space(), name, component.span()));
+                named.extend(nested_names(location, component));
+            }
+        }
+
+        for (location, space, name, span) in named {
+            let Some(name) = name else { continue };
+            let key = (space, ExpandedName::new(namespace, name.as_str()));
+            match components.entry(key) {
+                Entry::Vacant(entry) => {
+                    entry.insert((location, span));
+                }
+                // An overriding component that replaces components in
+                // several documents.
+                Entry::Occupied(entry) if entry.get().0 == location => {}
+                Entry::Occupied(entry) => {
+                    let ((space, name), (_, first)) = entry.remove_entry();
+                    return Err(SchemaError::DuplicateDefinition {
+                        kind: space.description(),
+                        name: name.to_string(),
+                        first: Box::new(first.clone()),
+                        duplicate: Box::new(span.clone()),
+                    });
+                }
+            }
+        }
+    }
+
+    let mut redefined = HashMap::new();
+    for (namespace, location, component) in redefinitions.into_iter().rev() {
+        let Some(name) = component.name() else {
+            continue;
+        };
+        let key = (
+            component.space(),
+            ExpandedName::new(namespace, name.as_str()),
+        );
+        if let Some((original, _)) = components.insert(key, (location, component.span())) {
+            redefined.insert(location, original);
+        }
+    }
+    let components = components
+        .into_iter()
+        .map(|(key, (location, _))| (key, location))
+        .collect();
+    Ok(Tables {
+        components,
+        redefined,
+    })
+}
+
+/// Lists the identity constraints defined within a component, together
+/// with their location.
+fn nested_names(
+    location: Location,
+    component: Component<'_>,
+) -> impl Iterator<Item = (Location, SymbolSpace, Option<&NCName>, &Span)> {
+    let constraints = component.identity_constraints().into_iter().enumerate();
+    constraints.map(move |(position, constraint)| {
+        let location = Location {
+            position,
+            ..location
+        };
+        let space = SymbolSpace::IdentityConstraint;
+        (location, space, constraint.name(), constraint.span())
+    })
+}
+
+/// Collects an element declaration and the ones nested in its type.
+fn element_declarations<'a>(element: &'a Element, elements: &mut Vec<&'a Element>) {
+    elements.push(element);
+    let alternatives = element
+        .alternatives()
+        .into_iter()
+        .filter_map(|alternative| alternative.complex_type());
+    for complex_type in element.complex_type().into_iter().chain(alternatives) {
+        complex_type_declarations(complex_type, elements);
+    }
+}
+
+/// Collects the element declarations in the content model of a complex
+/// type.
+fn complex_type_declarations<'a>(complex_type: &'a ComplexType, elements: &mut Vec<&'a Element>) {
+    let derived = complex_type
+        .complex_content()
+        .and_then(|content| content.derivation())
+        .and_then(|derivation| match derivation {
+            Derivation::Restriction(e) => e.particle(),
+            Derivation::Extension(e) => e.particle(),
+        });
+    if let Some(particle) = complex_type.particle().or(derived) {
+        particle_declarations(particle, elements);
+    }
+}
+
+/// Collects the element declarations in a particle.
+fn particle_declarations<'a>(particle: Particle<'a>, elements: &mut Vec<&'a Element>) {
+    let items = match particle {
+        Particle::Element(e) => return element_declarations(e, elements),
+        Particle::Group(e) => e.particle().into_iter().collect(),
+        Particle::Sequence(e) => e.items(),
+        Particle::Choice(e) => e.items(),
+        Particle::All(e) => e.items(),
+        Particle::Any(_) => vec![],
+    };
+    for item in items {
+        particle_declarations(item, elements);
+    }
+}
+
+/// Key of a document in a set: its URI, the namespace it took on if it is
+/// a chameleon, and the overriding components that apply to it.
+type DocumentKey = (String, Option<AnyURI>, Vec<Location>);
+
+/// What the loader needs to know about a document that has already been
+/// read to find out whether it can be reused.
+struct Summary {
+    /// Whether the document has a target namespace, i.e. whether it can be
+    /// a chameleon.
+    has_target_namespace: bool,
+    /// Whether the document includes or overrides other documents, which
+    /// overriding components propagate to.
+    composes: bool,
+    /// Kinds and names of the top-level components of the document.
+    names: BTreeSet<(SymbolSpace, NCName)>,
+}
+
+impl Summary {
+    fn new(schema: &Schema) -> Self {
+        let names = schema
+            .body
+            .iter()
+            .filter_map(top_level_component)
+            .filter_map(|component| Some((component.space(), component.name()?.clone())))
+            .collect();
+        let composes = schema
+            .body
+            .iter()
+            .any(|item| matches!(item, SchemaBody::Include(_) | SchemaBody::Override(_)));
+        Summary {
+            has_target_namespace: schema.target_namespace.is_some(),
+            composes,
+            names,
+        }
+    }
+
+    /// Drops the overriding components that do not change the document,
+    /// so that a document is only loaded again if it is actually
+    /// transformed by an override.
+    fn applicable(&self, documents: &[SchemaDocument], overrides: &[Location]) -> Vec<Location> {
+        let applies = |location: &&Location| {
+            self.composes
+                || component_key(documents, location)
+                    .is_some_and(|(space, name)| self.names.contains(&(space, name.clone())))
+        };
+        overrides.iter().filter(applies).copied().collect()
+    }
+}
+
+struct Loader<'a, R> {
+    resolver: &'a R,
+    documents: Vec<SchemaDocument>,
+    index: HashMap<DocumentKey, usize>,
+    summaries: HashMap<String, Summary>,
+}
+
+impl<R: SchemaResolver> Loader<'_, R> {
+    /// Returns the overriding components that apply to the document
+    /// referenced by the element at `index` in the body of document `from`.
+    ///
+    /// Overrides propagate through includes and overrides. The components
+    /// of an override apply in addition to the ones that apply to the
+    /// overriding document, which win if both replace the same component.
+    fn overrides(&self, from: usize, index: usize, kind: ReferenceKind) -> Vec<Location> {
+        let inherited = &self.documents[from].overrides;
+        let mut overrides = match kind {
+            ReferenceKind::Include | ReferenceKind::Override => inherited.clone(),
+            ReferenceKind::Import | ReferenceKind::Redefine => return vec![],
+        };
+        if let SchemaBody::Override(e) = &self.documents[from].schema.body[index] {
+            for member in 0..e.body.len() {
+                let location = Location {
+                    document: from,
+                    index,
+                    member: Some(member),
+                    position: 0,
+                };
+                let Some(key) = component_key(&self.documents, &location) else {
+                    continue;
+                };
+                let documents = &self.documents;
+                if !inherited
+                    .iter()
+                    .any(|inherited| component_key(documents, inherited) == Some(key))
+                {
+                    overrides.push(location);
+                }
+            }
+        }
+        overrides.sort();
+        overrides
+    }
+
+    /// Loads the document referenced by document `from`, returning its
+    /// index. `imported` is the namespace of an `xs:import`, `overrides`
+    /// are the overriding components that apply to the document.
+    ///
+    /// Returns `None` for an import without `schemaLocation` that the
+    /// resolver cannot locate by its namespace, which is skipped.
+    fn load(
+        &mut self,
+        from: usize,
+        kind: ReferenceKind,
+        imported: Option<&str>,
+        location: Option<&str>,
+        overrides: Vec<Location>,
+    ) -> Result<Option<usize>, SchemaError> {
+        let including = &self.documents[from];
+        let base = Some(including.uri.as_str());
+        let source = match (kind, location) {
+            (ReferenceKind::Import, _) => self.resolver.resolve_import(base, imported, location),
+            (_, Some(location)) => self.resolver.resolve(base, location),
+            (_, None) => return Ok(None),
+        };
+        let source = match (source, location) {
+            (Ok(source), _) => source,
+            // An import without location may just name the namespace of
+            // the components it refers to.
+            (Err(_), None) => return Ok(None),
+            (Err(error), Some(location)) => return Err(unresolved(base, location, error)),
+        };
+        // Errors reading an import without location refer to its namespace.
+        let location = location.or(imported).unwrap_or_default();
+        let uri = source.uri.clone();
+        // Imported documents keep their own target namespace.
+        let namespace = match kind {
+            ReferenceKind::Import => None,
+            _ => including.schema.effective_target_namespace_uri().cloned(),
+        };
+        let in_including = |error| SchemaError::InDocument {
+            uri: including.uri.clone(),
+            error: Box::new(error),
+        };
+
+        let loaded = self.summaries.get(&uri).and_then(|summary| {
+            let chameleon = if summary.has_target_namespace {
+                None
+            } else {
+                namespace.clone()
+            };
+            let overrides = summary.applicable(&self.documents, &overrides);
+            self.index.get(&(uri.clone(), chameleon, overrides))
+        });
+        if let Some(&document) = loaded {
+            if kind != ReferenceKind::Import {
+                let schema = &self.documents[document].schema;
+                schema
+                    .check_include_namespace(namespace.as_ref())
+                    .map_err(in_including)?;
+            }
+            return Ok(Some(document));
+        }
+
+        let mut schema = parse(&uri, &read(source, Some(&including.uri), location)?)?;
+        if kind != ReferenceKind::Import {
+            schema
+                .include_into_namespace(namespace.as_ref())
+                .map_err(in_including)?;
+        }
+        let chameleon = schema.chameleon_namespace().cloned();
+        let summary = Summary::new(&schema);
+        let overrides = summary.applicable(&self.documents, &overrides);
+        self.summaries.insert(uri.clone(), summary);
+        Ok(Some(self.add(uri, chameleon, overrides, schema)))
+    }
+
+    fn add(
+        &mut self,
+        uri: String,
+        chameleon: Option<AnyURI>,
+        overrides: Vec<Location>,
+        schema: Schema,
+    ) -> usize {
+        let document = self.documents.len();
+        if !self.summaries.contains_key(&uri) {
+            self.summaries.insert(uri.clone(), Summary::new(&schema));
+        }
+        let key = (uri.clone(), chameleon, overrides.clone());
+        self.index.insert(key, document);
+        self.documents.push(SchemaDocument {
+            uri,
+            schema,
+            references: vec![],
+            overrides,
+        });
+        document
+    }
+}
+
+/// Reads a schema document loaded from `uri`.
+fn parse(uri: &str, source: &str) -> Result<Schema, SchemaError> {
+    Document::parse(source, Some(Arc::from(uri)))
+        .and_then(|document| document.deserialize())
+        .map_err(|error| SchemaError::InDocument {
+            uri: uri.to_string(),
+            error: Box::new(error),
+        })
+}
+
+/// Lists the schema locations a schema refers to, together with the index
+/// of the referencing element in the body of the schema and the namespace
+/// of imports.
+fn referenced_locations(
+    schema: &Schema,
+) -> Vec<(usize, ReferenceKind, Option<AnyURI>, Option<AnyURI>)> {
+    let references = schema.body.iter().enumerate();
+    references
+        .filter_map(|(index, item)| match item {
+            SchemaBody::Include(e) => {
+                let location = Some(e.schema_location.clone());
+                Some((index, ReferenceKind::Include, None, location))
+            }
+            SchemaBody::Import(e) => {
+                let location = e.schema_location.clone();
+                Some((index, ReferenceKind::Import, e.namespace.clone(), location))
+            }
+            SchemaBody::Redefine(e) => {
+                let location = Some(e.schema_location.clone());
+                Some((index, ReferenceKind::Redefine, None, location))
+            }
+            SchemaBody::Override(e) => {
+                let location = Some(e.schema_location.clone());
+                Some((index, ReferenceKind::Override, None, location))
+            }
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,40 @@
+//! Source locations of schema components.
+use std::{fmt, sync::Arc};
+
+use serde::Deserialize;
+
+/// Location of a schema component in the document it was read from.
+///
+/// A span covers the whole element that defines the component, from the
+/// `<` of its start tag up to and including the `>` of its end tag (or of
+/// the start tag itself for empty elements).
+#[derive(Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Span {
+    /// Name of the source document, if it was read from a named source
+    /// such as a file.
+    pub file: Option<Arc<str>>,
+    /// 1-based line of the start tag.
+    pub line: usize,
+    /// 1-based column (in characters) of the start tag.
+    pub column: usize,
+    /// Byte offset of the start of the element in the source document.
+    pub start: usize,
+    /// Byte offset just past the end of the element in the source document.
+    pub end: usize,
+}
+
+impl Span {
+    /// Returns the byte range covered by the span.
+    pub fn range(&self) -> std::ops::Range<usize> {
+        self.start..self.end
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{file}:")?;
+        }
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
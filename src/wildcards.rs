@@ -0,0 +1,152 @@
+//! Wildcards, which allow elements or attributes by their namespace rather
+//! than by their name.
+//!
+//! The `namespace` and `notNamespace` attributes of `xs:any` and
+//! `xs:anyAttribute` are given as lists of URIs and keywords such as
+//! `##targetNamespace`. A [Wildcard] holds the set of namespaces they
+//! denote, which is what combining wildcards works on: the attribute
+//! wildcard of a complex type is the [intersection](Wildcard::intersection)
+//! of those of the type and its attribute groups, and an extension adds the
+//! wildcard of its base type through a [union](Wildcard::union).
+//!
+//! The `notQName` attribute is not taken into account.
+use std::collections::BTreeSet;
+
+use crate::{particles::Any, AnyAttribute, ProcessContents};
+
+/// The namespaces a [Wildcard] allows. A namespace of `None` stands for
+/// names without namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NamespaceConstraint {
+    /// Any namespace, as well as no namespace.
+    Any,
+    /// Only the listed namespaces.
+    Enumeration(BTreeSet<Option<String>>),
+    /// All but the listed namespaces.
+    Not(BTreeSet<Option<String>>),
+}
+
+impl NamespaceConstraint {
+    /// Returns whether the constraint allows names in `namespace`.
+    pub fn allows(&self, namespace: Option<&str>) -> bool {
+        let namespace = namespace.map(str::to_string);
+        match self {
+            NamespaceConstraint::Any => true,
+            NamespaceConstraint::Enumeration(namespaces) => namespaces.contains(&namespace),
+            NamespaceConstraint::Not(namespaces) => !namespaces.contains(&namespace),
+        }
+    }
+}
+
+/// An element or attribute wildcard.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wildcard {
+    /// The namespaces of the names the wildcard allows.
+    pub namespaces: NamespaceConstraint,
+    /// How the elements or attributes the wildcard allows are validated.
+    pub process_contents: ProcessContents,
+}
+
+impl Wildcard {
+    /// Returns the wildcard given by an `xs:anyAttribute` in a schema
+    /// whose target namespace is `target_namespace`.
+    pub fn from_any_attribute(e: &AnyAttribute, target_namespace: Option<&str>) -> Self {
+        Self::new(
+            e.namespace.as_deref(),
+            e.not_namespace.as_deref(),
+            e.process_contents,
+            target_namespace,
+        )
+    }
+
+    /// Returns the wildcard given by an `xs:any` in a schema whose target
+    /// namespace is `target_namespace`.
+    pub fn from_any(e: &Any, target_namespace: Option<&str>) -> Self {
+        Self::new(
+            e.namespace.as_deref(),
+            e.not_namespace.as_deref(),
+            e.process_contents,
+            target_namespace,
+        )
+    }
+
+    fn new(
+        namespace: Option<&str>,
+        not_namespace: Option<&str>,
+        process_contents: Option<ProcessContents>,
+        target_namespace: Option<&str>,
+    ) -> Self {
+        let list = |value: &str| -> BTreeSet<Option<String>> {
+            let uris = value.split_ascii_whitespace();
+            uris.map(|uri| match uri {
+                "##targetNamespace" => target_namespace.map(str::to_string),
+                "##local" => None,
+                uri => Some(uri.to_string()),
+            })
+            .collect()
+        };
+        let namespaces = match (namespace.map(str::trim), not_namespace) {
+            (Some("##any"), _) => NamespaceConstraint::Any,
+            (Some("##other"), _) => {
+                let mut namespaces = BTreeSet::from([None]);
+                namespaces.insert(target_namespace.map(str::to_string));
+                NamespaceConstraint::Not(namespaces)
+            }
+            (Some(namespace), _) => NamespaceConstraint::Enumeration(list(namespace)),
+            (None, Some(not_namespace)) => NamespaceConstraint::Not(list(not_namespace)),
+            (None, None) => NamespaceConstraint::Any,
+        };
+        Wildcard {
+            namespaces,
+            process_contents: process_contents.unwrap_or(ProcessContents::Strict),
+        }
+    }
+
+    /// Returns whether the wildcard allows names in `namespace`.
+    pub fn allows(&self, namespace: Option<&str>) -> bool {
+        self.namespaces.allows(namespace)
+    }
+
+    /// Returns the wildcard that allows the names either wildcard allows,
+    /// with the `processContents` of `self`.
+    pub fn union(&self, other: &Wildcard) -> Wildcard {
+        use NamespaceConstraint::*;
+        let negation = |namespaces: BTreeSet<_>| {
+            if namespaces.is_empty() {
+                Any
+            } else {
+                Not(namespaces)
+            }
+        };
+        let namespaces = match (&self.namespaces, &other.namespaces) {
+            (Any, _) | (_, Any) => Any,
+            (Enumeration(a), Enumeration(b)) => Enumeration(a.union(b).cloned().collect()),
+            (Not(a), Not(b)) => negation(a.intersection(b).cloned().collect()),
+            (Not(not), Enumeration(allowed)) | (Enumeration(allowed), Not(not)) => {
+                negation(not.difference(allowed).cloned().collect())
+            }
+        };
+        Wildcard {
+            namespaces,
+            process_contents: self.process_contents,
+        }
+    }
+
+    /// Returns the wildcard that allows the names both wildcards allow,
+    /// with the `processContents` of `self`.
+    pub fn intersection(&self, other: &Wildcard) -> Wildcard {
+        use NamespaceConstraint::*;
+        let namespaces = match (&self.namespaces, &other.namespaces) {
+            (Any, namespaces) | (namespaces, Any) => namespaces.clone(),
+            (Enumeration(a), Enumeration(b)) => Enumeration(a.intersection(b).cloned().collect()),
+            (Not(a), Not(b)) => Not(a.union(b).cloned().collect()),
+            (Not(not), Enumeration(allowed)) | (Enumeration(allowed), Not(not)) => {
+                Enumeration(allowed.difference(not).cloned().collect())
+            }
+        };
+        Wildcard {
+            namespaces,
+            process_contents: self.process_contents,
+        }
+    }
+}
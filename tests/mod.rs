@@ -1,11 +1,26 @@
 use std::{fs::File, io::BufReader};
 
-use schematis::Schema;
+use schematis::{
+    basics::{NCName, QName},
+    builtins::{Cardinality, Ordered, Variety},
+    catalog::{Catalog, CatalogResolver},
+    facets::{Facet, FacetKind},
+    model::{
+        AttributeUse, Compositor, ContentParticle, DerivationConflict, DerivationMethod,
+        ElementDeclaration, Term,
+    },
+    namespaces::{ExpandedName, XSD_NAMESPACE},
+    particles::{IdentityConstraint, MaxOccurs, Particle},
+    resolver::{FileResolver, MemoryResolver},
+    set::{ReferenceKind, TypeDefinition},
+    wildcards::NamespaceConstraint,
+    Derivation, ProcessContents, Schema, SchemaError, SchemaSet, SimpleTypeContent,
+};
 
 fn read_xsd(path: &str) -> Schema {
     let file = File::open(path).unwrap();
     let reader = BufReader::new(file);
-    Schema::from_reader(reader)
+    Schema::from_reader(reader).unwrap()
 }
 
 #[test]
@@ -61,3 +76,1870 @@ fn deserialize_oasis_br_2() {
     let path = "tests/data/br-2.xsd";
     let _schema = read_xsd(path);
 }
+
+#[test]
+fn malformed_xml_is_reported_with_path() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:element name="a"></xs:elem>
+    </xs:schema>"#;
+    let error = Schema::from_reader(xsd.as_bytes()).unwrap_err();
+    assert!(matches!(error, SchemaError::Xml { .. }), "{error}");
+    assert_eq!(error.path(), Some("/xs:schema/xs:element[1]"));
+}
+
+#[test]
+fn unknown_element_is_reported_as_unsupported() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:complexType name="a"/>
+        <xs:complexType name="b">
+            <xs:sequence>
+                <xs:frobnicate/>
+            </xs:sequence>
+        </xs:complexType>
+    </xs:schema>"#;
+    let error = Schema::from_reader(xsd.as_bytes()).unwrap_err();
+    match error {
+        SchemaError::UnsupportedConstruct { path, name } => {
+            assert_eq!(
+                path,
+                "/xs:schema/xs:complexType[2]/xs:sequence[1]/xs:frobnicate[1]"
+            );
+            assert_eq!(name, "frobnicate");
+        }
+        error => panic!("unexpected error: {error}"),
+    }
+}
+
+#[test]
+fn invalid_attribute_value_is_reported() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:element name="a" form="sometimes"/>
+    </xs:schema>"#;
+    let error = Schema::from_reader(xsd.as_bytes()).unwrap_err();
+    match error {
+        SchemaError::InvalidValue {
+            path, attribute, ..
+        } => {
+            assert_eq!(path, "/xs:schema/xs:element[1]");
+            assert_eq!(attribute.as_deref(), Some("form"));
+        }
+        error => panic!("unexpected error: {error}"),
+    }
+}
+
+#[test]
+fn components_record_their_source_span() {
+    let xsd = "<xs:schema xmlns:xs=\"http://www.w3.org/2001/XMLSchema\" targetNamespace=\"urn:t\">\n  <xs:simpleType name=\"a\">\n    <xs:restriction base=\"xs:string\">\n      <xs:enumeration value=\"x\"/>\n    </xs:restriction>\n  </xs:simpleType>\n</xs:schema>";
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    let simple_type = schema.simple_types()[0];
+    let span = simple_type.span();
+    assert_eq!((span.line, span.column), (2, 3));
+    assert!(xsd[span.range()].starts_with("<xs:simpleType"));
+    assert!(xsd[span.range()].ends_with("</xs:simpleType>"));
+
+    let SimpleTypeContent::Restriction(restriction) = simple_type.content().unwrap() else {
+        panic!("expected a restriction");
+    };
+    let Facet::Enumeration(enumeration) = &restriction.facets()[0] else {
+        panic!("expected an enumeration");
+    };
+    assert_eq!(
+        &xsd[enumeration.span().range()],
+        "<xs:enumeration value=\"x\"/>"
+    );
+    assert_eq!(enumeration.span().line, 4);
+}
+
+#[test]
+fn spans_carry_the_file_name() {
+    let schema = Schema::from_file("tests/data/ws-addr.xsd").unwrap();
+    let element = schema.elements()[0];
+    assert_eq!(
+        element.span().file.as_deref(),
+        Some("tests/data/ws-addr.xsd")
+    );
+    assert!(element
+        .span()
+        .to_string()
+        .starts_with("tests/data/ws-addr.xsd:"));
+}
+
+#[test]
+fn lenient_mode_skips_unknown_and_malformed_constructs() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:t">
+        <xs:element name="kept"/>
+        <xs:vendorExtension foo="bar"/>
+        <xs:element name="bad-form" form="sometimes"/>
+        <xs:complexType name="t" vendor="x">
+            <xs:sequence>
+                <xs:element name="inner"/>
+                <xs:frobnicate/>
+            </xs:sequence>
+        </xs:complexType>
+    </xs:schema>"#;
+    assert!(Schema::from_reader(xsd.as_bytes()).is_err());
+
+    let (schema, warnings) = Schema::from_reader_lenient(xsd.as_bytes()).unwrap();
+    let names: Vec<_> = schema
+        .elements()
+        .iter()
+        .map(|e| e.name.clone().unwrap())
+        .collect();
+    assert_eq!(names, ["kept"]);
+    let complex_type = schema.complex_types()[0];
+    assert_eq!(complex_type.sequence().unwrap().items().len(), 1);
+
+    let dropped: Vec<_> = warnings
+        .iter()
+        .map(|w| match &w.error {
+            SchemaError::UnsupportedConstruct { name, .. } => name.clone(),
+            SchemaError::InvalidValue { attribute, .. } => attribute.clone().unwrap(),
+            error => panic!("unexpected error: {error}"),
+        })
+        .collect();
+    assert_eq!(
+        dropped,
+        ["vendorExtension", "form", "@vendor", "frobnicate"]
+    );
+    assert_eq!(warnings[0].span.line, 3);
+}
+
+#[test]
+fn foreign_attributes_are_preserved() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:ext="urn:ext"
+            targetNamespace="urn:t" xml:lang="en">
+        <xs:element name="order" ext:javaName="PurchaseOrder" xml:lang="nl"/>
+        <xs:complexType name="t" ext:final="yes">
+            <xs:sequence ext:name="seq"/>
+        </xs:complexType>
+    </xs:schema>"#;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    assert_eq!(schema.xml_lang.as_deref(), Some("en"));
+    assert!(schema.foreign_attributes().is_empty());
+
+    let element = schema.elements()[0];
+    let attributes: Vec<_> = element
+        .foreign_attributes()
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.as_str()))
+        .collect();
+    assert_eq!(
+        attributes,
+        [
+            (
+                "{http://www.w3.org/XML/1998/namespace}lang".to_string(),
+                "nl"
+            ),
+            ("{urn:ext}javaName".to_string(), "PurchaseOrder"),
+        ]
+    );
+
+    let complex_type = schema.complex_types()[0];
+    assert_eq!(complex_type.name.as_deref(), Some("t"));
+    let (name, value) = complex_type.foreign_attributes().iter().next().unwrap();
+    assert_eq!(
+        (name.namespace(), name.local_name()),
+        (Some("urn:ext"), "final")
+    );
+    assert_eq!(value, "yes");
+    let sequence = complex_type.sequence().unwrap();
+    assert_eq!(
+        sequence.foreign_attributes().values().next().unwrap(),
+        "seq"
+    );
+}
+
+#[test]
+fn undeclared_prefix_is_malformed_xml() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:t">
+        <xs:element name="a" ext:javaName="A"/>
+    </xs:schema>"#;
+    let error = Schema::from_reader(xsd.as_bytes()).unwrap_err();
+    assert!(matches!(error, SchemaError::Xml { .. }));
+    assert_eq!(error.path(), Some("/xs:schema/xs:element[1]"));
+}
+
+#[test]
+fn chameleon_include_takes_on_target_namespace() {
+    let chameleon = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:simpleType name="code">
+            <xs:restriction base="xs:string"/>
+        </xs:simpleType>
+    </xs:schema>"#;
+    let including = Schema::from_reader(
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:t">
+            <xs:include schemaLocation="chameleon.xsd"/>
+        </xs:schema>"#
+            .as_bytes(),
+    )
+    .unwrap();
+
+    let mut included = Schema::from_reader(chameleon.as_bytes()).unwrap();
+    assert_eq!(included.target_namespace, None);
+    assert_eq!(included.effective_target_namespace(), None);
+    included.include_into(&including).unwrap();
+    assert!(included.is_chameleon());
+    assert_eq!(included.effective_target_namespace(), Some("urn:t"));
+
+    let mut other = Schema::from_reader(
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:other"/>"#
+            .as_bytes(),
+    )
+    .unwrap();
+    let error = other.include_into(&including).unwrap_err();
+    assert!(matches!(
+        error,
+        SchemaError::TargetNamespaceMismatch { ref found, .. } if found == "urn:other"
+    ));
+}
+
+#[test]
+fn qualified_names_resolve_against_in_scope_bindings() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+            xmlns:tns="urn:t" xmlns="urn:default" targetNamespace="urn:t">
+        <xs:complexType name="t">
+            <xs:sequence xmlns:other="urn:other">
+                <xs:element name="a" type="other:Address"/>
+                <xs:element name="b" type="tns:Code" xmlns:tns="urn:shadowed"/>
+                <xs:element name="c" type="Plain"/>
+            </xs:sequence>
+        </xs:complexType>
+    </xs:schema>"#;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    assert_eq!(schema.namespaces().namespace("tns"), Some("urn:t"));
+    assert_eq!(schema.namespaces().namespace("other"), None);
+
+    let complex_type = schema.complex_types()[0];
+    let sequence = complex_type.sequence().unwrap();
+    let resolved: Vec<_> = sequence
+        .items()
+        .iter()
+        .map(|item| match item {
+            Particle::Element(e) => *e,
+            _ => unreachable!(),
+        })
+        .map(|e| {
+            e.resolve_qname(e.r#type.as_ref().unwrap())
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        resolved,
+        [
+            "{urn:other}Address",
+            "{urn:shadowed}Code",
+            "{urn:default}Plain"
+        ]
+    );
+    assert_eq!(
+        sequence.resolve_qname(&"missing:Foo".parse().unwrap()),
+        None
+    );
+    assert_eq!(
+        sequence
+            .resolve_qname(&QName::new("xs:string").unwrap())
+            .unwrap()
+            .namespace(),
+        Some("http://www.w3.org/2001/XMLSchema")
+    );
+}
+
+#[test]
+fn lexical_values_are_validated() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" version="  1.0
+            beta ">
+        <xs:element name="1bad name"/>
+    </xs:schema>"#;
+    let error = Schema::from_reader(xsd.as_bytes()).unwrap_err();
+    assert!(matches!(
+        error,
+        SchemaError::InvalidValue { ref attribute, .. } if attribute.as_deref() == Some("name")
+    ));
+
+    let schema = Schema::from_reader(xsd.replace("1bad name", "good").as_bytes()).unwrap();
+    assert_eq!(schema.version.as_ref().unwrap(), "1.0 beta");
+    assert_eq!(schema.elements()[0].name.as_ref().unwrap(), "good");
+
+    let qname = QName::new("tns:Address").unwrap();
+    assert_eq!(qname.prefix(), Some("tns"));
+    assert_eq!(qname.local_name(), "Address");
+    assert_eq!(QName::new("Address").unwrap().prefix(), None);
+    assert!(QName::new("tns:").is_err());
+    assert!(QName::new("a:b:c").is_err());
+    assert!(NCName::new("a.b-c_d").is_ok());
+    assert!(NCName::new("-a").is_err());
+    assert!(NCName::new("a:b").is_err());
+    assert!(NCName::new("e\u{301}t\u{e9}\u{203f}1").is_ok());
+    assert!(NCName::new("\u{203f}a").is_err());
+    assert!(NCName::new("a\u{b2}").is_err());
+    assert!(NCName::new("\u{aa}").is_err());
+}
+
+#[test]
+fn element_accessors_expose_body_and_occurrence() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:element name="catalog">
+            <xs:annotation><xs:documentation>A catalog</xs:documentation></xs:annotation>
+            <xs:complexType>
+                <xs:sequence>
+                    <xs:element name="item" type="xs:string" minOccurs="0" maxOccurs="unbounded"/>
+                    <xs:element name="count" maxOccurs="3">
+                        <xs:simpleType><xs:restriction base="xs:int"/></xs:simpleType>
+                    </xs:element>
+                </xs:sequence>
+            </xs:complexType>
+            <xs:key name="k"><xs:selector xpath="item"/><xs:field xpath="@id"/></xs:key>
+            <xs:unique name="u"><xs:selector xpath="item"/><xs:field xpath="."/></xs:unique>
+            <xs:keyref name="r" refer="k"><xs:selector xpath="item"/><xs:field xpath="@ref"/></xs:keyref>
+        </xs:element>
+    </xs:schema>"#;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    let catalog = schema.elements()[0];
+    assert!(catalog.annotation().is_some());
+    assert!(catalog.simple_type().is_none());
+    assert_eq!(catalog.keys().len(), 1);
+    assert_eq!(catalog.uniques().len(), 1);
+    assert_eq!(catalog.keyrefs().len(), 1);
+    let kinds: Vec<_> = catalog
+        .identity_constraints()
+        .iter()
+        .map(|constraint| match constraint {
+            IdentityConstraint::Unique(_) => "unique",
+            IdentityConstraint::Key(_) => "key",
+            IdentityConstraint::Keyref(_) => "keyref",
+        })
+        .collect();
+    assert_eq!(kinds, ["key", "unique", "keyref"]);
+    assert_eq!(catalog.effective_min_occurs(), 1);
+    assert_eq!(catalog.effective_max_occurs(), MaxOccurs::Bounded(1));
+
+    let sequence = catalog.complex_type().unwrap().sequence().unwrap();
+    let elements: Vec<_> = sequence
+        .items()
+        .into_iter()
+        .map(|item| match item {
+            Particle::Element(e) => e,
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(elements[0].effective_min_occurs(), 0);
+    assert!(matches!(
+        elements[0].effective_max_occurs(),
+        MaxOccurs::Unbounded(_)
+    ));
+    assert_eq!(elements[1].effective_max_occurs(), MaxOccurs::Bounded(3));
+    assert!(elements[1].simple_type().is_some());
+}
+
+#[test]
+fn element_type_alternatives_are_read_in_order() {
+    let xsd = r###"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:t="urn:t"
+            targetNamespace="urn:t">
+        <xs:element name="message" type="t:Message">
+            <xs:alternative test="@kind = 'text'" type="t:TextMessage"/>
+            <xs:alternative test="@kind = 'binary'" xpathDefaultNamespace="##targetNamespace">
+                <xs:annotation><xs:documentation>Inline</xs:documentation></xs:annotation>
+                <xs:simpleType><xs:restriction base="xs:base64Binary"/></xs:simpleType>
+            </xs:alternative>
+            <xs:alternative type="xs:error"/>
+        </xs:element>
+    </xs:schema>"###;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    let alternatives = schema.elements()[0].alternatives();
+    assert_eq!(alternatives.len(), 3);
+    assert_eq!(alternatives[0].test.as_deref(), Some("@kind = 'text'"));
+    assert_eq!(
+        alternatives[0]
+            .resolve_qname(alternatives[0].r#type.as_ref().unwrap())
+            .unwrap()
+            .to_string(),
+        "{urn:t}TextMessage"
+    );
+    assert!(alternatives[1].r#type.is_none());
+    assert!(alternatives[1].annotation().is_some());
+    assert!(alternatives[1].simple_type().is_some());
+    assert_eq!(
+        alternatives[1].xpath_default_namespace.as_ref().unwrap(),
+        "##targetNamespace"
+    );
+    assert_eq!(alternatives[2].test, None);
+}
+
+#[test]
+fn overrides_keep_their_replacement_components() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:override schemaLocation="base.xsd">
+            <xs:annotation/>
+            <xs:simpleType name="code">
+                <xs:restriction base="xs:token"/>
+            </xs:simpleType>
+            <xs:element name="root" type="xs:string"/>
+            <xs:attribute name="lang" type="xs:language"/>
+            <xs:notation name="png" public="image/png"/>
+        </xs:override>
+    </xs:schema>"#;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    let overrides = schema.overrides();
+    assert_eq!(overrides.len(), 1);
+    let r#override = overrides[0];
+    assert_eq!(r#override.schema_location, "base.xsd");
+    assert_eq!(r#override.annotations().len(), 1);
+    assert_eq!(r#override.simple_types()[0].name.as_ref().unwrap(), "code");
+    assert_eq!(r#override.elements()[0].name.as_ref().unwrap(), "root");
+    assert_eq!(r#override.attributes().len(), 1);
+    assert_eq!(r#override.notations().len(), 1);
+    assert!(r#override.complex_types().is_empty());
+}
+
+#[test]
+fn content_derivations_expose_their_content_model() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:complexType name="extended">
+            <xs:complexContent>
+                <xs:extension base="base">
+                    <xs:sequence><xs:element name="extra"/></xs:sequence>
+                    <xs:attribute name="a"/>
+                    <xs:attributeGroup ref="common"/>
+                    <xs:anyAttribute/>
+                </xs:extension>
+            </xs:complexContent>
+        </xs:complexType>
+        <xs:complexType name="restricted">
+            <xs:complexContent>
+                <xs:restriction base="base">
+                    <xs:choice><xs:element name="one"/><xs:element name="other"/></xs:choice>
+                    <xs:attribute name="a" use="prohibited"/>
+                </xs:restriction>
+            </xs:complexContent>
+        </xs:complexType>
+        <xs:complexType name="text">
+            <xs:simpleContent>
+                <xs:extension base="xs:string"/>
+            </xs:simpleContent>
+        </xs:complexType>
+    </xs:schema>"#;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    let types = schema.complex_types();
+
+    let Some(Derivation::Extension(extension)) = types[0].complex_content().unwrap().derivation()
+    else {
+        panic!("expected an extension");
+    };
+    assert_eq!(extension.base, "base");
+    assert!(matches!(extension.particle(), Some(Particle::Sequence(_))));
+    assert_eq!(extension.sequence().unwrap().items().len(), 1);
+    assert_eq!(extension.attributes().len(), 1);
+    assert_eq!(extension.attribute_groups().len(), 1);
+    assert!(extension.any_attribute().is_some());
+
+    let Some(Derivation::Restriction(restriction)) =
+        types[1].complex_content().unwrap().derivation()
+    else {
+        panic!("expected a restriction");
+    };
+    assert!(matches!(restriction.particle(), Some(Particle::Choice(_))));
+    assert_eq!(restriction.choice().unwrap().items().len(), 2);
+    assert_eq!(restriction.attributes().len(), 1);
+    assert!(restriction.any_attribute().is_none());
+
+    let derivation = types[2].simple_content().unwrap().derivation().unwrap();
+    assert!(matches!(derivation, Derivation::Extension(_)));
+    assert_eq!(derivation.base().unwrap(), "xs:string");
+}
+
+#[test]
+fn component_content_is_reachable() {
+    let xsd = r###"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+        <xs:redefine schemaLocation="base.xsd">
+            <xs:group name="g">
+                <xs:sequence><xs:group ref="g"/><xs:element name="extra"/></xs:sequence>
+            </xs:group>
+            <xs:attributeGroup name="ag">
+                <xs:attributeGroup ref="ag"/>
+                <xs:attribute name="a"/>
+            </xs:attributeGroup>
+        </xs:redefine>
+        <xs:group name="choices">
+            <xs:annotation/>
+            <xs:choice><xs:element name="x"/><xs:element name="y"/></xs:choice>
+        </xs:group>
+        <xs:attributeGroup name="common">
+            <xs:attribute name="id" type="xs:ID"/>
+            <xs:anyAttribute namespace="##other"/>
+        </xs:attributeGroup>
+        <xs:notation name="jpeg" public="image/jpeg" system="viewer.exe">
+            <xs:annotation/>
+        </xs:notation>
+        <xs:element name="root">
+            <xs:key name="k">
+                <xs:selector xpath="item"><xs:annotation/></xs:selector>
+                <xs:field xpath="@id"/>
+                <xs:field xpath="@version"/>
+            </xs:key>
+            <xs:keyref name="r" refer="k">
+                <xs:selector xpath="ref"/>
+                <xs:field xpath="@to"/>
+            </xs:keyref>
+        </xs:element>
+    </xs:schema>"###;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+
+    let redefine = schema.redefines()[0];
+    assert_eq!(redefine.groups().len(), 1);
+    assert_eq!(redefine.groups()[0].sequence().unwrap().items().len(), 2);
+    let attribute_group = redefine.attribute_groups()[0];
+    assert_eq!(
+        attribute_group.attribute_groups()[0]
+            .r#ref
+            .as_ref()
+            .unwrap(),
+        "ag"
+    );
+    assert_eq!(attribute_group.attributes().len(), 1);
+
+    let group = schema.groups()[0];
+    assert!(group.annotation().is_some());
+    assert!(matches!(group.particle(), Some(Particle::Choice(_))));
+    let common = schema.attribute_groups()[0];
+    assert_eq!(common.attributes().len(), 1);
+    assert!(common.any_attribute().is_some());
+
+    let notation = schema.notations()[0];
+    assert_eq!(notation.name, "jpeg");
+    assert_eq!(notation.public.as_ref().unwrap(), "image/jpeg");
+    assert_eq!(notation.system.as_ref().unwrap(), "viewer.exe");
+    assert!(notation.annotation().is_some());
+
+    let root = schema.elements()[0];
+    let key = root.keys()[0];
+    assert_eq!(key.name.as_ref().unwrap(), "k");
+    assert_eq!(key.selector().unwrap().xpath, "item");
+    assert!(key.selector().unwrap().annotation().is_some());
+    let fields: Vec<_> = key.fields().iter().map(|f| f.xpath.as_str()).collect();
+    assert_eq!(fields, ["@id", "@version"]);
+    let keyref = root.keyrefs()[0];
+    assert_eq!(keyref.refer.as_ref().unwrap(), "k");
+    assert_eq!(keyref.selector().unwrap().xpath, "ref");
+    assert_eq!(keyref.fields().len(), 1);
+}
+
+#[test]
+fn annotation_content_is_kept_as_xml() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+        xmlns:jaxb="https://jakarta.ee/xml/ns/jaxb" jaxb:version="3.0">
+      <xs:element name="order">
+        <xs:annotation>
+          <xs:appinfo source="urn:bindings"><jaxb:class name="PurchaseOrder"/></xs:appinfo>
+          <xs:documentation xml:lang="en">An <h:b xmlns:h="http://www.w3.org/1999/xhtml">order</h:b> &amp; its lines.</xs:documentation>
+        </xs:annotation>
+      </xs:element>
+    </xs:schema>"#;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    let annotation = schema.elements()[0].annotation().unwrap();
+
+    let app_info = annotation.app_infos()[0];
+    assert_eq!(app_info.text(), "");
+    let class = app_info.content().elements().next().unwrap();
+    assert_eq!(
+        class.name().to_string(),
+        "{https://jakarta.ee/xml/ns/jaxb}class"
+    );
+    assert_eq!(class.attribute("name"), Some("PurchaseOrder"));
+    assert_eq!(
+        app_info.to_xml(),
+        r#"<jaxb:class xmlns:jaxb="https://jakarta.ee/xml/ns/jaxb" name="PurchaseOrder"/>"#
+    );
+
+    let documentation = annotation.documentations()[0];
+    assert_eq!(documentation.text(), "An order & its lines.");
+    assert_eq!(
+        documentation.to_xml(),
+        r#"An <h:b xmlns:h="http://www.w3.org/1999/xhtml">order</h:b> &amp; its lines."#
+    );
+}
+
+#[test]
+fn schema_sets_follow_references_once() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "schemas/main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:include schemaLocation="common/types.xsd"/>
+          <xs:import namespace="urn:b" schemaLocation="./other.xsd"/>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "schemas/common/types.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+          <xs:include schemaLocation="../main.xsd"/>
+          <xs:simpleType name="code"><xs:restriction base="xs:string"/></xs:simpleType>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "schemas/other.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:b">
+          <xs:import namespace="urn:a" schemaLocation="main.xsd"/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("schemas/main.xsd", &resolver).unwrap();
+
+    let uris: Vec<_> = set.documents().iter().map(|d| d.uri()).collect();
+    assert_eq!(
+        uris,
+        [
+            "schemas/main.xsd",
+            "schemas/common/types.xsd",
+            "schemas/other.xsd"
+        ]
+    );
+    let references = set.root().references();
+    assert_eq!(references[0].kind, ReferenceKind::Include);
+    assert_eq!(references[1].kind, ReferenceKind::Import);
+    assert_eq!(set.documents()[1].references()[0].document, 0);
+    assert_eq!(set.documents()[2].references()[0].document, 0);
+    let types = set.document("schemas/common/types.xsd").unwrap().schema();
+    assert_eq!(types.effective_target_namespace(), Some("urn:a"));
+    assert_eq!(
+        types.simple_types()[0].span().file.as_deref(),
+        Some("schemas/common/types.xsd")
+    );
+
+    resolver.insert(
+        "broken.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+          <xs:redefine schemaLocation="missing.xsd"/>
+        </xs:schema>"#,
+    );
+    let error = SchemaSet::load("broken.xsd", &resolver).unwrap_err();
+    assert!(matches!(error, SchemaError::Unresolved { .. }));
+    assert_eq!(
+        error.to_string(),
+        "cannot load `missing.xsd` referenced from broken.xsd: no schema document at `missing.xsd`"
+    );
+
+    let set = SchemaSet::load("tests/data/ws-addr.xsd", &FileResolver).unwrap();
+    assert_eq!(set.documents().len(), 1);
+    assert!(set.root().uri().ends_with("ws-addr.xsd"));
+}
+
+#[test]
+fn imports_without_location_are_skipped_unless_resolved() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:import namespace="urn:b"/>
+          <xs:import/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let import = set.root().schema().imports()[0];
+    assert_eq!(import.namespace.as_deref(), Some("urn:b"));
+    assert_eq!(import.schema_location, None);
+    assert_eq!(set.documents().len(), 1);
+    assert!(set.root().references().is_empty());
+}
+
+#[test]
+fn catalogs_map_locations_to_local_copies() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "catalog/catalog.xml",
+        r#"<catalog xmlns="urn:oasis:names:tc:entity:xmlns:xml:catalog" prefer="public">
+          <rewriteURI uriStartString="http://www.w3.org/2001/" rewritePrefix="w3c/"/>
+          <group xml:base="../vendor/">
+            <uri name="urn:b" uri="b.xsd"/>
+            <uriSuffix uriSuffix="/unused.xsd" uri="unused.xsd"/>
+          </group>
+          <nextCatalog catalog="more.xml"/>
+          <nextCatalog catalog="catalog.xml"/>
+        </catalog>"#,
+    );
+    resolver.insert(
+        "catalog/more.xml",
+        r#"<catalog xmlns="urn:oasis:names:tc:entity:xmlns:xml:catalog">
+          <system systemId="http://example.com/c.xsd" uri="c.xsd"/>
+        </catalog>"#,
+    );
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:include schemaLocation="http://example.com/c.xsd"/>
+          <xs:import namespace="http://www.w3.org/XML/1998/namespace"
+                     schemaLocation="http://www.w3.org/2001/xml.xsd"/>
+          <xs:import namespace="urn:b" schemaLocation="http://example.com/b.xsd"/>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "local.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:import namespace="urn:b"/>
+          <xs:import namespace="urn:unknown"/>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "catalog/w3c/xml.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+            targetNamespace="http://www.w3.org/XML/1998/namespace"/>"#,
+    );
+    resolver.insert(
+        "vendor/b.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:b"/>"#,
+    );
+    resolver.insert(
+        "catalog/c.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"/>"#,
+    );
+
+    let catalog = Catalog::load("catalog/catalog.xml", &resolver).unwrap();
+    assert_eq!(
+        catalog
+            .resolve_uri("http://www.w3.org/2001/xml.xsd")
+            .as_deref(),
+        Some("catalog/w3c/xml.xsd")
+    );
+    assert_eq!(
+        catalog.resolve_uri("urn:b").as_deref(),
+        Some("vendor/b.xsd")
+    );
+    assert_eq!(
+        catalog
+            .resolve_system("http://example.com/c.xsd")
+            .as_deref(),
+        Some("catalog/c.xsd")
+    );
+    assert_eq!(catalog.resolve_uri("http://example.com/d.xsd"), None);
+
+    let resolver = CatalogResolver::new(catalog, resolver);
+    let set = SchemaSet::load("local.xsd", &resolver).unwrap();
+    let uris: Vec<_> = set.documents().iter().map(|d| d.uri()).collect();
+    assert_eq!(uris, ["local.xsd", "vendor/b.xsd"]);
+    assert_eq!(set.root().references()[0].location, None);
+
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let uris: Vec<_> = set.documents().iter().map(|d| d.uri()).collect();
+    assert_eq!(
+        uris,
+        [
+            "main.xsd",
+            "catalog/c.xsd",
+            "catalog/w3c/xml.xsd",
+            "vendor/b.xsd"
+        ]
+    );
+}
+
+#[test]
+fn schema_sets_look_up_components_by_expanded_name() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "order.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+            xmlns:c="urn:common" targetNamespace="urn:order">
+          <xs:include schemaLocation="types.xsd"/>
+          <xs:import namespace="urn:common" schemaLocation="common.xsd"/>
+          <xs:element name="order">
+            <xs:complexType>
+              <xs:sequence>
+                <xs:element name="lines">
+                  <xs:complexType>
+                    <xs:sequence>
+                      <xs:element name="line" type="c:line" maxOccurs="unbounded"/>
+                    </xs:sequence>
+                  </xs:complexType>
+                  <xs:unique name="lineNumber">
+                    <xs:selector xpath="line"/>
+                    <xs:field xpath="@number"/>
+                  </xs:unique>
+                </xs:element>
+              </xs:sequence>
+            </xs:complexType>
+          </xs:element>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "types.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+          <xs:simpleType name="code"><xs:restriction base="xs:token"/></xs:simpleType>
+          <xs:element name="code" type="code"/>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "common.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:common">
+          <xs:complexType name="line"/>
+          <xs:group name="lines"><xs:sequence/></xs:group>
+          <xs:attributeGroup name="audit"/>
+          <xs:attribute name="version"/>
+          <xs:notation name="png" public="image/png"/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("order.xsd", &resolver).unwrap();
+
+    let name = |namespace: &str, local: &str| ExpandedName::new(Some(namespace), local);
+    assert_eq!(
+        set.target_namespaces().into_iter().collect::<Vec<_>>(),
+        [Some("urn:common"), Some("urn:order")]
+    );
+    assert_eq!(set.documents_in(Some("urn:order")).count(), 2);
+    assert!(matches!(
+        set.type_definition(&name("urn:order", "code")),
+        Some(TypeDefinition::Simple(_))
+    ));
+    assert!(matches!(
+        set.type_definition(&name("urn:common", "line")),
+        Some(TypeDefinition::Complex(_))
+    ));
+    assert!(set.type_definition(&name("urn:order", "line")).is_none());
+    let elements: Vec<_> = set.elements().map(|(name, _)| name.to_string()).collect();
+    assert_eq!(elements, ["{urn:order}code", "{urn:order}order"]);
+    assert!(set.group(&name("urn:common", "lines")).is_some());
+    assert!(set.attribute_group(&name("urn:common", "audit")).is_some());
+    assert!(set.attribute(&name("urn:common", "version")).is_some());
+    assert!(set.notation(&name("urn:common", "png")).is_some());
+    let constraint = set
+        .identity_constraint(&name("urn:order", "lineNumber"))
+        .unwrap();
+    assert!(matches!(constraint, IdentityConstraint::Unique(_)));
+    assert_eq!(constraint.span().file.as_deref(), Some("order.xsd"));
+
+    resolver.insert(
+        "types.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+          <xs:element name="order"/>
+        </xs:schema>"#,
+    );
+    let error = SchemaSet::load("order.xsd", &resolver).unwrap_err();
+    assert!(matches!(error, SchemaError::DuplicateDefinition { .. }));
+    assert_eq!(
+        error.to_string(),
+        "duplicate element declaration `{urn:order}order` at types.xsd:2:11, \
+         first defined at order.xsd:5:11"
+    );
+}
+
+#[test]
+fn redefinitions_replace_the_redefined_components() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema"
+            xmlns="urn:a" targetNamespace="urn:a">
+          <xs:redefine schemaLocation="base.xsd">
+            <xs:complexType name="address">
+              <xs:complexContent>
+                <xs:extension base="address">
+                  <xs:sequence><xs:element name="country" type="xs:string"/></xs:sequence>
+                </xs:extension>
+              </xs:complexContent>
+            </xs:complexType>
+            <xs:group name="contact">
+              <xs:sequence>
+                <xs:group ref="contact"/>
+                <xs:element name="email" type="xs:string"/>
+              </xs:sequence>
+            </xs:group>
+          </xs:redefine>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "base.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+          <xs:complexType name="address">
+            <xs:sequence><xs:element name="street" type="xs:string"/></xs:sequence>
+          </xs:complexType>
+          <xs:group name="contact">
+            <xs:sequence><xs:element name="phone" type="xs:string"/></xs:sequence>
+          </xs:group>
+          <xs:simpleType name="zip"><xs:restriction base="xs:string"/></xs:simpleType>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let name = |local: &str| ExpandedName::new(Some("urn:a"), local);
+
+    let Some(TypeDefinition::Complex(address)) = set.type_definition(&name("address")) else {
+        panic!("address is not a complex type");
+    };
+    assert_eq!(address.span().file.as_deref(), Some("main.xsd"));
+    let base = address
+        .complex_content()
+        .and_then(|content| content.derivation())
+        .unwrap()
+        .base()
+        .unwrap();
+    let original = set
+        .redefined_type_definition(&address.resolve_qname(base).unwrap())
+        .unwrap();
+    assert_eq!(original.span().file.as_deref(), Some("base.xsd"));
+
+    let contact = set.group(&name("contact")).unwrap();
+    assert_eq!(contact.span().file.as_deref(), Some("main.xsd"));
+    let original = set.redefined_group(&name("contact")).unwrap();
+    assert_eq!(original.span().file.as_deref(), Some("base.xsd"));
+    assert!(set.redefined_type_definition(&name("zip")).is_none());
+    assert!(set.type_definition(&name("zip")).is_some());
+    assert_eq!(set.groups().count(), 1);
+}
+
+#[test]
+fn overrides_replace_components_transitively() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:override schemaLocation="a.xsd">
+            <xs:complexType name="T"><xs:sequence/></xs:complexType>
+            <xs:simpleType name="S"><xs:restriction base="xs:int"/></xs:simpleType>
+            <xs:element name="unused"/>
+          </xs:override>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "a.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:include schemaLocation="b.xsd"/>
+          <xs:complexType name="T"/>
+          <xs:element name="root" type="T"/>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "b.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:simpleType name="S"><xs:restriction base="xs:string"/></xs:simpleType>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let name = |local: &str| ExpandedName::new(Some("urn:a"), local);
+    let file = |definition: TypeDefinition| definition.span().file.as_deref().map(str::to_owned);
+
+    assert_eq!(
+        file(set.type_definition(&name("T")).unwrap()).unwrap(),
+        "main.xsd"
+    );
+    assert_eq!(
+        file(set.type_definition(&name("S")).unwrap()).unwrap(),
+        "main.xsd"
+    );
+    assert!(set.element(&name("root")).is_some());
+    assert!(set.element(&name("unused")).is_none());
+    assert!(set.document("b.xsd").unwrap().is_overridden());
+
+    // b.xsd is included both as is and transformed by the override.
+    resolver.insert(
+        "both.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:override schemaLocation="a.xsd">
+            <xs:simpleType name="S"><xs:restriction base="xs:int"/></xs:simpleType>
+          </xs:override>
+          <xs:include schemaLocation="b.xsd"/>
+        </xs:schema>"#,
+    );
+    let error = SchemaSet::load("both.xsd", &resolver).unwrap_err();
+    assert!(matches!(error, SchemaError::DuplicateDefinition { .. }));
+
+    // An override that does not change b.xsd leaves it the same document.
+    resolver.insert(
+        "same.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:override schemaLocation="b.xsd">
+            <xs:element name="unused"/>
+          </xs:override>
+          <xs:include schemaLocation="b.xsd"/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("same.xsd", &resolver).unwrap();
+    assert_eq!(set.documents().len(), 2);
+}
+
+#[test]
+fn component_models_resolve_references() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a" targetNamespace="urn:a">
+          <xs:include schemaLocation="common.xsd"/>
+          <xs:complexType name="Address">
+            <xs:sequence>
+              <xs:element name="street" type="xs:string"/>
+              <xs:group ref="tns:extra"/>
+            </xs:sequence>
+            <xs:attributeGroup ref="tns:common"/>
+          </xs:complexType>
+          <xs:complexType name="USAddress">
+            <xs:complexContent>
+              <xs:extension base="tns:Address">
+                <xs:sequence><xs:element name="zip" type="tns:code"/></xs:sequence>
+              </xs:extension>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:group name="extra">
+            <xs:sequence><xs:element ref="tns:note" minOccurs="0"/></xs:sequence>
+          </xs:group>
+          <xs:element name="note" type="xs:string"/>
+          <xs:element name="address" type="tns:Address">
+            <xs:key name="k"><xs:selector xpath="."/><xs:field xpath="street"/></xs:key>
+            <xs:keyref name="r" refer="tns:k"><xs:selector xpath="."/><xs:field xpath="street"/></xs:keyref>
+          </xs:element>
+          <xs:element name="usAddress" substitutionGroup="tns:address"/>
+          <xs:element name="copy" type="tns:Address">
+            <xs:keyref ref="tns:r"/>
+          </xs:element>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "common.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+          <xs:simpleType name="code"><xs:restriction base="xs:token"/></xs:simpleType>
+          <xs:attributeGroup name="common">
+            <xs:attribute name="lang" type="code"/>
+          </xs:attributeGroup>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let model = set.model().unwrap();
+    let name = |local: &str| ExpandedName::new(Some("urn:a"), local);
+
+    let address = model.type_definition(&name("Address")).unwrap();
+    let element = model.element(&name("address")).unwrap();
+    assert_eq!(element.type_definition(), address);
+    assert_eq!(
+        element
+            .type_definition()
+            .complex_type()
+            .unwrap()
+            .name
+            .as_ref()
+            .unwrap(),
+        "Address"
+    );
+    assert_eq!(
+        model.element(&name("usAddress")).unwrap().type_definition(),
+        address
+    );
+    assert_eq!(address.base_type(), Some(model.any_type()));
+    assert!(model.any_type().is_builtin());
+
+    let us_address = model.type_definition(&name("USAddress")).unwrap();
+    assert_eq!(us_address.base_type(), Some(address));
+    let Term::ModelGroup {
+        compositor,
+        particles,
+    } = &us_address.particle().unwrap().term
+    else {
+        panic!("expected a model group");
+    };
+    assert_eq!(*compositor, Compositor::Sequence);
+    let Term::Element(zip) = particles[0].term else {
+        panic!("expected an element");
+    };
+    let zip = model.element_by_id(zip);
+    assert!(!zip.is_global());
+    assert_eq!(zip.name(), &ExpandedName::new(None::<String>, "zip"));
+    let code = zip.type_definition();
+    assert_eq!(code.name(), Some(&name("code")));
+    assert_eq!(
+        code.base_type().unwrap().name(),
+        Some(&ExpandedName::new(Some(XSD_NAMESPACE), "token"))
+    );
+
+    // The chameleon attribute group refers to `code` in its new namespace.
+    let common = address.attribute_groups()[0];
+    assert_eq!(common.name(), &name("common"));
+    let lang = common.attribute_uses()[0].attribute();
+    assert_eq!(lang.type_definition(), code);
+
+    let extra = model.group(&name("extra")).unwrap();
+    let Term::ModelGroup { particles, .. } = &extra.particle().unwrap().term else {
+        panic!("expected a model group");
+    };
+    assert_eq!(particles[0].min_occurs, 0);
+    let Term::Element(note) = particles[0].term else {
+        panic!("expected an element");
+    };
+    assert_eq!(
+        Some(model.element_by_id(note)),
+        model.element(&name("note"))
+    );
+
+    let constraints = element.identity_constraints();
+    assert_eq!(constraints[1].referenced_key(), Some(constraints[0]));
+    assert_eq!(model.identity_constraint(&name("r")), Some(constraints[1]));
+    let copy = model.element(&name("copy")).unwrap().identity_constraints()[0];
+    assert_eq!(copy.reference(), Some(constraints[1]));
+    assert_eq!(copy.referenced_key(), None);
+
+    resolver.insert(
+        "broken.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a" targetNamespace="urn:a">
+          <xs:element name="address" type="tns:Missing"/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("broken.xsd", &resolver).unwrap();
+    let error = set.model().unwrap_err();
+    assert!(matches!(
+        error,
+        SchemaError::UnresolvedReference { ref name, .. } if name == "{urn:a}Missing"
+    ));
+}
+
+#[test]
+fn builtin_types_resolve_like_defined_types() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+          <xs:element name="count" type="xs:int"/>
+          <xs:attribute name="tokens" type="xs:NMTOKENS"/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let model = set.model().unwrap();
+    let xs = |local: &str| ExpandedName::new(Some(XSD_NAMESPACE), local);
+
+    let int = model
+        .element(&ExpandedName::new(None::<String>, "count"))
+        .unwrap()
+        .type_definition();
+    assert_eq!(int.name(), Some(&xs("int")));
+    let chain: Vec<_> = std::iter::successors(Some(int), |t| t.base_type())
+        .map(|t| t.name().unwrap().local_name().to_string())
+        .collect();
+    assert_eq!(
+        chain,
+        [
+            "int",
+            "long",
+            "integer",
+            "decimal",
+            "anyAtomicType",
+            "anySimpleType",
+            "anyType"
+        ]
+    );
+    let builtin = int.builtin().unwrap();
+    assert!(builtin.numeric && builtin.bounded);
+    assert_eq!(builtin.ordered, Ordered::Total);
+    assert_eq!(builtin.cardinality, Cardinality::Finite);
+    assert_eq!(
+        builtin.facet(FacetKind::MaxInclusive).unwrap().value,
+        "2147483647"
+    );
+    assert!(builtin.facet(FacetKind::FractionDigits).unwrap().fixed);
+    assert_eq!(builtin.primitive_type().unwrap().name, "decimal");
+
+    let tokens = model
+        .attribute(&ExpandedName::new(None::<String>, "tokens"))
+        .unwrap()
+        .type_definition();
+    assert_eq!(tokens.builtin().unwrap().variety, Some(Variety::List));
+    assert_eq!(tokens.item_type().unwrap().name(), Some(&xs("NMTOKEN")));
+
+    // Built-in types are part of the model whether they are used or not.
+    let stamp = model.type_definition(&xs("dateTimeStamp")).unwrap();
+    assert!(stamp.builtin().unwrap().xsd11);
+    assert_eq!(stamp.base_type().unwrap().name(), Some(&xs("dateTime")));
+    assert!(model.any_type().builtin().unwrap().is_complex());
+
+    resolver.insert(
+        "broken.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema">
+          <xs:element name="count" type="xs:integr"/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("broken.xsd", &resolver).unwrap();
+    assert!(matches!(
+        set.model().unwrap_err(),
+        SchemaError::UnresolvedReference { .. }
+    ));
+}
+
+#[test]
+fn type_derivations_honour_final_and_block() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a" targetNamespace="urn:a">
+          <xs:complexType name="Address" block="restriction">
+            <xs:sequence><xs:element name="street" type="xs:string"/></xs:sequence>
+          </xs:complexType>
+          <xs:complexType name="USAddress" final="extension">
+            <xs:complexContent>
+              <xs:extension base="tns:Address">
+                <xs:sequence><xs:element name="zip" type="tns:code" minOccurs="0"/></xs:sequence>
+              </xs:extension>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:complexType name="LocalAddress">
+            <xs:complexContent>
+              <xs:restriction base="tns:USAddress">
+                <xs:sequence><xs:element name="street" type="xs:string"/></xs:sequence>
+              </xs:restriction>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:complexType name="TaggedAddress">
+            <xs:complexContent>
+              <xs:extension base="tns:USAddress"/>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:simpleType name="code" final="list union">
+            <xs:restriction base="xs:token"/>
+          </xs:simpleType>
+          <xs:simpleType name="codes">
+            <xs:list itemType="tns:code"/>
+          </xs:simpleType>
+          <xs:simpleType name="codeOrInt">
+            <xs:union memberTypes="tns:code xs:int"/>
+          </xs:simpleType>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let model = set.model().unwrap();
+    let ty = |local: &str| {
+        let name = ExpandedName::new(Some("urn:a"), local);
+        model.type_definition(&name).unwrap()
+    };
+    let xs = |local: &str| {
+        let name = ExpandedName::new(Some(XSD_NAMESPACE), local);
+        model.type_definition(&name).unwrap()
+    };
+
+    let local = ty("LocalAddress");
+    let chain: Vec<_> = local
+        .derivation_chain()
+        .iter()
+        .map(|step| {
+            (
+                step.method,
+                step.base.name().unwrap().local_name().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        chain,
+        [
+            (DerivationMethod::Restriction, "USAddress".to_string()),
+            (DerivationMethod::Extension, "Address".to_string()),
+            (DerivationMethod::Restriction, "anyType".to_string()),
+        ]
+    );
+    assert!(ty("USAddress").is_derived_from(ty("Address")));
+    assert!(!ty("Address").is_derived_from(ty("USAddress")));
+    assert_eq!(
+        ty("Address").derived_types(),
+        [ty("LocalAddress"), ty("TaggedAddress"), ty("USAddress")]
+    );
+
+    // Simple types are derived by restriction, list or union.
+    assert_eq!(
+        ty("codes").derivation_method(),
+        Some(DerivationMethod::List)
+    );
+    assert_eq!(
+        ty("codeOrInt").derivation_method(),
+        Some(DerivationMethod::Union)
+    );
+    assert_eq!(ty("codes").base_type(), Some(xs("anySimpleType")));
+    assert!(ty("code").is_derived_from(xs("string")));
+    assert_eq!(ty("code").derivation_path(xs("token")).unwrap().len(), 1);
+
+    // USAddress is final for extension, code for list and union.
+    assert_eq!(
+        ty("USAddress").final_methods(),
+        [DerivationMethod::Extension]
+    );
+    assert_eq!(
+        ty("code").final_methods(),
+        [DerivationMethod::List, DerivationMethod::Union]
+    );
+    match ty("TaggedAddress").check_derivation(ty("Address"), &[]) {
+        Err(DerivationConflict::Final(step)) => assert_eq!(step.base, ty("USAddress")),
+        other => panic!("unexpected {other:?}"),
+    }
+    // Address blocks restrictions, which LocalAddress uses.
+    assert!(matches!(
+        local.check_derivation(ty("Address"), &[]),
+        Err(DerivationConflict::Blocked(_))
+    ));
+    assert_eq!(
+        local.check_derivation(ty("USAddress"), &[]).unwrap().len(),
+        1
+    );
+    assert!(matches!(
+        ty("USAddress").check_derivation(ty("Address"), &[DerivationMethod::Extension]),
+        Err(DerivationConflict::Blocked(_))
+    ));
+    assert_eq!(
+        ty("Address").check_derivation(ty("USAddress"), &[]),
+        Err(DerivationConflict::NotDerived)
+    );
+}
+
+#[test]
+fn effective_content_merges_base_and_derived_types() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r###"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a"
+              targetNamespace="urn:a" defaultAttributes="tns:defaults">
+          <xs:attributeGroup name="defaults">
+            <xs:attribute name="lang" type="xs:language"/>
+            <xs:anyAttribute namespace="##other"/>
+          </xs:attributeGroup>
+          <xs:attributeGroup name="ids">
+            <xs:attribute name="id" type="xs:ID"/>
+            <xs:anyAttribute namespace="urn:b urn:c"/>
+          </xs:attributeGroup>
+          <xs:group name="names">
+            <xs:sequence>
+              <xs:element name="first" type="xs:string"/>
+              <xs:element name="last" type="xs:string"/>
+            </xs:sequence>
+          </xs:group>
+          <xs:complexType name="Person" defaultAttributesApply="false">
+            <xs:group ref="tns:names" maxOccurs="2"/>
+            <xs:attribute name="age" type="xs:int"/>
+            <xs:attribute name="title" type="xs:string"/>
+            <xs:anyAttribute namespace="urn:b"/>
+          </xs:complexType>
+          <xs:complexType name="Employee">
+            <xs:complexContent mixed="true">
+              <xs:extension base="tns:Person">
+                <xs:sequence><xs:element name="salary" type="xs:decimal"/></xs:sequence>
+                <xs:attributeGroup ref="tns:ids"/>
+              </xs:extension>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:complexType name="Intern">
+            <xs:complexContent>
+              <xs:restriction base="tns:Employee">
+                <xs:sequence>
+                  <xs:group ref="tns:names"/>
+                  <xs:element name="salary" type="xs:decimal"/>
+                </xs:sequence>
+                <xs:attribute name="age" type="xs:int" use="required"/>
+                <xs:attribute name="title" use="prohibited"/>
+              </xs:restriction>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:complexType name="Price">
+            <xs:simpleContent>
+              <xs:extension base="xs:decimal">
+                <xs:attribute name="currency" type="xs:string"/>
+              </xs:extension>
+            </xs:simpleContent>
+          </xs:complexType>
+          <xs:complexType name="SmallPrice">
+            <xs:simpleContent>
+              <xs:restriction base="tns:Price">
+                <xs:maxInclusive value="100"/>
+              </xs:restriction>
+            </xs:simpleContent>
+          </xs:complexType>
+          <xs:complexType name="RoundedPrice">
+            <xs:simpleContent>
+              <xs:restriction base="tns:SmallPrice">
+                <xs:simpleType>
+                  <xs:restriction base="xs:decimal">
+                    <xs:fractionDigits value="2"/>
+                  </xs:restriction>
+                </xs:simpleType>
+                <xs:minInclusive value="0"/>
+              </xs:restriction>
+            </xs:simpleContent>
+          </xs:complexType>
+          <xs:complexType name="Open" defaultAttributesApply="false">
+            <xs:complexContent>
+              <xs:extension base="xs:anyType">
+                <xs:sequence><xs:element name="note" type="xs:string"/></xs:sequence>
+              </xs:extension>
+            </xs:complexContent>
+          </xs:complexType>
+        </xs:schema>"###,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let model = set.model().unwrap();
+    let ty = |local: &str| {
+        let name = ExpandedName::new(Some("urn:a"), local);
+        model.type_definition(&name).unwrap()
+    };
+    let names = |uses: &[AttributeUse]| -> Vec<String> {
+        uses.iter()
+            .map(|u| u.attribute().name().local_name().to_string())
+            .collect()
+    };
+    fn elements(particle: &ContentParticle) -> usize {
+        match &particle.term {
+            Term::Element(_) => 1,
+            Term::ModelGroup { particles, .. } => particles.iter().map(elements).sum(),
+            Term::Group(_) => panic!("group references are expanded"),
+            Term::Any(_) => 0,
+        }
+    }
+
+    let person = ty("Person").effective_content().unwrap();
+    assert!(!person.mixed);
+    assert_eq!(names(&person.attribute_uses), ["age", "title"]);
+    let particle = person.particle.unwrap();
+    assert_eq!(particle.max_occurs, MaxOccurs::Bounded(2));
+    assert_eq!(elements(&particle), 2);
+
+    // The extension appends its sequence, its attribute group and the
+    // default attributes, and unites its wildcard with that of Person.
+    let employee = ty("Employee").effective_content().unwrap();
+    assert!(employee.mixed);
+    let particle = employee.particle.unwrap();
+    let Term::ModelGroup {
+        compositor: Compositor::Sequence,
+        particles,
+    } = &particle.term
+    else {
+        panic!("expected a sequence");
+    };
+    assert_eq!(particles.len(), 2);
+    assert_eq!(particles[0].max_occurs, MaxOccurs::Bounded(2));
+    assert_eq!(elements(&particle), 3);
+    assert_eq!(
+        names(&employee.attribute_uses),
+        ["age", "title", "id", "lang"]
+    );
+    let wildcard = employee.attribute_wildcard.unwrap();
+    let expected = ["urn:b", "urn:c"].map(|uri| Some(uri.to_string()));
+    assert_eq!(
+        wildcard.namespaces,
+        NamespaceConstraint::Enumeration(expected.into_iter().collect())
+    );
+
+    // The restriction replaces the particle and redeclared attributes, and
+    // only keeps the wildcard of the default attributes.
+    let intern = ty("Intern").effective_content().unwrap();
+    assert!(!intern.mixed);
+    assert_eq!(elements(intern.particle.as_ref().unwrap()), 3);
+    assert_eq!(names(&intern.attribute_uses), ["id", "age", "lang"]);
+    assert!(intern.attribute_uses[1].is_required());
+    let wildcard = intern.attribute_wildcard.unwrap();
+    assert!(wildcard.allows(Some("urn:c")));
+    assert!(!wildcard.allows(Some("urn:a")) && !wildcard.allows(None));
+
+    let price = ty("Price").effective_content().unwrap();
+    assert!(price.particle.is_none());
+    assert_eq!(
+        price.simple_type.unwrap().name(),
+        Some(&ExpandedName::new(Some(XSD_NAMESPACE), "decimal"))
+    );
+    assert_eq!(names(&price.attribute_uses), ["currency", "lang"]);
+    assert!(price.attribute_wildcard.unwrap().allows(Some("urn:c")));
+    assert!(price.facets.is_empty());
+
+    // Restrictions of simple content add their facets, and an inline
+    // simple type takes the place of the inherited one.
+    let small = ty("SmallPrice").effective_content().unwrap();
+    assert_eq!(small.simple_type, price.simple_type);
+    let kinds = |facets: &[Facet]| facets.iter().map(Facet::kind).collect::<Vec<_>>();
+    assert_eq!(kinds(&small.facets), [FacetKind::MaxInclusive]);
+    let rounded = ty("RoundedPrice").effective_content().unwrap();
+    let simple_type = rounded.simple_type.unwrap();
+    assert!(simple_type.name().is_none());
+    assert_eq!(ty("RoundedPrice").simple_content_type(), Some(simple_type));
+    assert_eq!(kinds(&rounded.facets), [FacetKind::MinInclusive]);
+
+    // An extension of xs:anyType keeps its wildcards.
+    let open = ty("Open").effective_content().unwrap();
+    let particle = open.particle.unwrap();
+    let Term::ModelGroup { particles, .. } = &particle.term else {
+        panic!("expected a sequence");
+    };
+    assert_eq!(particles.len(), 2);
+    assert_eq!(elements(&particle), 1);
+    let wildcard = open.attribute_wildcard.unwrap();
+    assert!(wildcard.allows(Some("urn:z")) && wildcard.allows(None));
+    assert_eq!(wildcard.process_contents, ProcessContents::Lax);
+    assert!(model.any_type().effective_content().is_none());
+}
+
+#[test]
+fn substitution_groups_honour_abstract_and_block() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r##"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a"
+              targetNamespace="urn:a" blockDefault="restriction">
+          <xs:complexType name="Shape"/>
+          <xs:complexType name="Circle">
+            <xs:complexContent>
+              <xs:extension base="tns:Shape">
+                <xs:attribute name="radius" type="xs:double"/>
+              </xs:extension>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:complexType name="Dot">
+            <xs:complexContent>
+              <xs:restriction base="tns:Circle"/>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:element name="shape" type="tns:Shape" abstract="true"/>
+          <xs:element name="drawable" type="tns:Shape"/>
+          <xs:element name="circle" type="tns:Circle" substitutionGroup="tns:shape tns:drawable"/>
+          <xs:element name="ring" substitutionGroup="tns:circle"/>
+          <xs:element name="dot" type="tns:Dot" substitutionGroup="tns:circle"/>
+          <xs:element name="sealed" type="tns:Shape" block="#all"/>
+          <xs:element name="square" type="tns:Shape" substitutionGroup="tns:sealed"/>
+          <xs:element name="closed" type="tns:Shape" block="extension"/>
+          <xs:element name="oval" type="tns:Circle" substitutionGroup="tns:closed"/>
+        </xs:schema>"##,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let model = set.model().unwrap();
+    let element = |local: &str| {
+        let name = ExpandedName::new(Some("urn:a"), local);
+        model.element(&name).unwrap()
+    };
+    let names = |elements: Vec<ElementDeclaration>| -> Vec<String> {
+        elements
+            .iter()
+            .map(|e| e.name().local_name().to_string())
+            .collect()
+    };
+
+    // circle has two heads, and ring takes the type of circle.
+    assert_eq!(
+        names(element("circle").substitution_group_affiliations()),
+        ["shape", "drawable"]
+    );
+    assert_eq!(
+        names(element("shape").substitution_group_members()),
+        ["circle"]
+    );
+    assert_eq!(
+        names(element("drawable").substitution_group()),
+        ["drawable", "circle", "dot", "ring"]
+    );
+    // shape is abstract, and the blockDefault blocks dot, whose type is
+    // derived by restriction.
+    assert!(element("shape").is_abstract());
+    assert_eq!(
+        names(element("shape").substitutable_elements()),
+        ["circle", "ring"]
+    );
+    assert_eq!(
+        names(element("drawable").substitutable_elements()),
+        ["drawable", "circle", "ring"]
+    );
+    assert!(element("sealed").blocks_substitution());
+    assert_eq!(
+        names(element("sealed").substitutable_elements()),
+        ["sealed"]
+    );
+    assert_eq!(
+        element("closed").blocked_methods(),
+        [DerivationMethod::Extension]
+    );
+    assert_eq!(
+        names(element("closed").substitutable_elements()),
+        ["closed"]
+    );
+
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "list.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:b"
+              targetNamespace="urn:b" blockDefault="extension restriction">
+          <xs:complexType name="Shape"/>
+          <xs:element name="shape" type="tns:Shape"/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("list.xsd", &resolver).unwrap();
+    let model = set.model().unwrap();
+    let both = [DerivationMethod::Restriction, DerivationMethod::Extension];
+    let shape = model
+        .element(&ExpandedName::new(Some("urn:b"), "shape"))
+        .unwrap();
+    assert_eq!(shape.blocked_methods(), both);
+    assert_eq!(shape.type_definition().blocked_methods(), both);
+}
+
+#[test]
+fn schema_checks_report_constraint_violations() {
+    let xsd = r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:import namespace="urn:a" schemaLocation="a.xsd"/>
+          <xs:element name="both" type="xs:string" default="a" fixed="b"/>
+          <xs:element name="typed" type="xs:string">
+            <xs:simpleType>
+              <xs:restriction base="xs:string"/>
+            </xs:simpleType>
+          </xs:element>
+          <xs:complexType name="Content">
+            <xs:sequence minOccurs="2" maxOccurs="1">
+              <xs:element ref="both" nillable="true"/>
+            </xs:sequence>
+            <xs:attribute name="lang" type="xs:language" default="en" use="required"/>
+          </xs:complexType>
+          <xs:simpleType name="Empty">
+            <xs:union/>
+          </xs:simpleType>
+        </xs:schema>"#;
+    let schema = Schema::from_reader(xsd.as_bytes()).unwrap();
+    let rules: Vec<_> = schema.check().iter().map(|v| v.rule).collect();
+    assert_eq!(
+        rules,
+        [
+            "src-import.1.1",
+            "src-element.1",
+            "src-element.3",
+            "p-props-correct.2.1",
+            "src-element.2.2",
+            "src-attribute.2",
+            "src-simple-type.4",
+        ]
+    );
+
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r##"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a"
+              targetNamespace="urn:a">
+          <xs:complexType name="Base" final="extension">
+            <xs:attribute name="id" type="xs:ID" use="required"/>
+          </xs:complexType>
+          <xs:complexType name="Extended">
+            <xs:complexContent>
+              <xs:extension base="tns:Base"/>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:complexType name="Restricted">
+            <xs:complexContent>
+              <xs:restriction base="tns:Base">
+                <xs:attribute name="id" type="xs:ID" use="optional"/>
+                <xs:attribute name="extra" type="xs:string"/>
+              </xs:restriction>
+            </xs:complexContent>
+          </xs:complexType>
+          <xs:simpleType name="Sealed" final="#all">
+            <xs:restriction base="xs:string"/>
+          </xs:simpleType>
+          <xs:simpleType name="Sealeds">
+            <xs:list itemType="tns:Sealed"/>
+          </xs:simpleType>
+          <xs:simpleType name="Nested">
+            <xs:list itemType="tns:Sealeds"/>
+          </xs:simpleType>
+          <xs:simpleType name="Loop">
+            <xs:restriction base="tns:Loop"/>
+          </xs:simpleType>
+          <xs:simpleType name="SelfUnion">
+            <xs:union memberTypes="tns:OtherUnion xs:int"/>
+          </xs:simpleType>
+          <xs:simpleType name="OtherUnion">
+            <xs:union memberTypes="tns:SelfUnion"/>
+          </xs:simpleType>
+          <xs:simpleType name="SelfList">
+            <xs:list itemType="tns:SelfList"/>
+          </xs:simpleType>
+          <xs:element name="head" type="xs:string"/>
+          <xs:element name="member" type="xs:int" substitutionGroup="tns:head"/>
+          <xs:element name="sealed" type="tns:Base" final="extension restriction"/>
+          <xs:element name="extended" type="tns:Extended" substitutionGroup="tns:sealed"/>
+          <xs:group name="Cycle">
+            <xs:sequence>
+              <xs:group ref="tns:Cycle"/>
+            </xs:sequence>
+          </xs:group>
+          <xs:group name="Unordered">
+            <xs:all/>
+          </xs:group>
+          <xs:group name="Nesting">
+            <xs:choice>
+              <xs:group ref="tns:Unordered"/>
+            </xs:choice>
+          </xs:group>
+        </xs:schema>"##,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let mut rules: Vec<_> = set.check().iter().map(|v| v.rule).collect();
+    rules.sort();
+    assert_eq!(
+        rules,
+        [
+            "cos-all-limited.1.2",
+            "cos-ct-extends.1.1",
+            "cos-no-circular-unions",
+            "cos-no-circular-unions",
+            "cos-st-restricts.2.1",
+            "cos-st-restricts.2.2.1",
+            "derivation-ok-restriction.2.1.1",
+            "derivation-ok-restriction.2.2",
+            "e-props-correct.4",
+            "e-props-correct.4",
+            "mg-props-correct.2",
+            "st-props-correct.2",
+            "st-props-correct.2",
+        ]
+    );
+
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a"
+              targetNamespace="urn:a">
+          <xs:element name="root" type="tns:Missing"/>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let violations = set.check();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].rule, "src-resolve");
+
+    resolver.insert(
+        "redefining.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a"
+              targetNamespace="urn:a">
+          <xs:redefine schemaLocation="redefined.xsd">
+            <xs:simpleType name="Code">
+              <xs:restriction base="tns:Code"/>
+            </xs:simpleType>
+            <xs:simpleType name="Unknown">
+              <xs:restriction base="tns:Unknown"/>
+            </xs:simpleType>
+          </xs:redefine>
+        </xs:schema>"#,
+    );
+    resolver.insert(
+        "redefined.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" targetNamespace="urn:a">
+          <xs:simpleType name="Code">
+            <xs:restriction base="xs:string"/>
+          </xs:simpleType>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("redefining.xsd", &resolver).unwrap();
+    let violations = set.check();
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].rule, "src-redefine.2");
+    assert!(violations[0].message.contains("`Unknown`"));
+}
+
+#[test]
+fn facet_checks_report_inapplicable_and_inconsistent_facets() {
+    let mut resolver = MemoryResolver::new();
+    resolver.insert(
+        "main.xsd",
+        r#"<xs:schema xmlns:xs="http://www.w3.org/2001/XMLSchema" xmlns:tns="urn:a"
+              targetNamespace="urn:a">
+          <xs:simpleType name="Code">
+            <xs:restriction base="xs:string">
+              <xs:totalDigits value="3"/>
+              <xs:minLength value="5"/>
+              <xs:maxLength value="4" fixed="true"/>
+              <xs:maxLength value="6"/>
+              <xs:whiteSpace value="collapse"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="LongCode">
+            <xs:restriction base="tns:Code">
+              <xs:maxLength value="8"/>
+              <xs:whiteSpace value="preserve"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="Amount">
+            <xs:restriction base="xs:decimal">
+              <xs:minInclusive value="10.5"/>
+              <xs:minExclusive value="0"/>
+              <xs:maxInclusive value="010.50"/>
+              <xs:totalDigits value="4"/>
+              <xs:fractionDigits value="5"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="Small">
+            <xs:restriction base="xs:byte">
+              <xs:maxExclusive value="200"/>
+              <xs:fractionDigits value="1"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="Empty">
+            <xs:restriction base="xs:int">
+              <xs:minExclusive value="5"/>
+              <xs:maxExclusive value="-5"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="Positive">
+            <xs:restriction base="xs:decimal">
+              <xs:minInclusive value="1" fixed="true"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="SamePositive">
+            <xs:restriction base="tns:Positive">
+              <xs:minInclusive value="+1.0"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="OtherPositive">
+            <xs:restriction base="tns:Positive">
+              <xs:minInclusive value="2"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="Past">
+            <xs:restriction base="xs:date">
+              <xs:minInclusive value="2020-01-01"/>
+              <xs:maxInclusive value="2000-01-01"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="Morning">
+            <xs:restriction base="xs:dateTime">
+              <xs:minInclusive value="2020-01-01T08:00:00+02:00"/>
+              <xs:maxExclusive value="2020-01-01T06:00:00.5Z"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="Valid">
+            <xs:restriction base="xs:unsignedLong">
+              <xs:minExclusive value="0"/>
+              <xs:maxInclusive value="18446744073709551615"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="Text">
+            <xs:restriction base="xs:string">
+              <xs:minLength value=" 5 "/>
+              <xs:maxLength value="4000000000"/>
+            </xs:restriction>
+          </xs:simpleType>
+          <xs:simpleType name="LongerText">
+            <xs:restriction base="tns:Text">
+              <xs:maxLength value="4294967295"/>
+            </xs:restriction>
+          </xs:simpleType>
+        </xs:schema>"#,
+    );
+    let set = SchemaSet::load("main.xsd", &resolver).unwrap();
+    let model = set.model().unwrap();
+    let ty = model
+        .type_definition(&ExpandedName::new(Some("urn:a"), "Amount"))
+        .unwrap();
+    let restriction = match ty.simple_type().unwrap().content() {
+        Ok(SimpleTypeContent::Restriction(restriction)) => restriction,
+        _ => panic!("expected a restriction"),
+    };
+    let kinds: Vec<_> = restriction.facets().iter().map(Facet::kind).collect();
+    assert_eq!(
+        kinds[..2],
+        [FacetKind::MinInclusive, FacetKind::MinExclusive]
+    );
+
+    let mut rules: Vec<_> = set.check().iter().map(|v| v.rule).collect();
+    rules.sort();
+    assert_eq!(
+        rules,
+        [
+            "cos-applicable-facets",
+            "fractionDigits-totalDigits",
+            "fractionDigits-valid-restriction",
+            "maxExclusive-valid-restriction",
+            "maxLength-valid-restriction",
+            "maxLength-valid-restriction",
+            "minExclusive-less-than-equal-to-maxExclusive",
+            "minInclusive-less-than-equal-to-maxInclusive",
+            "minInclusive-minExclusive",
+            "minInclusive-valid-restriction",
+            "minLength-less-than-equal-to-maxLength",
+            "src-single-facet-value",
+            "whiteSpace-valid-restriction",
+        ]
+    );
+}